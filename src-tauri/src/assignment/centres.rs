@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::models::{assignation::IndividualExam, examinee::Examinee, AppValues, Entity, EntityId};
+
+/// How the examinees of a classroom are spread among their academic centres
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ClassroomCentres {
+    pub subject: EntityId,
+    pub classroom: EntityId,
+    pub examinees: usize,
+    /// From the academic centre with the most examinees in the classroom to the one with the least
+    pub centres: Vec<CentreShare>,
+    /// Some academic centre has more examinees in the classroom than the maximum share
+    pub exceeds: bool,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct CentreShare {
+    /// None for the examinees without an academic centre
+    pub academic_centre: Option<EntityId>,
+    pub examinees: usize,
+    /// Percentage of the examinees of the classroom
+    pub share: u32,
+}
+
+/// Reports the academic centres of the examinees in every classroom of the assignment, checking
+/// that none has more than `max_share` percent of the examinees of a classroom. Empty if there is
+/// no assignment
+pub fn centre_spread(values: &AppValues, max_share: u32) -> Vec<ClassroomCentres> {
+    let Some(assignation) = &values.assignation else {
+        return Vec::new();
+    };
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+
+    let mut spread = Vec::new();
+    for configuration in &assignation.0 {
+        for exam in configuration.exams() {
+            spread.extend(exam_spread(exam, &examinees, max_share));
+        }
+    }
+    spread
+}
+
+fn exam_spread(
+    exam: &IndividualExam,
+    examinees: &HashMap<&EntityId, &Examinee>,
+    max_share: u32,
+) -> Vec<ClassroomCentres> {
+    let Some(distribution) = &exam.distribution else {
+        return Vec::new();
+    };
+    let mut spread = Vec::new();
+    for used in &distribution.distribution {
+        let mut counts = HashMap::<Option<&EntityId>, usize>::new();
+        for examinee in used.examinees.iter().filter_map(|id| examinees.get(id)) {
+            *counts
+                .entry(examinee.academic_centre_id.as_ref())
+                .or_default() += 1;
+        }
+        let total = counts.values().sum::<usize>();
+        if total == 0 {
+            continue;
+        }
+        // The same rounding as the optimiser
+        let allowed = (total * max_share as usize / 100).max(1);
+        let exceeds = max_share < 100
+            && counts
+                .iter()
+                .any(|(centre, count)| centre.is_some() && *count > allowed);
+        let mut centres: Vec<CentreShare> = counts
+            .into_iter()
+            .map(|(centre, count)| CentreShare {
+                academic_centre: centre.cloned(),
+                examinees: count,
+                share: (count * 100 / total) as u32,
+            })
+            .collect();
+        centres.sort_by(|a, b| {
+            b.examinees
+                .cmp(&a.examinees)
+                .then_with(|| a.academic_centre.cmp(&b.academic_centre))
+        });
+        spread.push(ClassroomCentres {
+            subject: exam.subject.clone(),
+            classroom: used.classroom.clone(),
+            examinees: total,
+            centres,
+            exceeds,
+        });
+    }
+    spread
+}
@@ -0,0 +1,434 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{
+    assignation::ExamConfiguration,
+    classroom::Classroom,
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    EntityId,
+};
+
+use super::{
+    by_name,
+    conflicts::{ConflictResolution, SessionExam},
+    contains, highest_examinee_to_vigilant_ratio, ids,
+    individual::IndividualExamConfiguration,
+    interest::InterestRules,
+    push_unique, AssignmentError, Pin,
+};
+
+/// The exams of subjects that are held at the same time, they share the classrooms and vigilants
+pub(super) struct CollidingExamsConfiguration<'a> {
+    exams: Vec<IndividualExamConfiguration<'a>>,
+    available_classrooms: Vec<&'a Classroom>,
+    available_vigilants: Vec<&'a Vigilant>,
+    shared_room: Option<SharedRoom<'a>>,
+    interest: InterestRules,
+}
+
+/// Where the examinees enrolled in overlapping exams of the slot take all of them in sequence
+struct SharedRoom<'a> {
+    classroom: &'a Classroom,
+    vigilant: &'a Vigilant,
+    /// The examinees of every exam that take it in the classroom
+    examinees: Vec<Vec<&'a Examinee>>,
+}
+
+impl<'a> CollidingExamsConfiguration<'a> {
+    pub(super) fn new(subjects: Vec<(&'a Subject, Vec<Pin<'a>>)>) -> Self {
+        CollidingExamsConfiguration {
+            exams: subjects
+                .into_iter()
+                .map(|(subject, pins)| IndividualExamConfiguration::new(subject, pins))
+                .collect(),
+            available_classrooms: Vec::new(),
+            available_vigilants: Vec::new(),
+            shared_room: None,
+            interest: InterestRules::default(),
+        }
+    }
+
+    pub(super) fn add_examinees(&mut self, examinees: &[&'a Examinee]) {
+        for exam in &mut self.exams {
+            exam.add_examinees(examinees);
+        }
+    }
+
+    pub(super) fn add_classrooms(&mut self, classrooms: &[&'a Classroom]) {
+        for classroom in classrooms {
+            if classroom.court_location_id.is_none() {
+                push_unique(&mut self.available_classrooms, *classroom);
+            }
+        }
+    }
+
+    pub(super) fn add_vigilants(&mut self, vigilants: &[&'a Vigilant]) {
+        // The vigilants must be available from the first exam that starts to the last one that ends,
+        // extra time of the examinees included
+        let start = self
+            .exams
+            .iter()
+            .map(|exam| exam.subject.exam_date)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|starts| starts.into_iter().min());
+        let end = self
+            .exams
+            .iter()
+            .map(|exam| exam.subject.exam_end_for(exam.examinees.iter().copied()))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|ends| ends.into_iter().max());
+        for vigilant in vigilants {
+            if vigilant.role != VigilantRole::MEMBER {
+                continue;
+            }
+            if let Some((start, end)) = start.zip(end) {
+                if !vigilant.is_available(start, end) {
+                    continue;
+                }
+            }
+            push_unique(&mut self.available_vigilants, *vigilant);
+        }
+    }
+
+    pub(super) fn exclude_vigilants(&mut self, excluded: &HashSet<&EntityId>) {
+        self.available_vigilants
+            .retain(|vigilant| !excluded.contains(&vigilant.id));
+    }
+
+    pub(super) fn assigned_vigilants(&self) -> Vec<&'a Vigilant> {
+        let mut assigned = Vec::new();
+        for vigilant in self.exams.iter().flat_map(|exam| exam.assigned_vigilants()) {
+            push_unique(&mut assigned, vigilant);
+        }
+        assigned
+    }
+
+    pub(super) fn prefer_classrooms(&mut self, previous: &HashMap<&'a EntityId, &'a Classroom>) {
+        for exam in &mut self.exams {
+            exam.prefer_classrooms(previous);
+        }
+    }
+
+    pub(super) fn set_interest_rules(&mut self, interest: InterestRules) {
+        self.interest = interest;
+        for exam in &mut self.exams {
+            exam.set_interest_rules(interest);
+        }
+    }
+
+    pub(super) fn set_max_centre_share(&mut self, max_share: u32) {
+        for exam in &mut self.exams {
+            exam.set_max_centre_share(max_share);
+        }
+    }
+
+    pub(super) fn seated_examinees(&self) -> Vec<(&'a Examinee, &'a Classroom)> {
+        self.exams
+            .iter()
+            .flat_map(|exam| exam.seated_examinees())
+            .collect()
+    }
+
+    /// Takes out of their exams the examinees enrolled in overlapping exams of the slot, as the
+    /// resolution says. Returns the exams left for the incompatibility session
+    pub(super) fn separate_conflicts(
+        &mut self,
+        resolution: &ConflictResolution,
+        classrooms: &[&'a Classroom],
+    ) -> Result<Vec<SessionExam>, AssignmentError> {
+        let mut by_start: Vec<usize> = (0..self.exams.len()).collect();
+        by_start.sort_by_key(|&index| self.exams[index].subject.exam_date);
+        // The exams of every examinee, in the order they start
+        let mut enrolled: Vec<(&Examinee, Vec<usize>)> = Vec::new();
+        for &index in &by_start {
+            for examinee in &self.exams[index].examinees {
+                match enrolled
+                    .iter_mut()
+                    .find(|(listed, _)| std::ptr::eq(*listed, *examinee))
+                {
+                    Some((_, exams)) => exams.push(index),
+                    None => enrolled.push((examinee, vec![index])),
+                }
+            }
+        }
+
+        // Exams that overlap an earlier one of the same examinee
+        let mut overlapping: Vec<(&Examinee, Vec<usize>, Vec<usize>)> = Vec::new();
+        for (examinee, exams) in enrolled {
+            let (mut kept, mut left): (Vec<usize>, Vec<usize>) = (Vec::new(), Vec::new());
+            for exam in exams {
+                let subject = self.exams[exam].subject;
+                if kept
+                    .iter()
+                    .any(|&other| examinee.exam_overlaps(self.exams[other].subject, subject))
+                {
+                    left.push(exam);
+                } else {
+                    kept.push(exam);
+                }
+            }
+            if !left.is_empty() {
+                overlapping.push((examinee, kept, left));
+            }
+        }
+        if overlapping.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        match resolution {
+            ConflictResolution::SitBoth => Ok(Vec::new()),
+            ConflictResolution::IncompatibilitySession => {
+                let mut session = Vec::new();
+                for (examinee, _, left) in overlapping {
+                    for exam in left {
+                        self.exams[exam].remove_examinees(&[examinee]);
+                        session.push(SessionExam {
+                            examinee: examinee.id.clone(),
+                            subject: self.exams[exam].subject.id.clone(),
+                        });
+                    }
+                }
+                Ok(session)
+            }
+            ConflictResolution::SharedRoom { classroom } => {
+                let room = classrooms
+                    .iter()
+                    .find(|available| available.id == *classroom)
+                    .filter(|room| {
+                        room.court_location_id.is_none()
+                            && overlapping.len() <= room.total_capacity as usize
+                    });
+                let Some(room) = room else {
+                    return Err(AssignmentError::NotEnoughSeatsInSharedRoom {
+                        subjects: self.subjects(),
+                    });
+                };
+                self.available_classrooms
+                    .retain(|available| !std::ptr::eq(*available, *room));
+
+                // A vigilant that is not needed somewhere else and can watch all of them watches
+                // the classroom
+                let sharing: Vec<&Examinee> = overlapping
+                    .iter()
+                    .map(|(examinee, _, _)| *examinee)
+                    .collect();
+                let pinned: Vec<&Vigilant> = self
+                    .exams
+                    .iter()
+                    .flat_map(|exam| exam.pinned_vigilants())
+                    .collect();
+                let mut vigilants = self.available_vigilants.clone();
+                vigilants.sort_by(by_name);
+                let vigilant = vigilants.into_iter().find(|vigilant| {
+                    !contains(&pinned, vigilant)
+                        && self.interest.allows(vigilant, &sharing)
+                        && !self
+                            .exams
+                            .iter()
+                            .any(|exam| vigilant.specialties_ids.contains(&exam.subject.id))
+                });
+                let Some(vigilant) = vigilant else {
+                    return Err(AssignmentError::NoVigilantForSharedRoom {
+                        subjects: self.subjects(),
+                    });
+                };
+                self.available_vigilants
+                    .retain(|available| !std::ptr::eq(*available, vigilant));
+
+                let mut examinees = vec![Vec::new(); self.exams.len()];
+                for (examinee, kept, left) in overlapping {
+                    for exam in kept.into_iter().chain(left) {
+                        self.exams[exam].remove_examinees(&[examinee]);
+                        examinees[exam].push(examinee);
+                    }
+                }
+                self.shared_room = Some(SharedRoom {
+                    classroom: room,
+                    vigilant,
+                    examinees,
+                });
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn subjects(&self) -> Vec<EntityId> {
+        self.exams
+            .iter()
+            .map(|exam| exam.subject.id.clone())
+            .collect()
+    }
+
+    pub(super) fn do_assignment(&mut self) -> Vec<AssignmentError> {
+        for exam in &mut self.exams {
+            exam.reset_classrooms_and_vigilants();
+        }
+        let mut result = self.assign_classrooms();
+        result.extend(self.assign_vigilants());
+        if !result.is_empty() {
+            return result;
+        }
+        let result: Vec<AssignmentError> = self
+            .exams
+            .iter_mut()
+            .flat_map(|exam| exam.do_assignment())
+            .collect();
+        if !result.is_empty() {
+            return result;
+        }
+        if let Some(shared) = &self.shared_room {
+            for (exam, examinees) in self.exams.iter_mut().zip(&shared.examinees) {
+                if !examinees.is_empty() {
+                    exam.add_locked_classroom(shared.classroom, examinees, shared.vigilant);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    pub(super) fn use_empty_assignment(&mut self) {
+        for exam in &mut self.exams {
+            exam.use_empty_assignment();
+        }
+    }
+
+    fn not_enough_classrooms(&self) -> Vec<AssignmentError> {
+        vec![AssignmentError::NotEnoughClassrooms {
+            subjects: self.subjects(),
+        }]
+    }
+
+    fn assign_classrooms(&mut self) -> Vec<AssignmentError> {
+        if self.available_classrooms.len() < self.exams.len() {
+            return self.not_enough_classrooms();
+        }
+        let mut classrooms = self.available_classrooms.clone();
+        classrooms.sort_by_key(|classroom| classroom.priority);
+        // Exams that need some equipment choose first, so the rooms that have it are not taken by
+        // others
+        let mut order: Vec<usize> = (0..self.exams.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (self.exams[a].subject, self.exams[b].subject);
+            b.required_features
+                .len()
+                .cmp(&a.required_features.len())
+                .then_with(|| a.exam_date.cmp(&b.exam_date))
+        });
+
+        let assign_with = |capacity: fn(&Classroom) -> u32| {
+            let mut remaining = classrooms.clone();
+            let mut assignments = vec![Vec::new(); self.exams.len()];
+            let mut requirements: Vec<i64> = self
+                .exams
+                .iter()
+                .map(|exam| exam.examinees.len() as i64)
+                .collect();
+            // Locked classrooms are kept for their exams before any other is chosen
+            for &index in &order {
+                let exam = &self.exams[index];
+                for classroom in exam.pinned_classrooms() {
+                    let Some(position) = remaining.iter().position(|available| {
+                        std::ptr::eq(*available, classroom) && classroom.suits(exam.subject)
+                    }) else {
+                        continue;
+                    };
+                    remaining.remove(position);
+                    requirements[index] -= i64::from(capacity(classroom));
+                    assignments[index].push(classroom);
+                }
+            }
+            for &index in &order {
+                let exam = &self.exams[index];
+                let requirements = &mut requirements[index];
+                while *requirements > 0 {
+                    let position = remaining
+                        .iter()
+                        .position(|classroom| classroom.suits(exam.subject))?;
+                    let classroom = remaining.remove(position);
+                    *requirements -= i64::from(capacity(classroom));
+                    assignments[index].push(classroom);
+                }
+            }
+            Some(assignments)
+        };
+
+        let Some(assignments) = assign_with(|classroom| classroom.exam_capacity)
+            .or_else(|| assign_with(|classroom| classroom.total_capacity))
+        else {
+            return self.not_enough_classrooms();
+        };
+        for (exam, classrooms) in self.exams.iter_mut().zip(assignments) {
+            exam.add_classrooms(&classrooms);
+        }
+        Vec::new()
+    }
+
+    fn assign_vigilants(&mut self) -> Vec<AssignmentError> {
+        let mut vigilants = self.available_vigilants.clone();
+        vigilants.sort_by(by_name);
+
+        // Locked vigilants go back to their exams
+        let mut pinned = vec![0; self.exams.len()];
+        for (exam, pinned) in self.exams.iter_mut().zip(&mut pinned) {
+            for vigilant in exam.pinned_vigilants() {
+                let Some(position) = vigilants
+                    .iter()
+                    .position(|available| std::ptr::eq(*available, vigilant))
+                else {
+                    continue;
+                };
+                exam.add_vigilants(&[vigilants.remove(position)]);
+                *pinned += 1;
+            }
+        }
+        let total_vigilants = vigilants.len();
+
+        // Then assign specialists
+        let mut ratios = Vec::with_capacity(self.exams.len());
+        for (exam, pinned) in self.exams.iter_mut().zip(&pinned) {
+            let subject = &exam.subject.id;
+            let Some(index) = vigilants
+                .iter()
+                .position(|vigilant| vigilant.specialties_ids.contains(subject))
+            else {
+                return vec![AssignmentError::MissingSpecialist {
+                    subject: subject.clone(),
+                }];
+            };
+            exam.add_vigilants(&[vigilants.remove(index)]);
+            ratios.push((exam.examinees.len(), 1 + pinned));
+        }
+        if ratios.is_empty() {
+            return Vec::new();
+        }
+
+        let mut distributed_vigilants = ratios.len();
+        while distributed_vigilants < total_vigilants {
+            let highest = highest_examinee_to_vigilant_ratio(&ratios, |_, _| false).unwrap();
+            ratios[highest].1 += 1;
+            distributed_vigilants += 1;
+        }
+
+        // Now we know how many vigilants go to each exam, one of them is the specialist and some
+        // others may be locked
+        let mut remaining = vigilants.into_iter();
+        for ((exam, (_, total)), pinned) in self.exams.iter_mut().zip(ratios).zip(pinned) {
+            let vigilants: Vec<&Vigilant> = remaining.by_ref().take(total - 1 - pinned).collect();
+            exam.add_vigilants(&vigilants);
+        }
+        Vec::new()
+    }
+
+    pub(super) fn into_configuration(self) -> ExamConfiguration {
+        ExamConfiguration::CollidingExams {
+            exams: self
+                .exams
+                .into_iter()
+                .map(IndividualExamConfiguration::into_exam)
+                .collect(),
+            classrooms: ids(&self.available_classrooms),
+            vigilants: ids(&self.available_vigilants),
+        }
+    }
+}
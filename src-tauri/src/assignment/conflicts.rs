@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::{subject::Subject, AppValues, EntityId};
+
+/// Two exams held at the same time that an examinee is enrolled in
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ScheduleConflict {
+    pub examinee: EntityId,
+    /// The exam that starts first
+    pub first: EntityId,
+    pub second: EntityId,
+}
+
+/// What the assignment does with the examinees enrolled in exams held at the same time
+#[derive(Deserialize, Debug, Clone, Default, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ConflictResolution {
+    /// They are placed in the classrooms of both exams, as if they did not overlap
+    #[default]
+    SitBoth,
+    /// They take the exam that starts first as usual and are left out of the rest, which are
+    /// listed to be taken in an incompatibility session
+    IncompatibilitySession,
+    /// They take all their exams of the time slot in sequence in the classroom, which is kept
+    /// for them and locked
+    SharedRoom { classroom: EntityId },
+}
+
+/// An exam an examinee takes in the incompatibility session instead of with the rest
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SessionExam {
+    pub examinee: EntityId,
+    pub subject: EntityId,
+}
+
+/// Lists every pair of overlapping exams of each examinee, by examinee and then by the start of
+/// the exams
+pub fn schedule_conflicts(values: &AppValues) -> Vec<ScheduleConflict> {
+    let mut conflicts = Vec::new();
+    for examinee in &values.examinees {
+        let mut subjects: Vec<&Subject> = values
+            .subjects
+            .iter()
+            .filter(|subject| examinee.subjects_ids.contains(&subject.id))
+            .collect();
+        subjects.sort_by(|a, b| a.exam_date.cmp(&b.exam_date).then_with(|| a.id.cmp(&b.id)));
+        for (index, first) in subjects.iter().enumerate() {
+            for second in &subjects[index + 1..] {
+                if examinee.exam_overlaps(first, second) {
+                    conflicts.push(ScheduleConflict {
+                        examinee: examinee.id.clone(),
+                        first: first.id.clone(),
+                        second: second.id.clone(),
+                    });
+                }
+            }
+        }
+    }
+    conflicts.sort_by(|a, b| a.examinee.cmp(&b.examinee));
+    conflicts
+}
@@ -0,0 +1,623 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::models::{
+    classroom::{Classroom, ClassroomFeature},
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    AppValues, Entity, EntityId,
+};
+
+use super::{
+    by_name, conflicts::ConflictResolution, interest::InterestRules, is_accessible,
+    order_and_group_subjects, slot_span,
+};
+
+/// Why the exams can not be assigned and what could be changed so they can
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct Diagnosis {
+    /// Subjects without exam date or duration, they are not in any slot
+    pub undated_subjects: Vec<EntityId>,
+    pub slots: Vec<SlotDiagnosis>,
+    pub excluded_classrooms: Vec<ExcludedClassroom>,
+    /// Smallest set of changes found that would let every exam be assigned, empty if nothing
+    /// is missing
+    pub relaxations: Vec<Relaxation>,
+}
+
+/// Exams held at the same time, they compete for the classrooms and vigilants
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SlotDiagnosis {
+    #[ts(type = "string")]
+    pub start: DateTime<FixedOffset>,
+    #[ts(type = "string")]
+    pub end: DateTime<FixedOffset>,
+    pub subjects: Vec<SubjectDiagnosis>,
+    /// Seats the examinees need, one for every exam they take in the slot
+    pub seats_needed: usize,
+    /// Total capacity of the classrooms some exam of the slot can use
+    pub seats: usize,
+    pub seat_shortfall: usize,
+    pub vigilants_needed: usize,
+    pub vigilants: usize,
+    pub vigilant_shortfall: usize,
+    /// Vigilants left out because they are not available for the whole slot
+    pub unavailable_vigilants: Vec<EntityId>,
+    /// Vigilants left out because they did their maximum of duties in the slots before
+    pub exhausted_vigilants: Vec<EntityId>,
+    /// Examinees enrolled in overlapping exams of the slot, they take them in the shared room
+    /// when there is one
+    pub sharing_examinees: usize,
+    /// Seats the shared room is missing for them, all of them if it can not be used
+    pub shared_room_shortfall: usize,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SubjectDiagnosis {
+    pub subject: EntityId,
+    pub examinees: usize,
+    /// Total capacity of the classrooms the exam can use
+    pub seats: usize,
+    pub seat_shortfall: usize,
+    pub accessible_seat_shortfall: usize,
+    pub separate_room_shortfall: usize,
+    /// Other subjects of the slot that can use some of the same classrooms
+    pub competing_subjects: Vec<EntityId>,
+    /// The exam collides with others and no vigilant can be its specialist
+    pub missing_specialist: bool,
+    /// Examinees every vigilant of the slot has a conflict of interest with
+    pub conflicted_examinees: Vec<EntityId>,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ExcludedClassroom {
+    pub classroom: EntityId,
+    pub reason: ExclusionReason,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ExclusionReason {
+    /// The classroom is reserved for a court
+    CourtLocation {
+        court: EntityId,
+    },
+    NoCapacity,
+    /// It can not be used by the exam of the subject
+    MissingFeatures {
+        subject: EntityId,
+        features: Vec<ClassroomFeature>,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum Relaxation {
+    SetExamDate {
+        subject: EntityId,
+    },
+    /// The exam would fit if it could use classrooms without those features
+    DropRequiredFeatures {
+        subject: EntityId,
+        features: Vec<ClassroomFeature>,
+    },
+    /// Use the classrooms reserved for courts
+    UseCourtClassrooms {
+        subjects: Vec<EntityId>,
+        classrooms: Vec<EntityId>,
+    },
+    AddSeats {
+        subjects: Vec<EntityId>,
+        seats: usize,
+    },
+    AddAccessibleSeats {
+        subject: EntityId,
+        seats: usize,
+    },
+    AddSeparateRooms {
+        subject: EntityId,
+        rooms: usize,
+    },
+    /// Make the vigilants available for the whole slot
+    ExtendAvailability {
+        subjects: Vec<EntityId>,
+        vigilants: Vec<EntityId>,
+    },
+    AddVigilants {
+        subjects: Vec<EntityId>,
+        vigilants: usize,
+    },
+    AddSpecialist {
+        subject: EntityId,
+    },
+    /// Raise the maximum of duties of the vigilants so they can watch the slot too
+    RaiseMaxDuties {
+        subjects: Vec<EntityId>,
+        vigilants: Vec<EntityId>,
+    },
+    /// Add a vigilant without conflicts of interest with the examinees
+    AddVigilantWithoutConflicts {
+        subject: EntityId,
+        examinees: Vec<EntityId>,
+    },
+    /// Share a classroom with that many seats, not reserved for courts, among the examinees
+    /// enrolled in overlapping exams
+    UseBiggerSharedRoom {
+        subjects: Vec<EntityId>,
+        seats: usize,
+    },
+}
+
+/// Looks for what is missing for the assignment: the seats and vigilants every slot needs
+/// against the ones it has, the classrooms that can not be used and why, and the changes that
+/// would cover what is missing. The slots are gone through in order like the assignment does,
+/// so the duties done in the ones before count against the maximum of the vigilants
+pub fn diagnose(
+    values: &AppValues,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+) -> Diagnosis {
+    let (dated, undated): (Vec<&Subject>, Vec<&Subject>) = values
+        .subjects
+        .iter()
+        .partition(|subject| subject.exam_date.is_some() && subject.exam_duration.is_some());
+    let usable: Vec<&Classroom> = values
+        .classrooms
+        .iter()
+        .filter(|classroom| classroom.court_location_id.is_none() && classroom.total_capacity > 0)
+        .collect();
+
+    let mut relaxations: Vec<Relaxation> = undated
+        .iter()
+        .map(|subject| Relaxation::SetExamDate {
+            subject: subject.id.clone(),
+        })
+        .collect();
+    let mut slots = Vec::new();
+    let mut duties = HashMap::<&EntityId, usize>::new();
+    for group in order_and_group_subjects(dated.clone(), &values.examinees).unwrap_or_default() {
+        let vigilants = slot_vigilants(values, &group, &duties);
+        let slot = diagnose_slot(values, &group, &usable, &vigilants, conflicts, interest);
+        relax_slot(values, &group, &usable, &slot, &mut relaxations);
+        // Only an assigned slot uses the vigilants, and all of them are distributed
+        if is_assignable(&slot) {
+            for vigilant in &vigilants.available {
+                *duties.entry(vigilant.id()).or_default() += 1;
+            }
+        }
+        slots.push(slot);
+    }
+
+    Diagnosis {
+        undated_subjects: undated.iter().map(|subject| subject.id.clone()).collect(),
+        slots,
+        excluded_classrooms: excluded_classrooms(values, &dated),
+        relaxations,
+    }
+}
+
+fn enrolled<'a>(values: &'a AppValues, subject: &Subject) -> Vec<&'a Examinee> {
+    values
+        .examinees
+        .iter()
+        .filter(|examinee| examinee.subjects_ids.contains(&subject.id))
+        .collect()
+}
+
+fn suitable<'a>(usable: &[&'a Classroom], subject: &Subject) -> Vec<&'a Classroom> {
+    usable
+        .iter()
+        .filter(|classroom| classroom.suits(subject))
+        .copied()
+        .collect()
+}
+
+fn capacity(classrooms: &[&Classroom]) -> usize {
+    classrooms
+        .iter()
+        .map(|classroom| classroom.total_capacity as usize)
+        .sum()
+}
+
+/// Fewest classrooms, taking the biggest first, that seat the examinees
+fn classrooms_needed(classrooms: &[&Classroom], examinees: usize) -> usize {
+    let mut capacities: Vec<usize> = classrooms
+        .iter()
+        .map(|classroom| classroom.total_capacity as usize)
+        .collect();
+    capacities.sort_unstable_by(|a, b| b.cmp(a));
+    let mut seated = 0;
+    let mut needed = 0;
+    for capacity in capacities {
+        if seated >= examinees {
+            break;
+        }
+        seated += capacity;
+        needed += 1;
+    }
+    needed
+}
+
+/// Vigilants that can watch classrooms during a slot
+struct SlotVigilants<'a> {
+    available: Vec<&'a Vigilant>,
+    /// Left out by their availability
+    unavailable: Vec<&'a Vigilant>,
+    /// Left out by the duties they did in the slots before
+    exhausted: Vec<&'a Vigilant>,
+}
+
+fn slot_vigilants<'a>(
+    values: &'a AppValues,
+    group: &[&Subject],
+    duties: &HashMap<&EntityId, usize>,
+) -> SlotVigilants<'a> {
+    let (start, end) = slot_span(group, &values.examinees).unwrap();
+    let (mut exhausted, members): (Vec<&Vigilant>, Vec<&Vigilant>) = values
+        .vigilants
+        .iter()
+        .filter(|vigilant| vigilant.role == VigilantRole::MEMBER)
+        .partition(|vigilant| {
+            vigilant.max_duties.is_some_and(|max_duties| {
+                duties.get(vigilant.id()).copied().unwrap_or(0) >= usize::from(max_duties)
+            })
+        });
+    let (mut available, mut unavailable): (Vec<&Vigilant>, Vec<&Vigilant>) = members
+        .into_iter()
+        .partition(|vigilant| vigilant.is_available(start, end));
+    available.sort_by(by_name);
+    unavailable.sort_by(by_name);
+    exhausted.sort_by(by_name);
+    SlotVigilants {
+        available,
+        unavailable,
+        exhausted,
+    }
+}
+
+/// Examinees enrolled in exams of the slot that overlap
+fn sharing_examinees<'a>(values: &'a AppValues, group: &[&Subject]) -> Vec<&'a Examinee> {
+    values
+        .examinees
+        .iter()
+        .filter(|examinee| {
+            let enrolled: Vec<&&Subject> = group
+                .iter()
+                .filter(|subject| examinee.subjects_ids.contains(&subject.id))
+                .collect();
+            enrolled.iter().enumerate().any(|(index, first)| {
+                enrolled[index + 1..]
+                    .iter()
+                    .any(|second| examinee.exam_overlaps(first, second))
+            })
+        })
+        .collect()
+}
+
+/// The shared room fails like in the assignment: when it does not exist, is reserved for a
+/// court or has fewer seats than examinees share it
+fn shared_room_shortfall(
+    values: &AppValues,
+    conflicts: &ConflictResolution,
+    sharing: usize,
+) -> usize {
+    let ConflictResolution::SharedRoom { classroom } = conflicts else {
+        return 0;
+    };
+    if sharing == 0 {
+        return 0;
+    }
+    match values
+        .classrooms
+        .iter()
+        .find(|room| room.id == *classroom && room.court_location_id.is_none())
+    {
+        Some(room) => sharing.saturating_sub(room.total_capacity as usize),
+        None => sharing,
+    }
+}
+
+fn is_assignable(slot: &SlotDiagnosis) -> bool {
+    slot.seat_shortfall == 0
+        && slot.vigilant_shortfall == 0
+        && slot.shared_room_shortfall == 0
+        && slot.subjects.iter().all(|subject| {
+            subject.seat_shortfall == 0
+                && subject.accessible_seat_shortfall == 0
+                && subject.separate_room_shortfall == 0
+                && !subject.missing_specialist
+                && subject.conflicted_examinees.is_empty()
+        })
+}
+
+fn diagnose_slot(
+    values: &AppValues,
+    group: &[&Subject],
+    usable: &[&Classroom],
+    slot_vigilants: &SlotVigilants,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+) -> SlotDiagnosis {
+    let (start, end) = slot_span(group, &values.examinees).unwrap();
+    let available = &slot_vigilants.available;
+    let colliding = group.len() > 1;
+
+    let mut subjects = Vec::new();
+    let mut slot_classrooms: Vec<&Classroom> = Vec::new();
+    let mut vigilants_needed = 0;
+    for subject in group {
+        let examinees = enrolled(values, subject);
+        let classrooms = suitable(usable, subject);
+        for classroom in &classrooms {
+            if !slot_classrooms
+                .iter()
+                .any(|listed| std::ptr::eq(*listed, *classroom))
+            {
+                slot_classrooms.push(classroom);
+            }
+        }
+
+        let needing_access = examinees
+            .iter()
+            .filter(|examinee| examinee.accommodations.accessible_room)
+            .count();
+        let accessible: Vec<&Classroom> = classrooms
+            .iter()
+            .filter(|classroom| is_accessible(classroom))
+            .copied()
+            .collect();
+        let separate = examinees
+            .iter()
+            .filter(|examinee| examinee.accommodations.separate_room)
+            .count();
+
+        // Specialists do not watch classrooms when the exam is held alone
+        let watchers: Vec<&&Vigilant> = available
+            .iter()
+            .filter(|vigilant| colliding || !vigilant.specialties_ids.contains(&subject.id))
+            .collect();
+
+        // Alone, every classroom suitable for the exam is used. When colliding, only the ones
+        // needed, and a specialist
+        vigilants_needed += if colliding {
+            classrooms_needed(&classrooms, examinees.len()) + 1
+        } else {
+            classrooms.len()
+        };
+
+        subjects.push(SubjectDiagnosis {
+            subject: subject.id.clone(),
+            examinees: examinees.len(),
+            seats: capacity(&classrooms),
+            seat_shortfall: examinees.len().saturating_sub(capacity(&classrooms)),
+            accessible_seat_shortfall: needing_access.saturating_sub(capacity(&accessible)),
+            separate_room_shortfall: separate.saturating_sub(classrooms.len()),
+            competing_subjects: group
+                .iter()
+                .filter(|other| {
+                    other.id != subject.id
+                        && classrooms.iter().any(|classroom| classroom.suits(other))
+                })
+                .map(|other| other.id.clone())
+                .collect(),
+            missing_specialist: colliding
+                && !available
+                    .iter()
+                    .any(|vigilant| vigilant.specialties_ids.contains(&subject.id)),
+            conflicted_examinees: examinees
+                .iter()
+                .filter(|examinee| {
+                    !watchers.is_empty()
+                        && watchers
+                            .iter()
+                            .all(|vigilant| interest.conflict(vigilant, examinee).is_some())
+                })
+                .map(|examinee| examinee.id.clone())
+                .collect(),
+        });
+    }
+
+    // Specialists do not watch classrooms when the exam is held alone
+    let vigilants = if colliding {
+        available.len()
+    } else {
+        available
+            .iter()
+            .filter(|vigilant| !vigilant.specialties_ids.contains(&group[0].id))
+            .count()
+    };
+    let seats_needed: usize = subjects.iter().map(|subject| subject.examinees).sum();
+    let seats = capacity(&slot_classrooms);
+    let sharing = if colliding {
+        sharing_examinees(values, group).len()
+    } else {
+        0
+    };
+    let ids = |vigilants: &[&Vigilant]| -> Vec<EntityId> {
+        vigilants
+            .iter()
+            .map(|vigilant| vigilant.id.clone())
+            .collect()
+    };
+    SlotDiagnosis {
+        start,
+        end,
+        subjects,
+        seats_needed,
+        seats,
+        seat_shortfall: seats_needed.saturating_sub(seats),
+        vigilants_needed,
+        vigilants,
+        vigilant_shortfall: vigilants_needed.saturating_sub(vigilants),
+        unavailable_vigilants: ids(&slot_vigilants.unavailable),
+        exhausted_vigilants: ids(&slot_vigilants.exhausted),
+        sharing_examinees: sharing,
+        shared_room_shortfall: shared_room_shortfall(values, conflicts, sharing),
+    }
+}
+
+/// Adds the fewest changes that cover what the slot is missing, preferring the ones that change
+/// the least: dropping requirements and using what already exists before adding anything
+fn relax_slot(
+    values: &AppValues,
+    group: &[&Subject],
+    usable: &[&Classroom],
+    slot: &SlotDiagnosis,
+    relaxations: &mut Vec<Relaxation>,
+) {
+    let subject_ids: Vec<EntityId> = group.iter().map(|subject| subject.id.clone()).collect();
+
+    // Seats missing even if the exams could use every classroom not reserved for courts,
+    // whatever its features
+    let missing_seats = slot.seats_needed.saturating_sub(capacity(usable));
+    if missing_seats > 0 {
+        let mut courts: Vec<&Classroom> = values
+            .classrooms
+            .iter()
+            .filter(|classroom| {
+                classroom.court_location_id.is_some() && classroom.total_capacity > 0
+            })
+            .collect();
+        courts.sort_by_key(|classroom| std::cmp::Reverse(classroom.total_capacity));
+        let mut missing = missing_seats;
+        let mut classrooms = Vec::new();
+        for classroom in courts {
+            if missing == 0 {
+                break;
+            }
+            missing = missing.saturating_sub(classroom.total_capacity as usize);
+            classrooms.push(classroom.id.clone());
+        }
+        if !classrooms.is_empty() {
+            relaxations.push(Relaxation::UseCourtClassrooms {
+                subjects: subject_ids.clone(),
+                classrooms,
+            });
+        }
+        if missing > 0 {
+            relaxations.push(Relaxation::AddSeats {
+                subjects: subject_ids.clone(),
+                seats: missing,
+            });
+        }
+    }
+
+    for (subject, diagnosis) in group.iter().zip(&slot.subjects) {
+        let id = subject.id.clone();
+        // The rest of the seats missing are because of the features the exam requires
+        if diagnosis.seat_shortfall > 0 && !subject.required_features.is_empty() {
+            relaxations.push(Relaxation::DropRequiredFeatures {
+                subject: id.clone(),
+                features: subject.required_features.iter().copied().collect(),
+            });
+        }
+        if diagnosis.accessible_seat_shortfall > 0 {
+            relaxations.push(Relaxation::AddAccessibleSeats {
+                subject: id.clone(),
+                seats: diagnosis.accessible_seat_shortfall,
+            });
+        }
+        if diagnosis.separate_room_shortfall > 0 {
+            relaxations.push(Relaxation::AddSeparateRooms {
+                subject: id.clone(),
+                rooms: diagnosis.separate_room_shortfall,
+            });
+        }
+        if diagnosis.missing_specialist {
+            relaxations.push(Relaxation::AddSpecialist {
+                subject: id.clone(),
+            });
+        }
+        if !diagnosis.conflicted_examinees.is_empty() {
+            relaxations.push(Relaxation::AddVigilantWithoutConflicts {
+                subject: id,
+                examinees: diagnosis.conflicted_examinees.clone(),
+            });
+        }
+    }
+
+    if slot.shared_room_shortfall > 0 {
+        relaxations.push(Relaxation::UseBiggerSharedRoom {
+            subjects: subject_ids.clone(),
+            seats: slot.sharing_examinees,
+        });
+    }
+
+    if slot.vigilant_shortfall > 0 {
+        let vigilants: Vec<EntityId> = slot
+            .unavailable_vigilants
+            .iter()
+            .take(slot.vigilant_shortfall)
+            .cloned()
+            .collect();
+        let mut missing = slot.vigilant_shortfall - vigilants.len();
+        if !vigilants.is_empty() {
+            relaxations.push(Relaxation::ExtendAvailability {
+                subjects: subject_ids.clone(),
+                vigilants,
+            });
+        }
+        let vigilants: Vec<EntityId> = slot
+            .exhausted_vigilants
+            .iter()
+            .take(missing)
+            .cloned()
+            .collect();
+        missing -= vigilants.len();
+        if !vigilants.is_empty() {
+            relaxations.push(Relaxation::RaiseMaxDuties {
+                subjects: subject_ids.clone(),
+                vigilants,
+            });
+        }
+        if missing > 0 {
+            relaxations.push(Relaxation::AddVigilants {
+                subjects: subject_ids,
+                vigilants: missing,
+            });
+        }
+    }
+}
+
+fn excluded_classrooms(values: &AppValues, subjects: &[&Subject]) -> Vec<ExcludedClassroom> {
+    let mut excluded = Vec::new();
+    for classroom in &values.classrooms {
+        let reasons = if let Some(court) = classroom.court_location_id.clone() {
+            vec![ExclusionReason::CourtLocation { court }]
+        } else if classroom.total_capacity == 0 {
+            vec![ExclusionReason::NoCapacity]
+        } else {
+            subjects
+                .iter()
+                .filter(|subject| !classroom.suits(subject))
+                .map(|subject| ExclusionReason::MissingFeatures {
+                    subject: subject.id.clone(),
+                    features: subject
+                        .required_features
+                        .difference(&classroom.features)
+                        .copied()
+                        .collect(),
+                })
+                .collect()
+        };
+        excluded.extend(reasons.into_iter().map(|reason| ExcludedClassroom {
+            classroom: classroom.id.clone(),
+            reason,
+        }));
+    }
+    excluded
+}
@@ -0,0 +1,749 @@
+use std::collections::{HashMap, HashSet};
+
+use log::error;
+
+use crate::models::{
+    assignation::{ExamClassroomDistribution, ExamDistribution, IndividualExam},
+    classroom::Classroom,
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    EntityId,
+};
+
+use super::{
+    by_name, contains, highest_examinee_to_vigilant_ratio, ids, interest::InterestRules,
+    is_accessible, push_unique, AssignmentError, Pin,
+};
+
+/// The exam of a subject that is not held at the same time as any other
+pub(super) struct IndividualExamConfiguration<'a> {
+    pub(super) subject: &'a Subject,
+    pub(super) examinees: Vec<&'a Examinee>,
+    classrooms: Vec<&'a Classroom>,
+    vigilants: Vec<&'a Vigilant>,
+    /// Some vigilants were left out for not being available or having done all their duties
+    left_out_vigilants: bool,
+    /// What is locked of the previous assignment
+    pins: Vec<Pin<'a>>,
+    /// Classroom of the previous exam of each examinee, they are kept there if it is used
+    previous_classrooms: HashMap<&'a EntityId, &'a Classroom>,
+    /// Which vigilants can not watch which examinees
+    interest: InterestRules,
+    /// Highest percentage of the examinees of a classroom from the same academic centre, 100
+    /// means there is no limit
+    max_centre_share: u32,
+    distribution: Option<Distribution<'a>>,
+}
+
+#[derive(Default)]
+struct Distribution<'a> {
+    specialists: Vec<&'a Vigilant>,
+    classrooms: Vec<ClassroomDistribution<'a>>,
+    /// The pins that could be kept, they stay locked
+    pins: Vec<Pin<'a>>,
+}
+
+struct ClassroomDistribution<'a> {
+    classroom: &'a Classroom,
+    examinees: Vec<&'a Examinee>,
+    vigilants: Vec<&'a Vigilant>,
+}
+
+impl<'a> IndividualExamConfiguration<'a> {
+    pub(super) fn new(subject: &'a Subject, pins: Vec<Pin<'a>>) -> Self {
+        IndividualExamConfiguration {
+            subject,
+            examinees: Vec::new(),
+            classrooms: Vec::new(),
+            vigilants: Vec::new(),
+            left_out_vigilants: false,
+            pins,
+            previous_classrooms: HashMap::new(),
+            interest: InterestRules::default(),
+            max_centre_share: 100,
+            distribution: None,
+        }
+    }
+
+    pub(super) fn add_examinees(&mut self, examinees: &[&'a Examinee]) {
+        for examinee in examinees {
+            if examinee.subjects_ids.contains(&self.subject.id) {
+                push_unique(&mut self.examinees, *examinee);
+            }
+        }
+        self.distribution = None;
+    }
+
+    pub(super) fn add_classrooms(&mut self, classrooms: &[&'a Classroom]) {
+        for classroom in classrooms {
+            if classroom.court_location_id.is_none() && classroom.suits(self.subject) {
+                push_unique(&mut self.classrooms, *classroom);
+            }
+        }
+        self.distribution = None;
+    }
+
+    pub(super) fn add_vigilants(&mut self, vigilants: &[&'a Vigilant]) {
+        // The vigilants stay until the last examinee finishes, extra time included
+        let times = self
+            .subject
+            .exam_date
+            .zip(self.subject.exam_end_for(self.examinees.iter().copied()));
+        for vigilant in vigilants {
+            if vigilant.role != VigilantRole::MEMBER {
+                continue;
+            }
+            if let Some((start, end)) = times {
+                if !vigilant.is_available(start, end) {
+                    self.left_out_vigilants = true;
+                    continue;
+                }
+            }
+            push_unique(&mut self.vigilants, *vigilant);
+        }
+    }
+
+    pub(super) fn exclude_vigilants(&mut self, excluded: &HashSet<&EntityId>) {
+        let before = self.vigilants.len();
+        self.vigilants
+            .retain(|vigilant| !excluded.contains(&vigilant.id));
+        self.left_out_vigilants |= self.vigilants.len() < before;
+    }
+
+    pub(super) fn reset_classrooms_and_vigilants(&mut self) {
+        self.vigilants.clear();
+        self.classrooms.clear();
+        self.distribution = None;
+    }
+
+    /// Vigilants used by the last assignment done, specialists included
+    pub(super) fn assigned_vigilants(&self) -> Vec<&'a Vigilant> {
+        let mut assigned = Vec::new();
+        if let Some(distribution) = &self.distribution {
+            for vigilant in distribution
+                .specialists
+                .iter()
+                .chain(distribution.classrooms.iter().flat_map(|c| &c.vigilants))
+            {
+                push_unique(&mut assigned, *vigilant);
+            }
+        }
+        assigned
+    }
+
+    pub(super) fn prefer_classrooms(&mut self, previous: &HashMap<&'a EntityId, &'a Classroom>) {
+        self.previous_classrooms = self
+            .examinees
+            .iter()
+            .filter_map(|examinee| Some((&examinee.id, *previous.get(&examinee.id)?)))
+            .collect();
+    }
+
+    pub(super) fn set_interest_rules(&mut self, interest: InterestRules) {
+        self.interest = interest;
+    }
+
+    pub(super) fn set_max_centre_share(&mut self, max_share: u32) {
+        self.max_centre_share = max_share;
+    }
+
+    /// Takes examinees out of the exam, they take it somewhere else
+    pub(super) fn remove_examinees(&mut self, examinees: &[&Examinee]) {
+        self.examinees
+            .retain(|examinee| !contains(examinees, examinee));
+        self.distribution = None;
+    }
+
+    /// Adds a classroom with its examinees and vigilant already chosen to the assignment done.
+    /// It is locked, so doing the assignment again keeps it
+    pub(super) fn add_locked_classroom(
+        &mut self,
+        classroom: &'a Classroom,
+        examinees: &[&'a Examinee],
+        vigilant: &'a Vigilant,
+    ) {
+        let Some(distribution) = &mut self.distribution else {
+            return;
+        };
+        for examinee in examinees {
+            push_unique(&mut self.examinees, *examinee);
+        }
+        push_unique(&mut self.classrooms, classroom);
+        push_unique(&mut self.vigilants, vigilant);
+        distribution.pins.push(Pin {
+            classroom,
+            locked: true,
+            examinees: examinees.to_vec(),
+            vigilants: vec![vigilant],
+        });
+        distribution.classrooms.push(ClassroomDistribution {
+            classroom,
+            examinees: examinees.to_vec(),
+            vigilants: vec![vigilant],
+        });
+    }
+
+    /// Where every examinee sits in the last assignment done
+    pub(super) fn seated_examinees(&self) -> Vec<(&'a Examinee, &'a Classroom)> {
+        self.distribution
+            .iter()
+            .flat_map(|distribution| &distribution.classrooms)
+            .flat_map(|classroom| {
+                classroom
+                    .examinees
+                    .iter()
+                    .map(|examinee| (*examinee, classroom.classroom))
+            })
+            .collect()
+    }
+
+    /// Classrooms with something locked for the exam
+    pub(super) fn pinned_classrooms(&self) -> Vec<&'a Classroom> {
+        self.pins.iter().map(|pin| pin.classroom).collect()
+    }
+
+    /// Locked vigilants without a conflict of interest with the locked examinees of their
+    /// classroom, the rest are not kept
+    pub(super) fn pinned_vigilants(&self) -> Vec<&'a Vigilant> {
+        let mut vigilants = Vec::new();
+        for pin in &self.pins {
+            for vigilant in &pin.vigilants {
+                if self.interest.allows(vigilant, &pin.examinees) {
+                    push_unique(&mut vigilants, *vigilant);
+                }
+            }
+        }
+        vigilants
+    }
+
+    /// The pins that can still be kept: the ones of classrooms that can be used, with the
+    /// examinees that still take the exam and the vigilants that can still watch it without a
+    /// conflict of interest with them
+    fn valid_pins(&self) -> Vec<Pin<'a>> {
+        let mut examinees = Vec::new();
+        let mut vigilants = Vec::new();
+        let mut pins = Vec::new();
+        for pin in &self.pins {
+            if !contains(&self.classrooms, pin.classroom)
+                || pins
+                    .iter()
+                    .any(|kept: &Pin| std::ptr::eq(kept.classroom, pin.classroom))
+            {
+                continue;
+            }
+            let mut kept = Pin {
+                classroom: pin.classroom,
+                locked: pin.locked,
+                examinees: Vec::new(),
+                vigilants: Vec::new(),
+            };
+            for examinee in &pin.examinees {
+                if contains(&self.examinees, *examinee) && !contains(&examinees, *examinee) {
+                    examinees.push(*examinee);
+                    kept.examinees.push(*examinee);
+                }
+            }
+            for vigilant in &pin.vigilants {
+                if contains(&self.vigilants, *vigilant)
+                    && !contains(&vigilants, *vigilant)
+                    && self.interest.allows(vigilant, &kept.examinees)
+                {
+                    vigilants.push(*vigilant);
+                    kept.vigilants.push(*vigilant);
+                }
+            }
+            pins.push(kept);
+        }
+        pins
+    }
+
+    pub(super) fn use_empty_assignment(&mut self) {
+        self.distribution = Some(Distribution::default());
+    }
+
+    pub(super) fn do_assignment(&mut self) -> Vec<AssignmentError> {
+        self.distribution = None;
+        if self.classrooms.is_empty() {
+            return vec![AssignmentError::NoClassrooms];
+        }
+        let total_capacity: u64 = self
+            .classrooms
+            .iter()
+            .map(|classroom| u64::from(classroom.total_capacity))
+            .sum();
+        if self.examinees.len() as u64 > total_capacity {
+            return vec![AssignmentError::NotEnoughSeats {
+                subject: self.subject.id.clone(),
+            }];
+        }
+        if self.vigilants.len() < self.classrooms.len() {
+            return self.not_enough_vigilants();
+        }
+        // Vigilants locked in a classroom watch it, even if they are specialists
+        let pins = self.valid_pins();
+        let pinned: Vec<&Vigilant> = pins
+            .iter()
+            .flat_map(|pin| pin.vigilants.iter().copied())
+            .collect();
+        self.distribution = Some(Distribution {
+            specialists: self
+                .vigilants
+                .iter()
+                .filter(|vigilant| {
+                    vigilant.specialties_ids.contains(&self.subject.id)
+                        && !contains(&pinned, vigilant)
+                })
+                .copied()
+                .collect(),
+            classrooms: Vec::new(),
+            pins,
+        });
+        let result = self.assign_examinees();
+        if !result.is_empty() {
+            return result;
+        }
+        self.assign_vigilants()
+    }
+
+    fn not_enough_vigilants(&self) -> Vec<AssignmentError> {
+        let subject = self.subject.id.clone();
+        if self.left_out_vigilants {
+            vec![AssignmentError::NotEnoughAvailableVigilants { subject }]
+        } else {
+            vec![AssignmentError::NotEnoughVigilants { subject }]
+        }
+    }
+
+    fn assign_examinees(&mut self) -> Vec<AssignmentError> {
+        let subject = self.subject.id.clone();
+        let mut classrooms = self.classrooms.clone();
+        classrooms.sort_by_key(|classroom| classroom.priority);
+        let distribution = self.distribution.get_or_insert_with(Distribution::default);
+
+        // Locked examinees are already seated
+        let mut seated: Vec<Vec<&Examinee>> = classrooms
+            .iter()
+            .map(|classroom| {
+                distribution
+                    .pins
+                    .iter()
+                    .filter(|pin| std::ptr::eq(pin.classroom, *classroom))
+                    .flat_map(|pin| pin.examinees.iter().copied())
+                    .collect()
+            })
+            .collect();
+        let mut all_examinees: Vec<&Examinee> = self
+            .examinees
+            .iter()
+            .filter(|examinee| !seated.iter().any(|seated| contains(seated, examinee)))
+            .copied()
+            .collect();
+        all_examinees.sort_by(by_name);
+        // Classrooms with an examinee that takes the exam alone are not shared
+        let mut alone: Vec<bool> = seated
+            .iter()
+            .map(|examinees| {
+                examinees
+                    .iter()
+                    .any(|examinee| examinee.accommodations.separate_room)
+            })
+            .collect();
+
+        // Examinees that take the exam alone get the smallest empty classroom that suits them
+        for examinee in all_examinees
+            .iter()
+            .filter(|examinee| examinee.accommodations.separate_room)
+        {
+            let Some(index) = (0..classrooms.len())
+                .filter(|&index| {
+                    seated[index].is_empty()
+                        && (!examinee.accommodations.accessible_room
+                            || is_accessible(classrooms[index]))
+                })
+                .min_by_key(|&index| classrooms[index].total_capacity)
+            else {
+                return vec![AssignmentError::NotEnoughSeparateRooms { subject }];
+            };
+            seated[index].push(*examinee);
+            alone[index] = true;
+        }
+
+        let examinees: Vec<&Examinee> = all_examinees
+            .into_iter()
+            .filter(|examinee| !examinee.accommodations.separate_room)
+            .collect();
+        // The seats left in the classrooms that can be shared
+        let shared: Vec<usize> = (0..classrooms.len())
+            .filter(|&index| !alone[index])
+            .collect();
+        let space = |capacity: fn(&Classroom) -> u32| -> Vec<u64> {
+            shared
+                .iter()
+                .map(|&index| {
+                    u64::from(capacity(classrooms[index]))
+                        .saturating_sub(seated[index].len() as u64)
+                })
+                .collect()
+        };
+        let exam_space = space(|classroom| classroom.exam_capacity);
+        let total_space = space(|classroom| classroom.total_capacity);
+        let exam_capacity: u64 = exam_space.iter().sum();
+        let total_capacity: u64 = total_space.iter().sum();
+        let total_examinees = examinees.len() as u64;
+        if total_examinees > total_capacity {
+            return vec![AssignmentError::NotEnoughSeats { subject }];
+        }
+
+        // Each classroom gets its share of the examinees, by exam capacity if there is enough of
+        // it, but floored
+        let mut counts: Vec<u64> = (0..shared.len())
+            .map(|position| {
+                let (capacity, total) = if exam_capacity > total_examinees {
+                    (exam_space[position], exam_capacity)
+                } else {
+                    (total_space[position], total_capacity)
+                };
+                (capacity * total_examinees).checked_div(total).unwrap_or(0)
+            })
+            .collect();
+        let mut assigned_examinees: u64 = counts.iter().sum();
+
+        // Since we were flooring, most likely not all examinees are assigned. First fill the exam
+        // capacity of the classrooms in order of priority and then, just in case, the total one
+        fill_classrooms(
+            &mut counts,
+            |position| exam_space[position],
+            &mut assigned_examinees,
+            total_examinees,
+        );
+        fill_classrooms(
+            &mut counts,
+            |position| total_space[position],
+            &mut assigned_examinees,
+            total_examinees,
+        );
+        if assigned_examinees < total_examinees {
+            // Should never happen since the capacity was checked before
+            error!(
+                "The total capacity could not fit all the examinees, {assigned_examinees} of {total_examinees}"
+            );
+            return vec![AssignmentError::NotEnoughSeats { subject }];
+        }
+
+        // Examinees that need an accessible classroom take its seats first
+        let (mut needing_access, mut others): (Vec<&Examinee>, Vec<&Examinee>) = examinees
+            .into_iter()
+            .partition(|examinee| examinee.accommodations.accessible_room);
+        let accessible_seats: u64 = shared
+            .iter()
+            .zip(&counts)
+            .filter(|(&index, _)| is_accessible(classrooms[index]))
+            .map(|(_, count)| count)
+            .sum();
+        if needing_access.len() as u64 > accessible_seats {
+            return vec![AssignmentError::NotEnoughAccessibleSeats { subject }];
+        }
+
+        let mut access_quotas = Vec::with_capacity(shared.len());
+        let mut other_quotas = Vec::with_capacity(shared.len());
+        let (mut access_left, mut others_left) = (needing_access.len(), others.len());
+        for (&index, count) in shared.iter().zip(counts) {
+            let count = count as usize;
+            let mut added = 0;
+            if is_accessible(classrooms[index]) {
+                added = count.min(access_left);
+                access_left -= added;
+            }
+            let rest = (count - added).min(others_left);
+            others_left -= rest;
+            access_quotas.push(added);
+            other_quotas.push(rest);
+        }
+        for (examinees, quotas) in [
+            (&mut needing_access, access_quotas),
+            (&mut others, other_quotas),
+        ] {
+            let rooms: Vec<&Classroom> = shared.iter().map(|&index| classrooms[index]).collect();
+            for (position, taken) in distribute(
+                examinees,
+                &rooms,
+                quotas,
+                &self.previous_classrooms,
+                self.max_centre_share,
+            )
+            .into_iter()
+            .enumerate()
+            {
+                seated[shared[position]].extend(taken);
+            }
+        }
+
+        // Locked vigilants leave the classroom if the examinees seated around the locked ones
+        // give them a conflict of interest
+        let interest = self.interest;
+        distribution.classrooms = classrooms
+            .into_iter()
+            .zip(seated)
+            .map(|(classroom, mut examinees)| {
+                examinees.sort_by(by_name);
+                let vigilants = distribution
+                    .pins
+                    .iter()
+                    .filter(|pin| std::ptr::eq(pin.classroom, classroom))
+                    .flat_map(|pin| pin.vigilants.iter().copied())
+                    .filter(|vigilant| interest.allows(vigilant, &examinees))
+                    .collect();
+                ClassroomDistribution {
+                    classroom,
+                    examinees,
+                    vigilants,
+                }
+            })
+            .collect();
+        Vec::new()
+    }
+
+    fn assign_vigilants(&mut self) -> Vec<AssignmentError> {
+        let subject = self.subject.id.clone();
+        // Locked vigilants are already in their classrooms
+        let pinned: Vec<&Vigilant> = self
+            .distribution
+            .iter()
+            .flat_map(|distribution| &distribution.classrooms)
+            .flat_map(|classroom| classroom.vigilants.iter().copied())
+            .collect();
+        let mut vigilants: Vec<&Vigilant> = self
+            .vigilants
+            .iter()
+            .filter(|vigilant| {
+                !vigilant.specialties_ids.contains(&self.subject.id) && !contains(&pinned, vigilant)
+            })
+            .copied()
+            .collect();
+        vigilants.sort_by(by_name);
+        if vigilants.len() + pinned.len() < self.classrooms.len() {
+            return self.not_enough_vigilants();
+        }
+        let Some(distribution) = &mut self.distribution else {
+            return Vec::new();
+        };
+
+        // First make sure every classroom with examinees gets at least one vigilant
+        let used: Vec<usize> = (0..distribution.classrooms.len())
+            .filter(|&index| !distribution.classrooms[index].examinees.is_empty())
+            .collect();
+        if used.is_empty() {
+            return Vec::new();
+        }
+        let mut ratios: Vec<(usize, usize)> = used
+            .iter()
+            .map(|&index| {
+                let classroom = &distribution.classrooms[index];
+                (classroom.examinees.len(), classroom.vigilants.len().max(1))
+            })
+            .collect();
+        let mut assigned_vigilants = used
+            .iter()
+            .filter(|&&index| distribution.classrooms[index].vigilants.is_empty())
+            .count();
+        if assigned_vigilants > vigilants.len() {
+            return self.not_enough_vigilants();
+        }
+
+        // Then the rest go where there are the most examinees for each vigilant, on ties to the
+        // classroom with the best priority
+        while assigned_vigilants < vigilants.len() {
+            let highest = highest_examinee_to_vigilant_ratio(&ratios, |best, current| {
+                distribution.classrooms[used[best]].classroom.priority
+                    >= distribution.classrooms[used[current]].classroom.priority
+            })
+            .unwrap();
+            ratios[highest].1 += 1;
+            assigned_vigilants += 1;
+        }
+
+        // Vigilants are not put with examinees they have a conflict of interest with. The
+        // classrooms that the fewest vigilants can watch get their first vigilant before the rest
+        let interest = self.interest;
+        let mut remaining = vigilants;
+        let mut first: Vec<usize> = used
+            .iter()
+            .copied()
+            .filter(|&index| distribution.classrooms[index].vigilants.is_empty())
+            .collect();
+        first.sort_by_key(|&index| {
+            let examinees = &distribution.classrooms[index].examinees;
+            remaining
+                .iter()
+                .filter(|vigilant| interest.allows(vigilant, examinees))
+                .count()
+        });
+        for index in first {
+            let classroom = &mut distribution.classrooms[index];
+            let Some(position) = remaining
+                .iter()
+                .position(|vigilant| interest.allows(vigilant, &classroom.examinees))
+            else {
+                return vec![AssignmentError::ConflictOfInterest { subject }];
+            };
+            classroom.vigilants.push(remaining.remove(position));
+        }
+        for (&index, (_, total)) in used.iter().zip(ratios) {
+            let classroom = &mut distribution.classrooms[index];
+            while classroom.vigilants.len() < total {
+                let Some(position) = remaining
+                    .iter()
+                    .position(|vigilant| interest.allows(vigilant, &classroom.examinees))
+                else {
+                    break;
+                };
+                classroom.vigilants.push(remaining.remove(position));
+            }
+        }
+        // The ones left go where they can with the most examinees for each vigilant
+        for vigilant in remaining {
+            let allowed: Vec<usize> = used
+                .iter()
+                .copied()
+                .filter(|&index| {
+                    interest.allows(vigilant, &distribution.classrooms[index].examinees)
+                })
+                .collect();
+            let ratios: Vec<(usize, usize)> = allowed
+                .iter()
+                .map(|&index| {
+                    let classroom = &distribution.classrooms[index];
+                    (classroom.examinees.len(), classroom.vigilants.len())
+                })
+                .collect();
+            if let Some(highest) = highest_examinee_to_vigilant_ratio(&ratios, |_, _| false) {
+                distribution.classrooms[allowed[highest]]
+                    .vigilants
+                    .push(vigilant);
+            }
+        }
+        Vec::new()
+    }
+
+    pub(super) fn into_exam(self) -> IndividualExam {
+        let subject = self.subject.id.clone();
+        IndividualExam {
+            subject: subject.clone(),
+            examinees: ids(&self.examinees),
+            classrooms: ids(&self.classrooms),
+            vigilants: ids(&self.vigilants),
+            distribution: self.distribution.map(|distribution| ExamDistribution {
+                subject,
+                specialists: ids(&distribution.specialists),
+                distribution: distribution
+                    .classrooms
+                    .into_iter()
+                    .map(|classroom| {
+                        let pin = distribution
+                            .pins
+                            .iter()
+                            .find(|pin| std::ptr::eq(pin.classroom, classroom.classroom));
+                        ExamClassroomDistribution {
+                            classroom: classroom.classroom.id.clone(),
+                            examinees: ids(&classroom.examinees),
+                            vigilants: ids(&classroom.vigilants),
+                            locked: pin.is_some_and(|pin| pin.locked),
+                            locked_examinees: pin
+                                .map(|pin| ids(&pin.examinees))
+                                .unwrap_or_default(),
+                            locked_vigilants: pin
+                                .map(|pin| ids(&pin.vigilants))
+                                .unwrap_or_default(),
+                            seats: Vec::new(),
+                        }
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// Adds examinees one by one to the classrooms that have space left, going around them in order,
+/// until all are assigned or the classrooms are full
+fn fill_classrooms(
+    counts: &mut [u64],
+    capacity: impl Fn(usize) -> u64,
+    assigned: &mut u64,
+    total: u64,
+) {
+    let mut with_space: Vec<usize> = (0..counts.len())
+        .filter(|&index| capacity(index) > counts[index])
+        .collect();
+    let mut i = 0;
+    while !with_space.is_empty() && *assigned < total {
+        let index = with_space[i];
+        counts[index] += 1;
+        *assigned += 1;
+        if counts[index] < capacity(index) {
+            i = (i + 1) % with_space.len();
+        } else {
+            with_space.remove(i);
+            if i >= with_space.len() {
+                i = 0;
+            }
+        }
+    }
+}
+
+/// Splits the examinees among the classrooms, as many as their quota in each. The examinees
+/// whose previous exam was in one of the classrooms stay there while there is room for them,
+/// the rest fill the seats left in order. Below a `max_share` of 100 an examinee whose academic
+/// centre already has that share of the classroom is passed over for the next one, unless all
+/// the ones left are
+fn distribute<'a>(
+    examinees: &mut Vec<&'a Examinee>,
+    classrooms: &[&Classroom],
+    mut quotas: Vec<usize>,
+    previous_classrooms: &HashMap<&EntityId, &Classroom>,
+    max_share: u32,
+) -> Vec<Vec<&'a Examinee>> {
+    let mut taken = vec![Vec::new(); classrooms.len()];
+    examinees.retain(|examinee| {
+        let Some(previous) = previous_classrooms.get(&examinee.id) else {
+            return true;
+        };
+        let Some(position) = classrooms
+            .iter()
+            .position(|classroom| std::ptr::eq(*classroom, *previous))
+        else {
+            return true;
+        };
+        if quotas[position] == 0 {
+            return true;
+        }
+        quotas[position] -= 1;
+        taken[position].push(*examinee);
+        false
+    });
+    for (position, quota) in quotas.into_iter().enumerate() {
+        let quota = quota.min(examinees.len());
+        if max_share >= 100 {
+            taken[position].extend(examinees.drain(..quota));
+            continue;
+        }
+        // The same rounding as the optimiser
+        let allowed = ((taken[position].len() + quota) * max_share as usize / 100).max(1);
+        for _ in 0..quota {
+            let seated = &taken[position];
+            let next = examinees
+                .iter()
+                .position(|examinee| {
+                    examinee.academic_centre_id.is_none()
+                        || seated
+                            .iter()
+                            .filter(|other| other.academic_centre_id == examinee.academic_centre_id)
+                            .count()
+                            < allowed
+                })
+                .unwrap_or(0);
+            taken[position].push(examinees.remove(next));
+        }
+    }
+    taken
+}
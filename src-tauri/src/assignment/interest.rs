@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::{examinee::Examinee, vigilant::Vigilant, AppValues, Entity, EntityId};
+
+/// Relationships between a vigilant and an examinee that keep the vigilant from watching the
+/// classroom of the examinee
+#[derive(Deserialize, Debug, Clone, Copy, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct InterestRules {
+    /// The vigilant is from the academic centre of the examinee
+    pub same_centre: bool,
+    /// The vigilant declared the examinee as a relative
+    pub relatives: bool,
+    /// The vigilant teaches, at the academic centre of the examinee, a subject the examinee takes
+    pub subject_teacher: bool,
+}
+
+impl Default for InterestRules {
+    fn default() -> Self {
+        InterestRules {
+            same_centre: false,
+            relatives: true,
+            subject_teacher: true,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum InterestKind {
+    SameCentre,
+    Relative,
+    SubjectTeacher,
+}
+
+impl InterestRules {
+    /// The rule the vigilant breaks by watching the examinee, the most specific one if there are
+    /// several
+    pub fn conflict(&self, vigilant: &Vigilant, examinee: &Examinee) -> Option<InterestKind> {
+        if self.relatives
+            && vigilant
+                .relatives_nifs
+                .iter()
+                .any(|nif| nif.trim().eq_ignore_ascii_case(examinee.nif.trim()))
+        {
+            return Some(InterestKind::Relative);
+        }
+        if vigilant.academic_centre_id.is_none()
+            || vigilant.academic_centre_id != examinee.academic_centre_id
+        {
+            return None;
+        }
+        if self.subject_teacher
+            && vigilant
+                .specialties_ids
+                .iter()
+                .any(|subject| examinee.subjects_ids.contains(subject))
+        {
+            return Some(InterestKind::SubjectTeacher);
+        }
+        self.same_centre.then_some(InterestKind::SameCentre)
+    }
+
+    /// If the vigilant can watch a classroom with all the examinees
+    pub fn allows(&self, vigilant: &Vigilant, examinees: &[&Examinee]) -> bool {
+        examinees
+            .iter()
+            .all(|examinee| self.conflict(vigilant, examinee).is_none())
+    }
+}
+
+/// A vigilant watching the classroom of an examinee it should not watch
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct InterestConflict {
+    pub subject: EntityId,
+    pub classroom: EntityId,
+    pub vigilant: EntityId,
+    pub examinee: EntityId,
+    pub kind: InterestKind,
+}
+
+/// Lists the conflicts of interest of the current assignment, by subject and classroom. Empty if
+/// there is no assignment
+pub fn interest_conflicts(values: &AppValues, rules: &InterestRules) -> Vec<InterestConflict> {
+    let Some(assignation) = &values.assignation else {
+        return Vec::new();
+    };
+    let vigilants: HashMap<&EntityId, &Vigilant> = values
+        .vigilants
+        .iter()
+        .map(|vigilant| (vigilant.id(), vigilant))
+        .collect();
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for exam in assignation.individual_exams() {
+        let Some(distribution) = &exam.distribution else {
+            continue;
+        };
+        for used in &distribution.distribution {
+            for vigilant in used.vigilants.iter().filter_map(|id| vigilants.get(id)) {
+                for examinee in used.examinees.iter().filter_map(|id| examinees.get(id)) {
+                    if let Some(kind) = rules.conflict(vigilant, examinee) {
+                        conflicts.push(InterestConflict {
+                            subject: exam.subject.clone(),
+                            classroom: used.classroom.clone(),
+                            vigilant: vigilant.id.clone(),
+                            examinee: examinee.id.clone(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
@@ -0,0 +1,547 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::models::{
+    assignation::{AllExamConfiguration, ExamConfiguration},
+    classroom::{Classroom, ClassroomFeature},
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::Vigilant,
+    AppValues, Entity, EntityId,
+};
+
+use colliding::CollidingExamsConfiguration;
+use conflicts::{ConflictResolution, SessionExam};
+use diagnosis::{diagnose, Diagnosis};
+use individual::IndividualExamConfiguration;
+use interest::InterestRules;
+
+pub mod centres;
+mod colliding;
+pub mod conflicts;
+pub mod diagnosis;
+mod individual;
+pub mod interest;
+pub mod optimiser;
+pub mod seating;
+pub mod stability;
+pub mod workload;
+
+#[cfg(test)]
+mod tests;
+
+/// Why the exams of some subjects could not be assigned. The kinds are the ones the frontend
+/// already shows
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AssignmentError {
+    NotEnoughSeats {
+        subject: EntityId,
+    },
+    NotEnoughAccessibleSeats {
+        subject: EntityId,
+    },
+    NotEnoughSeparateRooms {
+        subject: EntityId,
+    },
+    NotEnoughVigilants {
+        subject: EntityId,
+    },
+    /// There would be enough vigilants if some were not out of their availability or duties
+    NotEnoughAvailableVigilants {
+        subject: EntityId,
+    },
+    NoClassrooms,
+    NotEnoughClassrooms {
+        subjects: Vec<EntityId>,
+    },
+    MissingExamDate {
+        subject: EntityId,
+    },
+    MissingSpecialist {
+        subject: EntityId,
+    },
+    /// Some classroom has no vigilant left that does not have a conflict of interest with its
+    /// examinees
+    ConflictOfInterest {
+        subject: EntityId,
+    },
+    /// The classroom chosen for the examinees with overlapping exams can not be used or seat
+    /// all of them
+    NotEnoughSeatsInSharedRoom {
+        subjects: Vec<EntityId>,
+    },
+    /// No vigilant left out of the exams can watch the examinees of the shared classroom
+    NoVigilantForSharedRoom {
+        subjects: Vec<EntityId>,
+    },
+    /// The assignment was asked for a session that is not in the project
+    UnknownSession {
+        session: EntityId,
+    },
+    /// The search for a better assignment stopped before finishing
+    SearchFailed,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct AssignmentResult {
+    pub assignation: AllExamConfiguration,
+    /// Problems of the exams that could not be assigned, they are left empty in the assignation
+    pub errors: Vec<AssignmentError>,
+    /// What is missing for the exams that could not be assigned, only if there are any
+    pub diagnosis: Option<Diagnosis>,
+    /// Exams left for the incompatibility session by the examinees with overlapping exams
+    pub incompatibility_session: Vec<SessionExam>,
+}
+
+/// Distributes the examinees and vigilants among the classrooms for the exam of every subject.
+/// Exams held at the same time share the classrooms and vigilants. Fails only if a subject has
+/// no exam date, otherwise the exams that can not be assigned are left empty and reported.
+/// Examinees enrolled in overlapping exams are placed as the conflict resolution says, and no
+/// vigilant watches a classroom with an examinee the interest rules keep it from watching
+pub fn assign(
+    values: &AppValues,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+) -> Result<AssignmentResult, AssignmentError> {
+    assign_keeping(values, &HashMap::new(), conflicts, interest, 100)
+}
+
+/// Does the assignment again keeping what is locked in the current one: the classrooms locked
+/// for their subjects and the examinees and vigilants locked in their classrooms. Only the rest is
+/// distributed, so enrolments, classrooms and vigilants that changed are taken into account.
+/// What can no longer be kept, like examinees no longer enrolled or classrooms that no longer
+/// suit the subject, is dropped
+pub fn reassign(
+    values: &AppValues,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+) -> Result<AssignmentResult, AssignmentError> {
+    assign_keeping(values, &locked_pins(values), conflicts, interest, 100)
+}
+
+/// Like `reassign`, but no academic centre gets more than `max_centre_share` percent of the
+/// examinees of a classroom while other examinees can take their seats
+fn reassign_spreading_centres(
+    values: &AppValues,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+    max_centre_share: u32,
+) -> Result<AssignmentResult, AssignmentError> {
+    assign_keeping(
+        values,
+        &locked_pins(values),
+        conflicts,
+        interest,
+        max_centre_share,
+    )
+}
+
+fn assign_keeping<'a>(
+    values: &'a AppValues,
+    pins: &HashMap<&EntityId, Vec<Pin<'a>>>,
+    conflicts: &ConflictResolution,
+    interest: &InterestRules,
+    max_centre_share: u32,
+) -> Result<AssignmentResult, AssignmentError> {
+    let mut configurations: Vec<Configuration> =
+        order_and_group_subjects(values.subjects.iter().collect(), &values.examinees)?
+            .into_iter()
+            .map(|subjects| Configuration::new(subjects, pins))
+            .collect();
+
+    let classrooms: Vec<&Classroom> = values.classrooms.iter().collect();
+    let vigilants: Vec<&Vigilant> = values.vigilants.iter().collect();
+    let examinees: Vec<&Examinee> = values.examinees.iter().collect();
+    for configuration in &mut configurations {
+        // The examinees go first, vigilants have to be available until they finish
+        configuration.add_examinees(&examinees);
+        configuration.add_classrooms(&classrooms);
+        configuration.add_vigilants(&vigilants);
+        configuration.set_interest_rules(*interest);
+        configuration.set_max_centre_share(max_centre_share);
+    }
+
+    // Every configuration is a slot, vigilants that did their maximum are not used in the next ones
+    // and examinees are kept in the classroom of their previous exam when it is used again
+    let mut errors = Vec::new();
+    let mut duties = HashMap::<&EntityId, usize>::new();
+    let mut previous_classrooms = HashMap::<&EntityId, &Classroom>::new();
+    let mut incompatibility_session = Vec::new();
+    for configuration in &mut configurations {
+        let exhausted: HashSet<&EntityId> = values
+            .vigilants
+            .iter()
+            .filter(|vigilant| {
+                vigilant.max_duties.is_some_and(|max_duties| {
+                    duties.get(vigilant.id()).copied().unwrap_or(0) >= usize::from(max_duties)
+                })
+            })
+            .map(Entity::id)
+            .collect();
+        configuration.exclude_vigilants(&exhausted);
+        configuration.prefer_classrooms(&previous_classrooms);
+        match configuration.separate_conflicts(conflicts, &classrooms) {
+            Ok(session) => incompatibility_session.extend(session),
+            Err(error) => {
+                configuration.use_empty_assignment();
+                errors.push(error);
+                continue;
+            }
+        }
+
+        let result = configuration.do_assignment();
+        if !result.is_empty() {
+            configuration.use_empty_assignment();
+            errors.extend(result);
+            continue;
+        }
+        for vigilant in configuration.assigned_vigilants() {
+            *duties.entry(vigilant.id()).or_default() += 1;
+        }
+        for (examinee, classroom) in configuration.seated_examinees() {
+            previous_classrooms.insert(examinee.id(), classroom);
+        }
+    }
+
+    Ok(AssignmentResult {
+        assignation: AllExamConfiguration(
+            configurations
+                .into_iter()
+                .map(Configuration::into_configuration)
+                .collect(),
+        ),
+        diagnosis: (!errors.is_empty()).then(|| diagnose(values, conflicts, interest)),
+        errors,
+        incompatibility_session,
+    })
+}
+
+/// Sorts the subjects by the start of their exams and groups the ones that collide, extra time
+/// of the examinees included
+pub fn order_and_group_subjects<'a>(
+    mut subjects: Vec<&'a Subject>,
+    examinees: &[Examinee],
+) -> Result<Vec<Vec<&'a Subject>>, AssignmentError> {
+    if let Some(subject) = subjects
+        .iter()
+        .find(|subject| subject.exam_date.is_none() || subject.exam_duration.is_none())
+    {
+        return Err(AssignmentError::MissingExamDate {
+            subject: subject.id.clone(),
+        });
+    }
+    subjects.sort_by_key(|subject| subject.exam_date);
+
+    // An exam collides with the group if it starts before every exam of the group has ended,
+    // not only the last one that started
+    let mut grouped: Vec<Vec<&Subject>> = Vec::new();
+    let mut group_end = None;
+    for subject in subjects {
+        let end = exam_end(subject, examinees);
+        match grouped.last_mut() {
+            Some(group) if subject.exam_date <= group_end => {
+                group.push(subject);
+                group_end = group_end.max(end);
+            }
+            _ => {
+                grouped.push(vec![subject]);
+                group_end = end;
+            }
+        }
+    }
+    Ok(grouped)
+}
+
+/// From the first exam of the subjects that starts to the last one that ends, extra time of the
+/// examinees included
+fn slot_span(
+    subjects: &[&Subject],
+    examinees: &[Examinee],
+) -> Option<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+    let start = subjects
+        .iter()
+        .filter_map(|subject| subject.exam_date)
+        .min()?;
+    let end = subjects
+        .iter()
+        .filter_map(|subject| exam_end(subject, examinees))
+        .max()?;
+    Some((start, end))
+}
+
+/// When the last examinee enrolled in the subject finishes its exam, extra time included
+fn exam_end(subject: &Subject, examinees: &[Examinee]) -> Option<DateTime<FixedOffset>> {
+    subject.exam_end_for(
+        examinees
+            .iter()
+            .filter(|examinee| examinee.subjects_ids.contains(&subject.id)),
+    )
+}
+
+/// A classroom of the current assignment of a subject with what is locked in it
+#[derive(Clone)]
+struct Pin<'a> {
+    classroom: &'a Classroom,
+    /// The classroom is kept for the subject
+    locked: bool,
+    examinees: Vec<&'a Examinee>,
+    vigilants: Vec<&'a Vigilant>,
+}
+
+/// What is locked in the current assignation, by subject
+fn locked_pins(values: &AppValues) -> HashMap<&EntityId, Vec<Pin<'_>>> {
+    let classrooms: HashMap<&EntityId, &Classroom> = values
+        .classrooms
+        .iter()
+        .map(|classroom| (classroom.id(), classroom))
+        .collect();
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+    let vigilants: HashMap<&EntityId, &Vigilant> = values
+        .vigilants
+        .iter()
+        .map(|vigilant| (vigilant.id(), vigilant))
+        .collect();
+
+    let mut pins = HashMap::<&EntityId, Vec<Pin>>::new();
+    let Some(assignation) = &values.assignation else {
+        return pins;
+    };
+    for exam in assignation.individual_exams() {
+        let Some(distribution) = &exam.distribution else {
+            continue;
+        };
+        for used in distribution
+            .distribution
+            .iter()
+            .filter(|used| used.is_pinned())
+        {
+            let Some(classroom) = classrooms.get(&used.classroom) else {
+                continue;
+            };
+            pins.entry(&exam.subject).or_default().push(Pin {
+                classroom,
+                locked: used.locked,
+                examinees: used
+                    .locked_examinees
+                    .iter()
+                    .filter_map(|id| examinees.get(id).copied())
+                    .collect(),
+                vigilants: used
+                    .locked_vigilants
+                    .iter()
+                    .filter_map(|id| vigilants.get(id).copied())
+                    .collect(),
+            });
+        }
+    }
+    pins
+}
+
+enum Configuration<'a> {
+    Individual(IndividualExamConfiguration<'a>),
+    Colliding(CollidingExamsConfiguration<'a>),
+}
+
+impl<'a> Configuration<'a> {
+    fn new(mut subjects: Vec<&'a Subject>, pins: &HashMap<&EntityId, Vec<Pin<'a>>>) -> Self {
+        let pins_of = |subject: &Subject| pins.get(&subject.id).cloned().unwrap_or_default();
+        if subjects.len() == 1 {
+            let subject = subjects.remove(0);
+            Configuration::Individual(IndividualExamConfiguration::new(subject, pins_of(subject)))
+        } else {
+            Configuration::Colliding(CollidingExamsConfiguration::new(
+                subjects
+                    .into_iter()
+                    .map(|subject| (subject, pins_of(subject)))
+                    .collect(),
+            ))
+        }
+    }
+
+    fn add_examinees(&mut self, examinees: &[&'a Examinee]) {
+        match self {
+            Configuration::Individual(exam) => exam.add_examinees(examinees),
+            Configuration::Colliding(exams) => exams.add_examinees(examinees),
+        }
+    }
+
+    fn add_classrooms(&mut self, classrooms: &[&'a Classroom]) {
+        match self {
+            Configuration::Individual(exam) => exam.add_classrooms(classrooms),
+            Configuration::Colliding(exams) => exams.add_classrooms(classrooms),
+        }
+    }
+
+    fn add_vigilants(&mut self, vigilants: &[&'a Vigilant]) {
+        match self {
+            Configuration::Individual(exam) => exam.add_vigilants(vigilants),
+            Configuration::Colliding(exams) => exams.add_vigilants(vigilants),
+        }
+    }
+
+    fn exclude_vigilants(&mut self, excluded: &HashSet<&EntityId>) {
+        match self {
+            Configuration::Individual(exam) => exam.exclude_vigilants(excluded),
+            Configuration::Colliding(exams) => exams.exclude_vigilants(excluded),
+        }
+    }
+
+    fn prefer_classrooms(&mut self, previous: &HashMap<&'a EntityId, &'a Classroom>) {
+        match self {
+            Configuration::Individual(exam) => exam.prefer_classrooms(previous),
+            Configuration::Colliding(exams) => exams.prefer_classrooms(previous),
+        }
+    }
+
+    fn set_interest_rules(&mut self, interest: InterestRules) {
+        match self {
+            Configuration::Individual(exam) => exam.set_interest_rules(interest),
+            Configuration::Colliding(exams) => exams.set_interest_rules(interest),
+        }
+    }
+
+    fn set_max_centre_share(&mut self, max_share: u32) {
+        match self {
+            Configuration::Individual(exam) => exam.set_max_centre_share(max_share),
+            Configuration::Colliding(exams) => exams.set_max_centre_share(max_share),
+        }
+    }
+
+    fn seated_examinees(&self) -> Vec<(&'a Examinee, &'a Classroom)> {
+        match self {
+            Configuration::Individual(exam) => exam.seated_examinees(),
+            Configuration::Colliding(exams) => exams.seated_examinees(),
+        }
+    }
+
+    fn separate_conflicts(
+        &mut self,
+        resolution: &ConflictResolution,
+        classrooms: &[&'a Classroom],
+    ) -> Result<Vec<SessionExam>, AssignmentError> {
+        match self {
+            // A single exam can not overlap with another
+            Configuration::Individual(_) => Ok(Vec::new()),
+            Configuration::Colliding(exams) => exams.separate_conflicts(resolution, classrooms),
+        }
+    }
+
+    fn do_assignment(&mut self) -> Vec<AssignmentError> {
+        match self {
+            Configuration::Individual(exam) => exam.do_assignment(),
+            Configuration::Colliding(exams) => exams.do_assignment(),
+        }
+    }
+
+    fn use_empty_assignment(&mut self) {
+        match self {
+            Configuration::Individual(exam) => exam.use_empty_assignment(),
+            Configuration::Colliding(exams) => exams.use_empty_assignment(),
+        }
+    }
+
+    fn assigned_vigilants(&self) -> Vec<&'a Vigilant> {
+        match self {
+            Configuration::Individual(exam) => exam.assigned_vigilants(),
+            Configuration::Colliding(exams) => exams.assigned_vigilants(),
+        }
+    }
+
+    fn into_configuration(self) -> ExamConfiguration {
+        match self {
+            Configuration::Individual(exam) => ExamConfiguration::IndividualExam(exam.into_exam()),
+            Configuration::Colliding(exams) => exams.into_configuration(),
+        }
+    }
+}
+
+/// People that are listed by their surenames and then their names
+trait Named {
+    fn surenames(&self) -> &str;
+    fn name(&self) -> &str;
+}
+
+impl Named for Examinee {
+    fn surenames(&self) -> &str {
+        &self.surenames
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for Vigilant {
+    fn surenames(&self) -> &str {
+        &self.surenames
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn by_name<T: Named>(a: &&T, b: &&T) -> Ordering {
+    a.surenames()
+        .to_lowercase()
+        .cmp(&b.surenames().to_lowercase())
+        .then_with(|| a.name().to_lowercase().cmp(&b.name().to_lowercase()))
+}
+
+/// If the entity itself is in the list
+fn contains<T>(list: &[&T], entity: &T) -> bool {
+    list.iter().any(|listed| std::ptr::eq(*listed, entity))
+}
+
+/// Adds the entity if it is not already in the list
+fn push_unique<'a, T>(list: &mut Vec<&'a T>, entity: &'a T) {
+    if !contains(list, entity) {
+        list.push(entity);
+    }
+}
+
+fn ids<T: Entity>(entities: &[&T]) -> Vec<EntityId> {
+    entities.iter().map(|entity| entity.id().clone()).collect()
+}
+
+fn is_accessible(classroom: &Classroom) -> bool {
+    classroom
+        .features
+        .contains(&ClassroomFeature::WheelchairAccess)
+}
+
+/// Index of the entry, given as examinees and vigilants, with the most examinees for each
+/// vigilant. On ties, `prefer_current` is asked if the entry being looked at should replace the
+/// highest one found so far
+fn highest_examinee_to_vigilant_ratio(
+    ratios: &[(usize, usize)],
+    prefer_current: impl Fn(usize, usize) -> bool,
+) -> Option<usize> {
+    let mut highest: Option<usize> = None;
+    for (index, &(examinees, vigilants)) in ratios.iter().enumerate() {
+        let Some(best) = highest else {
+            highest = Some(index);
+            continue;
+        };
+        let (best_examinees, best_vigilants) = ratios[best];
+        match (examinees * best_vigilants).cmp(&(best_examinees * vigilants)) {
+            Ordering::Greater => highest = Some(index),
+            Ordering::Equal if prefer_current(best, index) => highest = Some(index),
+            _ => {}
+        }
+    }
+    highest
+}
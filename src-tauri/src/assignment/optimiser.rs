@@ -0,0 +1,1129 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, FixedOffset};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::{
+    assignation::{
+        AllExamConfiguration, ExamClassroomDistribution, ExamConfiguration, IndividualExam,
+    },
+    classroom::Classroom,
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    AppValues, EntityId,
+};
+
+use super::{
+    by_name,
+    conflicts::{ConflictResolution, SessionExam},
+    diagnosis::Diagnosis,
+    interest::InterestRules,
+    is_accessible, reassign_spreading_centres, slot_span, AssignmentError,
+};
+
+/// Iterations without finding a better assignment after which the search is considered settled
+const STALL_ITERATIONS: u64 = 1_000_000;
+/// Longest the search runs for, whatever time budget it is given
+const MAX_TIME_BUDGET: u32 = 60_000;
+
+#[derive(Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct OptimisationSettings {
+    /// Milliseconds the search can run for, at most a minute
+    pub time_budget: u32,
+    pub rooms_weight: u32,
+    pub overload_weight: u32,
+    pub room_changes_weight: u32,
+    /// Highest percentage of the examinees of a classroom that should come from the same
+    /// academic centre, 100 means there is no limit
+    pub max_centre_share: u32,
+    pub centre_weight: u32,
+    /// How the assignment the search starts from places the examinees with overlapping exams
+    pub conflicts: ConflictResolution,
+    /// Which vigilants can not watch which examinees, no move breaks them
+    pub interest: InterestRules,
+    /// Makes the search repeatable, a random one is used if there is none
+    #[ts(optional)]
+    pub seed: Option<u32>,
+}
+
+impl Default for OptimisationSettings {
+    fn default() -> Self {
+        OptimisationSettings {
+            time_budget: 2000,
+            rooms_weight: 100,
+            overload_weight: 1,
+            room_changes_weight: 10,
+            max_centre_share: 100,
+            centre_weight: 10,
+            conflicts: ConflictResolution::default(),
+            interest: InterestRules::default(),
+            seed: None,
+        }
+    }
+}
+
+/// What the optimiser minimises, each part is multiplied by its weight in the settings
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct AssignmentCost {
+    /// Classrooms with examinees in every exam
+    pub rooms_used: usize,
+    /// Sum over the classrooms of the examinees squared divided by the vigilants, it grows
+    /// quickly when few vigilants watch many examinees
+    pub overload: f64,
+    /// Times an examinee has to change classroom between one exam and the next one
+    pub room_changes: usize,
+    /// Examinees above the maximum share of their academic centre in their classrooms
+    pub centre_excess: usize,
+}
+
+impl AssignmentCost {
+    fn weighted(&self, settings: &OptimisationSettings) -> f64 {
+        self.rooms_used as f64 * f64::from(settings.rooms_weight)
+            + self.overload * f64::from(settings.overload_weight)
+            + self.room_changes as f64 * f64::from(settings.room_changes_weight)
+            + self.centre_excess as f64 * f64::from(settings.centre_weight)
+    }
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct OptimisationResult {
+    pub assignation: AllExamConfiguration,
+    /// Problems of the exams that could not be assigned, these are not optimised
+    pub errors: Vec<AssignmentError>,
+    pub diagnosis: Option<Diagnosis>,
+    pub incompatibility_session: Vec<SessionExam>,
+    /// Cost of the assignment the search started from
+    pub initial_cost: AssignmentCost,
+    pub cost: AssignmentCost,
+    /// The time budget ran out before the search settled, a better assignment could exist
+    pub timed_out: bool,
+}
+
+/// Improves the default assignment with a local search that moves examinees and vigilants
+/// between classrooms, keeping every constraint of the default one: capacities, classrooms that
+/// suit the subject, accommodations, one exam per classroom at a time, at least a vigilant per
+/// classroom used, the availability and maximum duties of the vigilants and their conflicts of
+/// interest with the examinees. The default assignment already keeps the academic centres under
+/// their maximum share where the order of the examinees allows it. What is locked in the
+/// current assignation is kept, the search only moves the rest. The best assignment found is
+/// returned when the time budget runs out
+pub fn optimise(
+    values: &AppValues,
+    settings: &OptimisationSettings,
+) -> Result<OptimisationResult, AssignmentError> {
+    let mut result = reassign_spreading_centres(
+        values,
+        &settings.conflicts,
+        &settings.interest,
+        settings.max_centre_share,
+    )?;
+    let problem = Problem::new(values, &result.assignation, settings);
+    let mut state = problem.initial_state(&result.assignation);
+    let initial_cost = problem.evaluate(&state);
+
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed.into()),
+        None => StdRng::from_entropy(),
+    };
+    let timed_out = problem.search(&mut state, &mut rng);
+
+    problem.write(&state, &mut result.assignation);
+    Ok(OptimisationResult {
+        cost: problem.evaluate(&state),
+        assignation: result.assignation,
+        errors: result.errors,
+        diagnosis: result.diagnosis,
+        incompatibility_session: result.incompatibility_session,
+        initial_cost,
+        timed_out,
+    })
+}
+
+/// The exams that can be optimised, the ones of time slots where the default assignment worked
+struct Problem<'a> {
+    settings: &'a OptimisationSettings,
+    examinees: Vec<&'a Examinee>,
+    /// Academic centre of every examinee, as an index
+    centres: Vec<Option<usize>>,
+    classrooms: Vec<&'a Classroom>,
+    vigilants: Vec<&'a Vigilant>,
+    /// Vigilants and examinees, as indexes, with a conflict of interest
+    conflicting: HashSet<(usize, usize)>,
+    exams: Vec<Exam>,
+    slots: Vec<Slot>,
+    /// Exams with examinees, the ones where moves are tried
+    movable: Vec<usize>,
+    /// The exams of every examinee, as exam and position in it, in the order they are held
+    schedules: Vec<Vec<(usize, usize)>>,
+}
+
+struct Exam {
+    slot: usize,
+    /// Configuration of the assignation and position among its exams
+    configuration: usize,
+    inner: usize,
+    start: DateTime<FixedOffset>,
+    examinees: Vec<usize>,
+    /// Classrooms that suit the subject
+    candidates: Vec<usize>,
+    /// The default assignment fitted in the exam capacity, so it is kept as the limit
+    use_exam_capacity: bool,
+    specialists: Vec<usize>,
+    /// Examinees, by position, that are locked in their classroom
+    pinned: Vec<bool>,
+    /// Classrooms locked for the exam, they are not left empty
+    locked: Vec<usize>,
+}
+
+/// Exams held at the same time, they can not share classrooms nor vigilants
+struct Slot {
+    /// Vigilants that can watch a classroom during the slot
+    pool: Vec<usize>,
+    /// Vigilants that are locked in their classroom
+    pinned: Vec<usize>,
+    /// Classrooms locked for an exam, as classroom and exam, no other exam can use them
+    locked: Vec<(usize, usize)>,
+}
+
+#[derive(Clone)]
+struct State {
+    /// Classroom of every examinee of every exam
+    seats: Vec<Vec<usize>>,
+    /// Every classroom during every slot
+    rooms: Vec<Vec<Room>>,
+    /// Classroom watched by every vigilant during every slot
+    guards: Vec<Vec<Option<usize>>>,
+    duties: Vec<usize>,
+}
+
+#[derive(Clone, Default)]
+struct Room {
+    exam: Option<usize>,
+    examinees: usize,
+    /// It has an examinee that takes the exam alone
+    alone: bool,
+    vigilants: Vec<usize>,
+}
+
+#[derive(Clone, Copy)]
+enum Change {
+    Seat {
+        exam: usize,
+        position: usize,
+        from: usize,
+        to: usize,
+    },
+    Guard {
+        slot: usize,
+        vigilant: usize,
+        from: Option<usize>,
+        to: Option<usize>,
+    },
+}
+
+impl Change {
+    fn inverse(self) -> Change {
+        match self {
+            Change::Seat {
+                exam,
+                position,
+                from,
+                to,
+            } => Change::Seat {
+                exam,
+                position,
+                from: to,
+                to: from,
+            },
+            Change::Guard {
+                slot,
+                vigilant,
+                from,
+                to,
+            } => Change::Guard {
+                slot,
+                vigilant,
+                from: to,
+                to: from,
+            },
+        }
+    }
+}
+
+fn index_of<'a, T>(
+    entities: &'a [T],
+    id: impl Fn(&'a T) -> &'a EntityId,
+) -> HashMap<&'a EntityId, usize> {
+    entities
+        .iter()
+        .enumerate()
+        .map(|(index, entity)| (id(entity), index))
+        .collect()
+}
+
+impl<'a> Problem<'a> {
+    fn new(
+        values: &'a AppValues,
+        assignation: &AllExamConfiguration,
+        settings: &'a OptimisationSettings,
+    ) -> Self {
+        let examinee_index = index_of(&values.examinees, |examinee| &examinee.id);
+        let classroom_index = index_of(&values.classrooms, |classroom| &classroom.id);
+        let vigilant_index = index_of(&values.vigilants, |vigilant| &vigilant.id);
+        let subjects = index_of(&values.subjects, |subject| &subject.id);
+
+        let mut exams = Vec::new();
+        let mut slots = Vec::new();
+        for (configuration, exam_configuration) in assignation.0.iter().enumerate() {
+            let individual_exams = exam_configuration.exams();
+            // Slots where the default assignment failed are left empty
+            let assigned = individual_exams.iter().all(|exam| {
+                exam.distribution.as_ref().is_some_and(|distribution| {
+                    distribution
+                        .distribution
+                        .iter()
+                        .map(|classroom| classroom.examinees.len())
+                        .sum::<usize>()
+                        == exam.examinees.len()
+                })
+            });
+            if !assigned {
+                continue;
+            }
+            // Slots with a classroom shared by examinees with overlapping exams are kept as they
+            // are, every classroom here holds a single exam
+            let mut used: Vec<&EntityId> = individual_exams
+                .iter()
+                .filter_map(|exam| exam.distribution.as_ref())
+                .flat_map(|distribution| &distribution.distribution)
+                .map(|classroom| &classroom.classroom)
+                .collect();
+            let total = used.len();
+            used.sort_unstable();
+            used.dedup();
+            if used.len() < total {
+                continue;
+            }
+
+            let slot = slots.len();
+            let mut slot_exams = Vec::new();
+            let mut specialists = Vec::new();
+            let mut pinned_vigilants = Vec::new();
+            // Vigilants stay until the last examinee of the slot finishes, extra time included
+            let slot_subjects: Vec<&Subject> = individual_exams
+                .iter()
+                .map(|exam| &values.subjects[subjects[&exam.subject]])
+                .collect();
+            let span = slot_span(&slot_subjects, &values.examinees);
+            for (inner, exam) in individual_exams.iter().enumerate() {
+                let subject = slot_subjects[inner];
+                let Some(start) = subject.exam_date else {
+                    continue;
+                };
+                let distribution = exam.distribution.as_ref().unwrap();
+                let exam_specialists: Vec<usize> = distribution
+                    .specialists
+                    .iter()
+                    .map(|id| vigilant_index[id])
+                    .collect();
+                specialists.extend(&exam_specialists);
+                pinned_vigilants.extend(
+                    distribution
+                        .distribution
+                        .iter()
+                        .flat_map(|used| &used.locked_vigilants)
+                        .map(|id| vigilant_index[id]),
+                );
+                slot_exams.push(exams.len());
+                exams.push(Exam {
+                    slot,
+                    configuration,
+                    inner,
+                    start,
+                    examinees: exam.examinees.iter().map(|id| examinee_index[id]).collect(),
+                    candidates: values
+                        .classrooms
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, classroom)| {
+                            (classroom.court_location_id.is_none() && classroom.suits(subject))
+                                || distribution
+                                    .distribution
+                                    .iter()
+                                    .any(|used| used.classroom == classroom.id)
+                        })
+                        .map(|(index, _)| index)
+                        .collect(),
+                    use_exam_capacity: distribution.distribution.iter().all(|used| {
+                        used.examinees.len()
+                            <= values.classrooms[classroom_index[&used.classroom]].exam_capacity
+                                as usize
+                    }),
+                    specialists: exam_specialists,
+                    pinned: exam
+                        .examinees
+                        .iter()
+                        .map(|examinee| {
+                            distribution
+                                .distribution
+                                .iter()
+                                .any(|used| used.locked_examinees.contains(examinee))
+                        })
+                        .collect(),
+                    locked: distribution
+                        .distribution
+                        .iter()
+                        .filter(|used| used.locked)
+                        .map(|used| classroom_index[&used.classroom])
+                        .collect(),
+                });
+            }
+
+            let mut pool: Vec<usize> = values
+                .vigilants
+                .iter()
+                .enumerate()
+                .filter(|(index, vigilant)| {
+                    vigilant.role == VigilantRole::MEMBER
+                        && !specialists.contains(index)
+                        && span.map_or(true, |(start, end)| vigilant.is_available(start, end))
+                })
+                .map(|(index, _)| index)
+                .collect();
+            for exam in &slot_exams {
+                let exam = exam_of(assignation, &exams[*exam]);
+                for classroom in &exam.distribution.as_ref().unwrap().distribution {
+                    for vigilant in &classroom.vigilants {
+                        if !pool.contains(&vigilant_index[vigilant]) {
+                            pool.push(vigilant_index[vigilant]);
+                        }
+                    }
+                }
+            }
+            let locked = slot_exams
+                .iter()
+                .flat_map(|exam| {
+                    exams[*exam]
+                        .locked
+                        .iter()
+                        .map(|classroom| (*classroom, *exam))
+                })
+                .collect();
+            slots.push(Slot {
+                pool,
+                pinned: pinned_vigilants,
+                locked,
+            });
+        }
+
+        let mut schedules = vec![Vec::new(); values.examinees.len()];
+        for (index, exam) in exams.iter().enumerate() {
+            for (position, examinee) in exam.examinees.iter().enumerate() {
+                schedules[*examinee].push((index, position));
+            }
+        }
+        for schedule in &mut schedules {
+            schedule.sort_by_key(|(exam, _)| exams[*exam].start);
+        }
+
+        let centre_index = index_of(&values.academic_centres, |centre| &centre.id);
+        Problem {
+            settings,
+            examinees: values.examinees.iter().collect(),
+            centres: values
+                .examinees
+                .iter()
+                .map(|examinee| {
+                    examinee
+                        .academic_centre_id
+                        .as_ref()
+                        .and_then(|centre| centre_index.get(centre).copied())
+                })
+                .collect(),
+            classrooms: values.classrooms.iter().collect(),
+            vigilants: values.vigilants.iter().collect(),
+            conflicting: values
+                .vigilants
+                .iter()
+                .enumerate()
+                .flat_map(|(vigilant_index, vigilant)| {
+                    values
+                        .examinees
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, examinee)| {
+                            settings.interest.conflict(vigilant, examinee).is_some()
+                        })
+                        .map(move |(examinee_index, _)| (vigilant_index, examinee_index))
+                })
+                .collect(),
+            movable: (0..exams.len())
+                .filter(|exam| !exams[*exam].examinees.is_empty())
+                .collect(),
+            exams,
+            slots,
+            schedules,
+        }
+    }
+
+    fn initial_state(&self, assignation: &AllExamConfiguration) -> State {
+        let classroom_index = index_of(&self.classrooms, |classroom| &classroom.id);
+        let vigilant_index = index_of(&self.vigilants, |vigilant| &vigilant.id);
+        let mut state = State {
+            seats: Vec::with_capacity(self.exams.len()),
+            rooms: vec![vec![Room::default(); self.classrooms.len()]; self.slots.len()],
+            guards: vec![vec![None; self.vigilants.len()]; self.slots.len()],
+            duties: vec![0; self.vigilants.len()],
+        };
+        for (index, exam) in self.exams.iter().enumerate() {
+            let individual = exam_of(assignation, exam);
+            let distribution = individual.distribution.as_ref().unwrap();
+            let mut seats = vec![0; exam.examinees.len()];
+            for used in &distribution.distribution {
+                let classroom = classroom_index[&used.classroom];
+                let room = &mut state.rooms[exam.slot][classroom];
+                for examinee in &used.examinees {
+                    if let Some(position) = individual.examinees.iter().position(|e| e == examinee)
+                    {
+                        seats[position] = classroom;
+                        room.exam = Some(index);
+                        room.examinees += 1;
+                        room.alone |= self.examinees[exam.examinees[position]]
+                            .accommodations
+                            .separate_room;
+                    }
+                }
+                for vigilant in &used.vigilants {
+                    let vigilant = vigilant_index[vigilant];
+                    room.vigilants.push(vigilant);
+                    state.guards[exam.slot][vigilant] = Some(classroom);
+                }
+            }
+            state.seats.push(seats);
+        }
+
+        // Duties are counted in every slot, also the ones left out of the search, so no move
+        // takes a vigilant over its maximum
+        for configuration in &assignation.0 {
+            let mut on_duty: Vec<usize> = configuration
+                .exams()
+                .iter()
+                .filter_map(|exam| exam.distribution.as_ref())
+                .flat_map(|distribution| {
+                    distribution.specialists.iter().chain(
+                        distribution
+                            .distribution
+                            .iter()
+                            .flat_map(|used| &used.vigilants),
+                    )
+                })
+                .filter_map(|vigilant| vigilant_index.get(vigilant).copied())
+                .collect();
+            on_duty.sort_unstable();
+            on_duty.dedup();
+            for vigilant in on_duty {
+                state.duties[vigilant] += 1;
+            }
+        }
+        state
+    }
+
+    /// Runs the search until the time budget runs out or it settles, leaving the best
+    /// assignment found in the state. Returns if the time budget ran out
+    fn search(&self, state: &mut State, rng: &mut StdRng) -> bool {
+        if self.movable.is_empty() {
+            return false;
+        }
+        let budget = Duration::from_millis(self.settings.time_budget.min(MAX_TIME_BUDGET).into());
+        let initial_temperature = f64::from(self.settings.rooms_weight.max(1));
+        let started = Instant::now();
+        let mut current = self.evaluate(state).weighted(self.settings);
+        let mut best = (state.clone(), current);
+        let mut last_improvement = 0;
+        let mut progress = 0.0;
+        let mut iteration: u64 = 0;
+        loop {
+            if iteration % 256 == 0 {
+                let elapsed = started.elapsed();
+                if elapsed >= budget {
+                    break;
+                }
+                progress = elapsed.as_secs_f64() / budget.as_secs_f64();
+            }
+            if iteration - last_improvement > STALL_ITERATIONS {
+                *state = best.0;
+                return false;
+            }
+            iteration += 1;
+
+            let Some(changes) = self.propose(state, rng) else {
+                continue;
+            };
+            let before = self.touched_cost(state, &changes);
+            let conflicts_before = self.touched_interest_conflicts(state, &changes);
+            for change in &changes {
+                state.apply(self, *change);
+            }
+            let delta = self.touched_cost(state, &changes) - before;
+            let temperature = initial_temperature * (1.0 - progress).powi(2);
+            // Moves that put vigilants with examinees they have a conflict of interest with are
+            // never taken
+            if self.touched_interest_conflicts(state, &changes) <= conflicts_before
+                && (delta <= 0.0 || rng.gen::<f64>() < (-delta / temperature).exp())
+            {
+                current += delta;
+                if current < best.1 - 1e-9 {
+                    best = (state.clone(), current);
+                    last_improvement = iteration;
+                }
+            } else {
+                for change in changes.iter().rev() {
+                    state.apply(self, change.inverse());
+                }
+            }
+        }
+        *state = best.0;
+        true
+    }
+
+    fn propose(&self, state: &State, rng: &mut StdRng) -> Option<Vec<Change>> {
+        let exam = *self.movable.choose(rng)?;
+        match rng.gen_range(0..10) {
+            0..=4 => self.relocate_examinee(state, exam, rng),
+            5 | 6 => self.swap_examinees(state, exam, rng),
+            7 => self.relocate_vigilant(state, self.exams[exam].slot, rng),
+            8 => self.exchange_vigilant(state, self.exams[exam].slot, rng),
+            _ => self.add_vigilant(state, self.exams[exam].slot, rng),
+        }
+    }
+
+    /// Moves an examinee to another classroom, taking a vigilant there if it was empty
+    fn relocate_examinee(
+        &self,
+        state: &State,
+        exam: usize,
+        rng: &mut StdRng,
+    ) -> Option<Vec<Change>> {
+        let slot = self.exams[exam].slot;
+        let position = rng.gen_range(0..self.exams[exam].examinees.len());
+        let from = state.seats[exam][position];
+        let to = *self.exams[exam].candidates.choose(rng)?;
+        if from == to
+            || self.exams[exam].pinned[position]
+            || !self.can_sit(state, exam, position, to)
+        {
+            return None;
+        }
+        let rooms = &state.rooms[slot];
+        if rooms[from].examinees == 1
+            && (self.exams[exam].locked.contains(&from)
+                || rooms[from]
+                    .vigilants
+                    .iter()
+                    .any(|vigilant| self.is_pinned(slot, *vigilant)))
+        {
+            // A locked classroom is not left empty and locked vigilants do not follow
+            return None;
+        }
+        let mut changes = vec![Change::Seat {
+            exam,
+            position,
+            from,
+            to,
+        }];
+        if rooms[from].examinees == 1 {
+            // The vigilants follow the last examinee
+            changes.extend(rooms[from].vigilants.iter().map(|vigilant| Change::Guard {
+                slot,
+                vigilant: *vigilant,
+                from: Some(from),
+                to: Some(to),
+            }));
+        } else if rooms[to].examinees == 0 {
+            let (vigilant, guarding) = self.spare_vigilant(state, slot, from, rng)?;
+            changes.push(Change::Guard {
+                slot,
+                vigilant,
+                from: guarding,
+                to: Some(to),
+            });
+        }
+        Some(changes)
+    }
+
+    fn swap_examinees(&self, state: &State, exam: usize, rng: &mut StdRng) -> Option<Vec<Change>> {
+        let examinees = &self.exams[exam].examinees;
+        let first = rng.gen_range(0..examinees.len());
+        let second = rng.gen_range(0..examinees.len());
+        let (from, to) = (state.seats[exam][first], state.seats[exam][second]);
+        let pinned = &self.exams[exam].pinned;
+        if from == to || pinned[first] || pinned[second] {
+            return None;
+        }
+        for (position, classroom) in [(first, to), (second, from)] {
+            let accommodations = &self.examinees[examinees[position]].accommodations;
+            if accommodations.separate_room
+                || accommodations.accessible_room && !is_accessible(self.classrooms[classroom])
+            {
+                return None;
+            }
+        }
+        Some(vec![
+            Change::Seat {
+                exam,
+                position: first,
+                from,
+                to,
+            },
+            Change::Seat {
+                exam,
+                position: second,
+                from: to,
+                to: from,
+            },
+        ])
+    }
+
+    fn relocate_vigilant(
+        &self,
+        state: &State,
+        slot: usize,
+        rng: &mut StdRng,
+    ) -> Option<Vec<Change>> {
+        let rooms = &state.rooms[slot];
+        let from = *occupied(rooms)
+            .filter(|classroom| rooms[*classroom].vigilants.len() > 1)
+            .collect::<Vec<_>>()
+            .choose(rng)?;
+        let to = *occupied(rooms)
+            .filter(|classroom| *classroom != from)
+            .collect::<Vec<_>>()
+            .choose(rng)?;
+        let vigilant = *rooms[from].vigilants.choose(rng)?;
+        if self.is_pinned(slot, vigilant) {
+            return None;
+        }
+        Some(vec![Change::Guard {
+            slot,
+            vigilant,
+            from: Some(from),
+            to: Some(to),
+        }])
+    }
+
+    /// Replaces a vigilant with one that is not watching any classroom
+    fn exchange_vigilant(
+        &self,
+        state: &State,
+        slot: usize,
+        rng: &mut StdRng,
+    ) -> Option<Vec<Change>> {
+        let rooms = &state.rooms[slot];
+        let classroom = *occupied(rooms).collect::<Vec<_>>().choose(rng)?;
+        let leaving = *rooms[classroom].vigilants.choose(rng)?;
+        if self.is_pinned(slot, leaving) {
+            return None;
+        }
+        let entering = *self.free_vigilants(state, slot).choose(rng)?;
+        Some(vec![
+            Change::Guard {
+                slot,
+                vigilant: leaving,
+                from: Some(classroom),
+                to: None,
+            },
+            Change::Guard {
+                slot,
+                vigilant: entering,
+                from: None,
+                to: Some(classroom),
+            },
+        ])
+    }
+
+    fn add_vigilant(&self, state: &State, slot: usize, rng: &mut StdRng) -> Option<Vec<Change>> {
+        let classroom = *occupied(&state.rooms[slot])
+            .collect::<Vec<_>>()
+            .choose(rng)?;
+        Some(vec![Change::Guard {
+            slot,
+            vigilant: *self.free_vigilants(state, slot).choose(rng)?,
+            from: None,
+            to: Some(classroom),
+        }])
+    }
+
+    fn can_sit(&self, state: &State, exam: usize, position: usize, classroom: usize) -> bool {
+        let accommodations = &self.examinees[self.exams[exam].examinees[position]].accommodations;
+        let room = &state.rooms[self.exams[exam].slot][classroom];
+        if accommodations.accessible_room && !is_accessible(self.classrooms[classroom]) {
+            return false;
+        }
+        let slot = &self.slots[self.exams[exam].slot];
+        if room.exam.is_some_and(|other| other != exam)
+            || slot
+                .locked
+                .iter()
+                .any(|&(locked, other)| locked == classroom && other != exam)
+            || room.examinees >= self.capacity(exam, classroom)
+        {
+            return false;
+        }
+        if accommodations.separate_room {
+            room.examinees == 0
+        } else {
+            !room.alone
+        }
+    }
+
+    fn capacity(&self, exam: usize, classroom: usize) -> usize {
+        let classroom = self.classrooms[classroom];
+        if self.exams[exam].use_exam_capacity {
+            classroom.exam_capacity as usize
+        } else {
+            classroom.total_capacity as usize
+        }
+    }
+
+    fn can_take_duty(&self, state: &State, vigilant: usize) -> bool {
+        self.vigilants[vigilant]
+            .max_duties
+            .map_or(true, |max_duties| {
+                state.duties[vigilant] < usize::from(max_duties)
+            })
+    }
+
+    fn free_vigilants(&self, state: &State, slot: usize) -> Vec<usize> {
+        self.slots[slot]
+            .pool
+            .iter()
+            .copied()
+            .filter(|vigilant| {
+                state.guards[slot][*vigilant].is_none() && self.can_take_duty(state, *vigilant)
+            })
+            .collect()
+    }
+
+    /// A vigilant that can go to a classroom that is going to be used, preferably one of the
+    /// classroom the examinee leaves, then one not watching any and then one from a classroom
+    /// with more than one
+    fn spare_vigilant(
+        &self,
+        state: &State,
+        slot: usize,
+        leaving: usize,
+        rng: &mut StdRng,
+    ) -> Option<(usize, Option<usize>)> {
+        let rooms = &state.rooms[slot];
+        let movable = |classroom: usize| -> Vec<usize> {
+            rooms[classroom]
+                .vigilants
+                .iter()
+                .copied()
+                .filter(|vigilant| !self.is_pinned(slot, *vigilant))
+                .collect()
+        };
+        if rooms[leaving].vigilants.len() > 1 {
+            if let Some(vigilant) = movable(leaving).choose(rng) {
+                return Some((*vigilant, Some(leaving)));
+            }
+        }
+        if let Some(vigilant) = self.free_vigilants(state, slot).choose(rng) {
+            return Some((*vigilant, None));
+        }
+        let classroom = *occupied(rooms)
+            .filter(|classroom| rooms[*classroom].vigilants.len() > 1)
+            .collect::<Vec<_>>()
+            .choose(rng)?;
+        Some((*movable(classroom).choose(rng)?, Some(classroom)))
+    }
+
+    fn is_pinned(&self, slot: usize, vigilant: usize) -> bool {
+        self.slots[slot].pinned.contains(&vigilant)
+    }
+
+    /// Weighted cost of the classrooms and examinees affected by the changes
+    fn touched_cost(&self, state: &State, changes: &[Change]) -> f64 {
+        let (rooms, examinees) = self.touched(changes);
+        self.cost_of(state, rooms, examinees)
+            .weighted(self.settings)
+    }
+
+    /// Classrooms, by slot, and examinees affected by the changes
+    fn touched(&self, changes: &[Change]) -> (Vec<(usize, usize)>, Vec<usize>) {
+        let mut rooms = Vec::new();
+        let mut examinees = Vec::new();
+        for change in changes {
+            match *change {
+                Change::Seat {
+                    exam,
+                    position,
+                    from,
+                    to,
+                } => {
+                    let slot = self.exams[exam].slot;
+                    rooms.extend([(slot, from), (slot, to)]);
+                    examinees.push(self.exams[exam].examinees[position]);
+                }
+                Change::Guard { slot, from, to, .. } => rooms.extend(
+                    from.into_iter()
+                        .chain(to)
+                        .map(|classroom| (slot, classroom)),
+                ),
+            }
+        }
+        rooms.sort_unstable();
+        rooms.dedup();
+        examinees.sort_unstable();
+        examinees.dedup();
+        (rooms, examinees)
+    }
+
+    /// Vigilants watching examinees they have a conflict of interest with in the classrooms
+    /// affected by the changes, counted once for every examinee
+    fn touched_interest_conflicts(&self, state: &State, changes: &[Change]) -> usize {
+        if self.conflicting.is_empty() {
+            return 0;
+        }
+        let (rooms, _) = self.touched(changes);
+        let mut conflicts = 0;
+        for (slot, classroom) in rooms {
+            let room = &state.rooms[slot][classroom];
+            let Some(exam) = room.exam else {
+                continue;
+            };
+            for (position, examinee) in self.exams[exam].examinees.iter().enumerate() {
+                if state.seats[exam][position] != classroom {
+                    continue;
+                }
+                conflicts += room
+                    .vigilants
+                    .iter()
+                    .filter(|vigilant| self.conflicting.contains(&(**vigilant, *examinee)))
+                    .count();
+            }
+        }
+        conflicts
+    }
+
+    fn evaluate(&self, state: &State) -> AssignmentCost {
+        let rooms = (0..self.slots.len())
+            .flat_map(|slot| (0..self.classrooms.len()).map(move |classroom| (slot, classroom)));
+        self.cost_of(state, rooms, 0..self.examinees.len())
+    }
+
+    fn cost_of(
+        &self,
+        state: &State,
+        rooms: impl IntoIterator<Item = (usize, usize)>,
+        examinees: impl IntoIterator<Item = usize>,
+    ) -> AssignmentCost {
+        let mut cost = AssignmentCost::default();
+        for (slot, classroom) in rooms {
+            let room = &state.rooms[slot][classroom];
+            if room.examinees > 0 {
+                cost.rooms_used += 1;
+                cost.overload +=
+                    (room.examinees * room.examinees) as f64 / room.vigilants.len().max(1) as f64;
+                cost.centre_excess += self.centre_excess(state, room, classroom);
+            }
+        }
+        for examinee in examinees {
+            cost.room_changes += self.schedules[examinee]
+                .windows(2)
+                .filter(|pair| {
+                    let ((first, first_position), (second, second_position)) = (pair[0], pair[1]);
+                    state.seats[first][first_position] != state.seats[second][second_position]
+                })
+                .count();
+        }
+        cost
+    }
+
+    /// Examinees of the classroom above the share their academic centres can have in it
+    fn centre_excess(&self, state: &State, room: &Room, classroom: usize) -> usize {
+        let max_share = self.settings.max_centre_share as usize;
+        let Some(exam) = room.exam.filter(|_| max_share < 100) else {
+            return 0;
+        };
+        // Rounded down, but a classroom with a single examinee is never above the maximum
+        let allowed = (room.examinees * max_share / 100).max(1);
+        let mut counts = HashMap::<usize, usize>::new();
+        for (position, examinee) in self.exams[exam].examinees.iter().enumerate() {
+            if state.seats[exam][position] != classroom {
+                continue;
+            }
+            if let Some(centre) = self.centres[*examinee] {
+                *counts.entry(centre).or_default() += 1;
+            }
+        }
+        counts
+            .values()
+            .map(|count| count.saturating_sub(allowed))
+            .sum()
+    }
+
+    /// Puts the distribution of the state in the assignation
+    fn write(&self, state: &State, assignation: &mut AllExamConfiguration) {
+        for (index, exam) in self.exams.iter().enumerate() {
+            let rooms = &state.rooms[exam.slot];
+            let mut used: Vec<usize> = (0..rooms.len())
+                .filter(|classroom| {
+                    rooms[*classroom].exam == Some(index) || exam.locked.contains(classroom)
+                })
+                .collect();
+            used.sort_by_key(|classroom| self.classrooms[*classroom].priority);
+            let distribution: Vec<ExamClassroomDistribution> = used
+                .iter()
+                .map(|classroom| {
+                    let mut examinees: Vec<&Examinee> = (0..exam.examinees.len())
+                        .filter(|position| state.seats[index][*position] == *classroom)
+                        .map(|position| self.examinees[exam.examinees[position]])
+                        .collect();
+                    examinees.sort_by(by_name);
+                    let mut vigilants: Vec<&Vigilant> = rooms[*classroom]
+                        .vigilants
+                        .iter()
+                        .map(|vigilant| self.vigilants[*vigilant])
+                        .collect();
+                    vigilants.sort_by(by_name);
+                    let locked_examinees = (0..exam.examinees.len())
+                        .filter(|position| {
+                            exam.pinned[*position] && state.seats[index][*position] == *classroom
+                        })
+                        .map(|position| self.examinees[exam.examinees[position]].id.clone())
+                        .collect();
+                    let locked_vigilants = rooms[*classroom]
+                        .vigilants
+                        .iter()
+                        .filter(|vigilant| self.is_pinned(exam.slot, **vigilant))
+                        .map(|vigilant| self.vigilants[*vigilant].id.clone())
+                        .collect();
+                    ExamClassroomDistribution {
+                        classroom: self.classrooms[*classroom].id.clone(),
+                        examinees: examinees.iter().map(|e| e.id.clone()).collect(),
+                        vigilants: vigilants.iter().map(|v| v.id.clone()).collect(),
+                        locked: exam.locked.contains(classroom),
+                        locked_examinees,
+                        locked_vigilants,
+                        seats: Vec::new(),
+                    }
+                })
+                .collect();
+            let watching: Vec<EntityId> = distribution
+                .iter()
+                .flat_map(|classroom| classroom.vigilants.iter().cloned())
+                .collect();
+
+            let (colliding_vigilants, individual) = exams_of_mut(assignation, exam);
+            if let Some(vigilants) = colliding_vigilants {
+                push_all(vigilants, &watching);
+                individual.classrooms = distribution
+                    .iter()
+                    .map(|classroom| classroom.classroom.clone())
+                    .collect();
+                individual.vigilants = exam
+                    .specialists
+                    .iter()
+                    .map(|specialist| self.vigilants[*specialist].id.clone())
+                    .chain(watching)
+                    .collect();
+            } else {
+                push_all(&mut individual.vigilants, &watching);
+            }
+            if let Some(exam_distribution) = &mut individual.distribution {
+                exam_distribution.distribution = distribution;
+            }
+        }
+    }
+}
+
+fn occupied(rooms: &[Room]) -> impl Iterator<Item = usize> + '_ {
+    (0..rooms.len()).filter(|classroom| rooms[*classroom].examinees > 0)
+}
+
+fn push_all(ids: &mut Vec<EntityId>, new: &[EntityId]) {
+    for id in new {
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+}
+
+fn exam_of<'b>(assignation: &'b AllExamConfiguration, exam: &Exam) -> &'b IndividualExam {
+    &assignation.0[exam.configuration].exams()[exam.inner]
+}
+
+fn exams_of_mut<'b>(
+    assignation: &'b mut AllExamConfiguration,
+    exam: &Exam,
+) -> (Option<&'b mut Vec<EntityId>>, &'b mut IndividualExam) {
+    match &mut assignation.0[exam.configuration] {
+        ExamConfiguration::IndividualExam(individual) => (None, individual),
+        ExamConfiguration::CollidingExams {
+            exams, vigilants, ..
+        } => (Some(vigilants), &mut exams[exam.inner]),
+    }
+}
+
+impl State {
+    fn apply(&mut self, problem: &Problem, change: Change) {
+        match change {
+            Change::Seat {
+                exam,
+                position,
+                from,
+                to,
+            } => {
+                let slot = problem.exams[exam].slot;
+                let separate = problem.examinees[problem.exams[exam].examinees[position]]
+                    .accommodations
+                    .separate_room;
+                let room = &mut self.rooms[slot][from];
+                room.examinees -= 1;
+                if room.examinees == 0 {
+                    room.exam = None;
+                }
+                if separate {
+                    room.alone = false;
+                }
+                let room = &mut self.rooms[slot][to];
+                room.examinees += 1;
+                room.exam = Some(exam);
+                room.alone |= separate;
+                self.seats[exam][position] = to;
+            }
+            Change::Guard {
+                slot,
+                vigilant,
+                from,
+                to,
+            } => {
+                if let Some(from) = from {
+                    let vigilants = &mut self.rooms[slot][from].vigilants;
+                    if let Some(index) = vigilants.iter().position(|v| *v == vigilant) {
+                        vigilants.swap_remove(index);
+                    }
+                    self.duties[vigilant] -= 1;
+                }
+                self.guards[slot][vigilant] = to;
+                if let Some(to) = to {
+                    self.rooms[slot][to].vigilants.push(vigilant);
+                    self.duties[vigilant] += 1;
+                }
+            }
+        }
+    }
+}
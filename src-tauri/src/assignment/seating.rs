@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::{
+    assignation::{AllExamConfiguration, ExamineeSeat},
+    classroom::Classroom,
+    examinee::Examinee,
+    AppValues, Entity, EntityId,
+};
+
+use super::by_name;
+
+/// In which order the examinees of a classroom take its seats
+#[derive(Deserialize, Debug, Clone, Copy, Default, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum SeatingStrategy {
+    /// By surenames and names
+    #[default]
+    Alphabetical,
+    /// Taking in turns one examinee of every academic centre, so schoolmates do not sit together
+    ByCentreInterleaved,
+    Random {
+        /// Makes the order repeatable, a random one is used if there is none
+        #[ts(optional)]
+        seed: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum SeatingError {
+    AssignmentNotDone,
+    /// The seats were asked for a session that is not in the project
+    UnknownSession {
+        session: EntityId,
+    },
+    /// The grid of the classroom has less usable seats than examinees in it, the ones left
+    /// without a seat are the last ones in the order of the strategy
+    NotEnoughUsableSeats {
+        subject: EntityId,
+        classroom: EntityId,
+        seats: usize,
+        examinees: usize,
+    },
+}
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SeatingResult {
+    pub assignation: AllExamConfiguration,
+    pub errors: Vec<SeatingError>,
+}
+
+/// Gives every examinee of the assignment a seat in its classroom. Classrooms with a seat grid
+/// fill the seats of their spacing pattern first, the rest just number their examinees
+pub fn assign_seats(
+    values: &AppValues,
+    strategy: &SeatingStrategy,
+) -> Result<SeatingResult, SeatingError> {
+    let Some(mut assignation) = values.assignation.clone() else {
+        return Err(SeatingError::AssignmentNotDone);
+    };
+    let classrooms: HashMap<&EntityId, &Classroom> = values
+        .classrooms
+        .iter()
+        .map(|classroom| (classroom.id(), classroom))
+        .collect();
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+    let mut rng = match strategy {
+        SeatingStrategy::Random { seed: Some(seed) } => StdRng::seed_from_u64((*seed).into()),
+        _ => StdRng::from_entropy(),
+    };
+
+    let mut errors = Vec::new();
+    for configuration in &mut assignation.0 {
+        for exam in configuration.exams_mut() {
+            let Some(distribution) = &mut exam.distribution else {
+                continue;
+            };
+            for used in &mut distribution.distribution {
+                let in_classroom: Vec<&Examinee> = used
+                    .examinees
+                    .iter()
+                    .filter_map(|id| examinees.get(id).copied())
+                    .collect();
+                let ordered = order(in_classroom, strategy, &mut rng);
+                let seats: Vec<u32> = match classrooms
+                    .get(&used.classroom)
+                    .and_then(|classroom| classroom.seat_grid.as_ref())
+                {
+                    Some(grid) => grid.seat_order(),
+                    None => (1..=ordered.len() as u32).collect(),
+                };
+                if seats.len() < ordered.len() {
+                    errors.push(SeatingError::NotEnoughUsableSeats {
+                        subject: exam.subject.clone(),
+                        classroom: used.classroom.clone(),
+                        seats: seats.len(),
+                        examinees: ordered.len(),
+                    });
+                }
+                used.seats = ordered
+                    .iter()
+                    .zip(seats)
+                    .map(|(examinee, seat)| ExamineeSeat {
+                        examinee: examinee.id.clone(),
+                        seat,
+                    })
+                    .collect();
+                used.seats.sort_by_key(|seat| seat.seat);
+            }
+        }
+    }
+    Ok(SeatingResult {
+        assignation,
+        errors,
+    })
+}
+
+fn order<'a>(
+    mut examinees: Vec<&'a Examinee>,
+    strategy: &SeatingStrategy,
+    rng: &mut StdRng,
+) -> Vec<&'a Examinee> {
+    examinees.sort_by(by_name);
+    match strategy {
+        SeatingStrategy::Alphabetical => examinees,
+        SeatingStrategy::ByCentreInterleaved => interleave_by_centre(examinees),
+        SeatingStrategy::Random { .. } => {
+            examinees.shuffle(rng);
+            examinees
+        }
+    }
+}
+
+/// Takes one examinee of every academic centre in turns, starting by the centres with the most
+/// examinees so the last turns do not leave schoolmates together
+fn interleave_by_centre(examinees: Vec<&Examinee>) -> Vec<&Examinee> {
+    let total = examinees.len();
+    let mut centres: Vec<Vec<&Examinee>> = Vec::new();
+    let mut index_of = HashMap::<Option<&EntityId>, usize>::new();
+    for examinee in examinees {
+        let index = *index_of
+            .entry(examinee.academic_centre_id.as_ref())
+            .or_insert_with(|| {
+                centres.push(Vec::new());
+                centres.len() - 1
+            });
+        centres[index].push(examinee);
+    }
+    centres.sort_by_key(|centre| std::cmp::Reverse(centre.len()));
+
+    let mut interleaved = Vec::with_capacity(total);
+    let mut turn = 0;
+    while interleaved.len() < total {
+        for centre in &centres {
+            if let Some(examinee) = centre.get(turn) {
+                interleaved.push(*examinee);
+            }
+        }
+        turn += 1;
+    }
+    interleaved
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::models::{subject::Subject, AppValues, Entity, EntityId};
+
+/// The classrooms an examinee goes through, in the order of the exams
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ExamineeRoomChanges {
+    pub examinee: EntityId,
+    pub classrooms: Vec<EntityId>,
+    /// Times the examinee has to change classroom between one exam and the next one
+    pub changes: usize,
+}
+
+/// Reports the room changes of every examinee with some exam in the assignment, from the ones with
+/// the most changes to the ones with the least. Empty if there is no assignment
+pub fn room_changes(values: &AppValues) -> Vec<ExamineeRoomChanges> {
+    let Some(assignation) = &values.assignation else {
+        return Vec::new();
+    };
+    let subjects: HashMap<&EntityId, &Subject> = values
+        .subjects
+        .iter()
+        .map(|subject| (subject.id(), subject))
+        .collect();
+
+    let mut schedules = HashMap::<&EntityId, Vec<(&Subject, &EntityId)>>::new();
+    for configuration in &assignation.0 {
+        for exam in configuration.exams() {
+            let (Some(subject), Some(distribution)) =
+                (subjects.get(&exam.subject), &exam.distribution)
+            else {
+                continue;
+            };
+            for used in &distribution.distribution {
+                for examinee in &used.examinees {
+                    schedules
+                        .entry(examinee)
+                        .or_default()
+                        .push((subject, &used.classroom));
+                }
+            }
+        }
+    }
+
+    let mut changes: Vec<ExamineeRoomChanges> = schedules
+        .into_iter()
+        .map(|(examinee, mut schedule)| {
+            schedule.sort_by_key(|(subject, _)| subject.exam_date);
+            let classrooms: Vec<EntityId> = schedule
+                .into_iter()
+                .map(|(_, classroom)| classroom.clone())
+                .collect();
+            ExamineeRoomChanges {
+                examinee: examinee.clone(),
+                changes: classrooms
+                    .windows(2)
+                    .filter(|pair| pair[0] != pair[1])
+                    .count(),
+                classrooms,
+            }
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        b.changes
+            .cmp(&a.changes)
+            .then_with(|| a.examinee.cmp(&b.examinee))
+    });
+    changes
+}
@@ -0,0 +1,1018 @@
+use serde_json::{json, Value};
+
+use super::*;
+use crate::models::assignation::{ExamClassroomDistribution, IndividualExam};
+
+fn examinee(id: i32, subjects: &[i32]) -> Value {
+    json!({
+        "id": id,
+        "nif": format!("{id:08}X"),
+        "name": format!("Examinee {id}"),
+        "surenames": "Surenames",
+        "origin": "Origin",
+        "courtId": null,
+        "academicCentreId": null,
+        "subjectsIds": subjects,
+    })
+}
+
+fn examinee_with(id: i32, subjects: &[i32], accommodations: Value) -> Value {
+    let mut examinee = examinee(id, subjects);
+    examinee["accommodations"] = accommodations;
+    examinee
+}
+
+/// A subject examined on the 4th of June of 2024, starting at `start`
+fn subject(id: i32, start: &str, hours: u32) -> Value {
+    json!({
+        "id": id,
+        "name": format!("Subject {id}"),
+        "kind": "OBLIGATORY",
+        "examDate": format!("2024-06-04T{start}:00+02:00"),
+        "examDuration": format!("PT{hours}H"),
+    })
+}
+
+fn vigilant(id: i32, specialties: &[i32]) -> Value {
+    json!({
+        "id": id,
+        "name": format!("Vigilant {id}"),
+        "surenames": "Surenames",
+        "role": "MEMBER",
+        "specialtiesIds": specialties,
+        "academicCentreId": null,
+        "mainCourtId": null,
+    })
+}
+
+fn classroom(id: i32, exam_capacity: u32, total_capacity: u32) -> Value {
+    json!({
+        "id": id,
+        "code": format!("C{id}"),
+        "locationCode": "L",
+        "totalCapacity": total_capacity,
+        "examCapacity": exam_capacity,
+        "priority": id,
+        "kind": "",
+        "notes": [],
+    })
+}
+
+fn project(
+    examinees: Vec<Value>,
+    subjects: Vec<Value>,
+    vigilants: Vec<Value>,
+    classrooms: Vec<Value>,
+) -> AppValues {
+    let ids = json!({
+        "examinees": examinees.len(),
+        "academicCentres": 0,
+        "subjects": subjects.len(),
+        "vigilants": vigilants.len(),
+        "classrooms": classrooms.len(),
+    });
+    serde_json::from_value(json!({
+        "examinees": examinees,
+        "academicCentres": [],
+        "subjects": subjects,
+        "vigilants": vigilants,
+        "classrooms": classrooms,
+        "assignation": null,
+        "ids": ids,
+    }))
+    .unwrap()
+}
+
+fn examinees(range: std::ops::Range<i32>, subjects: &[i32]) -> Vec<Value> {
+    range.map(|id| examinee(id, subjects)).collect()
+}
+
+fn id(id: i32) -> EntityId {
+    serde_json::from_value(json!(id)).unwrap()
+}
+
+fn run(values: &AppValues) -> AssignmentResult {
+    assign(
+        values,
+        &ConflictResolution::default(),
+        &InterestRules::default(),
+    )
+    .unwrap()
+}
+
+fn distribution(result: &AssignmentResult, subject: i32) -> &[ExamClassroomDistribution] {
+    let exams = result.assignation.individual_exams();
+    let exam: &IndividualExam = exams
+        .into_iter()
+        .find(|exam| exam.subject == id(subject))
+        .unwrap();
+    &exam.distribution.as_ref().unwrap().distribution
+}
+
+#[test]
+fn missing_exam_date_fails_the_whole_assignment() {
+    let mut values = project(
+        examinees(0..2, &[0]),
+        vec![subject(0, "09:00", 2), subject(1, "12:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    values.subjects[1].exam_date = None;
+
+    assert_eq!(
+        assign(
+            &values,
+            &ConflictResolution::default(),
+            &InterestRules::default()
+        )
+        .unwrap_err(),
+        AssignmentError::MissingExamDate { subject: id(1) }
+    );
+}
+
+#[test]
+fn examinees_are_split_by_exam_capacity() {
+    let values = project(
+        examinees(0..10, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[])],
+        vec![classroom(0, 4, 10), classroom(1, 8, 10)],
+    );
+
+    let result = run(&values);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let distribution = distribution(&result, 0);
+    assert_eq!(
+        distribution
+            .iter()
+            .map(|classroom| classroom.examinees.len())
+            .sum::<usize>(),
+        10
+    );
+    for (used, classroom) in distribution.iter().zip([4, 8]) {
+        assert!(used.examinees.len() <= classroom);
+        assert_eq!(used.vigilants.len(), 1);
+    }
+}
+
+#[test]
+fn examinees_over_the_exam_capacity_use_the_total_one() {
+    let values = project(
+        examinees(0..14, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[])],
+        vec![classroom(0, 4, 10), classroom(1, 8, 10)],
+    );
+
+    let result = run(&values);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let distribution = distribution(&result, 0);
+    assert_eq!(
+        distribution
+            .iter()
+            .map(|classroom| classroom.examinees.len())
+            .sum::<usize>(),
+        14
+    );
+    assert!(distribution
+        .iter()
+        .all(|classroom| classroom.examinees.len() <= 10));
+}
+
+/// The classrooms of the exam with the examinees and how many vigilants are in each of them
+fn seated(result: &AssignmentResult, subject: i32) -> Vec<(EntityId, Vec<EntityId>, usize)> {
+    distribution(result, subject)
+        .iter()
+        .map(|classroom| {
+            (
+                classroom.classroom.clone(),
+                classroom.examinees.clone(),
+                classroom.vigilants.len(),
+            )
+        })
+        .collect()
+}
+
+fn ids(range: std::ops::Range<i32>) -> Vec<EntityId> {
+    range.map(id).collect()
+}
+
+#[test]
+fn exam_without_assignment_has_no_distribution() {
+    let values = project(vec![], vec![subject(0, "09:00", 2)], vec![], vec![]);
+
+    let exam = individual::IndividualExamConfiguration::new(&values.subjects[0], Vec::new());
+    assert!(exam.into_exam().distribution.is_none());
+}
+
+#[test]
+fn exam_is_assigned_when_the_total_capacity_fits_the_examinees() {
+    // Exam and total capacity of each classroom, in order of priority
+    for (capacities, fits) in [
+        (vec![(1, 1)], false),
+        (vec![(1, 5)], true),
+        (vec![(5, 15)], true),
+        (vec![(1, 7)], true),
+        (vec![(7, 14)], true),
+        (vec![(1, 2), (1, 2)], false),
+        (vec![(1, 3), (1, 2)], true),
+        (vec![(3, 6), (2, 4)], true),
+        (vec![(1, 6), (1, 6)], true),
+        (vec![(3, 5), (3, 5)], true),
+    ] {
+        let values = project(
+            examinees(0..5, &[0]),
+            vec![subject(0, "09:00", 2)],
+            vec![vigilant(0, &[]), vigilant(1, &[])],
+            capacities
+                .iter()
+                .zip(0..)
+                .map(|(&(exam, total), id)| classroom(id, exam, total))
+                .collect(),
+        );
+
+        let result = run(&values);
+        if fits {
+            assert!(
+                result.errors.is_empty(),
+                "{capacities:?}: {:?}",
+                result.errors
+            );
+        } else {
+            assert_eq!(
+                result.errors,
+                vec![AssignmentError::NotEnoughSeats { subject: id(0) }],
+                "{capacities:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn examinees_and_vigilants_fill_the_classrooms_in_order_of_priority() {
+    // Exam and total capacity of each classroom in order of priority, and how many examinees
+    // and vigilants the first one gets
+    for (capacities, first) in [
+        (vec![(1, 10)], (10, 3)),
+        (vec![(5, 15)], (10, 3)),
+        (vec![(1, 11)], (10, 3)),
+        (vec![(11, 14)], (10, 3)),
+        (vec![(1, 5), (1, 5)], (5, 2)),
+        (vec![(5, 10), (5, 10)], (5, 2)),
+        (vec![(1, 5), (1, 10)], (4, 1)),
+        (vec![(10, 15), (10, 15)], (5, 2)),
+        (vec![(2, 3), (10, 20)], (2, 1)),
+    ] {
+        let values = project(
+            examinees(0..10, &[0]),
+            vec![subject(0, "09:00", 2)],
+            vec![vigilant(0, &[]), vigilant(1, &[]), vigilant(2, &[])],
+            capacities
+                .iter()
+                .zip(0..)
+                .map(|(&(exam, total), id)| classroom(id, exam, total))
+                .collect(),
+        );
+
+        let result = run(&values);
+        assert!(
+            result.errors.is_empty(),
+            "{capacities:?}: {:?}",
+            result.errors
+        );
+        let (examinees, vigilants) = first;
+        let mut expected = vec![(id(0), ids(0..examinees), vigilants)];
+        if capacities.len() > 1 {
+            expected.push((id(1), ids(examinees..10), 3 - vigilants));
+        }
+        assert_eq!(seated(&result, 0), expected, "{capacities:?}");
+    }
+
+    let values = project(
+        examinees(0..10, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 1, 1), classroom(1, 1, 1)],
+    );
+    assert_eq!(
+        run(&values).errors,
+        vec![AssignmentError::NotEnoughSeats { subject: id(0) }]
+    );
+}
+
+#[test]
+fn exams_that_can_not_be_assigned_are_reported_by_kind() {
+    let seats = project(
+        examinees(0..11, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    let classrooms = project(
+        examinees(0..1, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![],
+    );
+    let vigilants = project(
+        examinees(0..15, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10), classroom(1, 10, 10)],
+    );
+    let mut available = vigilants.clone();
+    available.vigilants.push(
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": "Vigilant 1",
+            "surenames": "Surenames",
+            "role": "MEMBER",
+            "specialtiesIds": [],
+            "academicCentreId": null,
+            "mainCourtId": null,
+            "availability": [{
+                "start": "2024-06-04T15:00:00+02:00",
+                "end": "2024-06-04T20:00:00+02:00",
+            }],
+        }))
+        .unwrap(),
+    );
+    let accommodations = |accessible_room: bool, separate_room: bool| json!({ "accessibleRoom": accessible_room, "separateRoom": separate_room });
+    let accessible = project(
+        vec![examinee_with(0, &[0], accommodations(true, false))],
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    let separate = project(
+        vec![
+            examinee_with(0, &[0], accommodations(false, true)),
+            examinee_with(1, &[0], accommodations(false, true)),
+        ],
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    let mut interest = project(
+        examinees(0..1, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    interest.vigilants[0].relatives_nifs = vec![interest.examinees[0].nif.clone()];
+
+    for (values, error) in [
+        (seats, AssignmentError::NotEnoughSeats { subject: id(0) }),
+        (classrooms, AssignmentError::NoClassrooms),
+        (
+            vigilants,
+            AssignmentError::NotEnoughVigilants { subject: id(0) },
+        ),
+        (
+            available,
+            AssignmentError::NotEnoughAvailableVigilants { subject: id(0) },
+        ),
+        (
+            accessible,
+            AssignmentError::NotEnoughAccessibleSeats { subject: id(0) },
+        ),
+        (
+            separate,
+            AssignmentError::NotEnoughSeparateRooms { subject: id(0) },
+        ),
+        (
+            interest,
+            AssignmentError::ConflictOfInterest { subject: id(0) },
+        ),
+    ] {
+        let result = run(&values);
+        assert_eq!(result.errors, vec![error]);
+        assert!(result.diagnosis.is_some());
+        assert!(distribution(&result, 0).is_empty());
+    }
+}
+
+#[test]
+fn exams_that_start_while_any_of_the_group_is_held_collide() {
+    let values = project(
+        vec![],
+        vec![
+            subject(0, "09:00", 3),
+            subject(1, "09:30", 1),
+            subject(2, "11:00", 1),
+            subject(3, "12:30", 1),
+        ],
+        vec![],
+        vec![],
+    );
+
+    let groups: Vec<Vec<EntityId>> =
+        order_and_group_subjects(values.subjects.iter().collect(), &values.examinees)
+            .unwrap()
+            .into_iter()
+            .map(|group| group.iter().map(|subject| subject.id.clone()).collect())
+            .collect();
+    assert_eq!(groups, vec![vec![id(0), id(1), id(2)], vec![id(3)]]);
+}
+
+#[test]
+fn exams_are_grouped_while_they_overlap_in_order_of_start() {
+    let values = project(
+        vec![],
+        vec![
+            subject(0, "12:00", 2),
+            subject(1, "19:30", 2),
+            subject(2, "13:00", 2),
+            subject(3, "14:30", 2),
+            subject(4, "17:00", 2),
+            subject(5, "19:30", 2),
+        ],
+        vec![],
+        vec![],
+    );
+
+    let groups: Vec<Vec<EntityId>> =
+        order_and_group_subjects(values.subjects.iter().collect(), &values.examinees)
+            .unwrap()
+            .into_iter()
+            .map(|group| group.iter().map(|subject| subject.id.clone()).collect())
+            .collect();
+    assert_eq!(
+        groups,
+        vec![vec![id(0), id(2), id(3)], vec![id(4)], vec![id(1), id(5)]]
+    );
+}
+
+#[test]
+fn exams_that_start_during_the_extra_time_of_an_examinee_collide() {
+    let mut all = vec![examinee_with(
+        0,
+        &[0, 1],
+        json!({ "extraTimePercentage": 50 }),
+    )];
+    all.extend(examinees(1..6, &[0]));
+    all.extend(examinees(6..11, &[1]));
+    let values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "11:30", 2)],
+        vec![
+            vigilant(0, &[0]),
+            vigilant(1, &[1]),
+            vigilant(2, &[]),
+            vigilant(3, &[]),
+        ],
+        vec![classroom(0, 10, 10), classroom(1, 10, 10)],
+    );
+
+    let groups =
+        order_and_group_subjects(values.subjects.iter().collect(), &values.examinees).unwrap();
+    assert_eq!(groups.len(), 1);
+    let result = run(&values);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let first = distribution(&result, 0);
+    let second = distribution(&result, 1);
+    assert!(first
+        .iter()
+        .all(|used| second.iter().all(|other| other.classroom != used.classroom)));
+}
+
+#[test]
+fn colliding_exams_do_not_share_classrooms_or_vigilants() {
+    let mut all = examinees(0..6, &[0]);
+    all.extend(examinees(6..12, &[1]));
+    let values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "10:00", 2)],
+        vec![
+            vigilant(0, &[0]),
+            vigilant(1, &[1]),
+            vigilant(2, &[]),
+            vigilant(3, &[]),
+        ],
+        vec![classroom(0, 10, 10), classroom(1, 10, 10)],
+    );
+
+    let result = run(&values);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let first = distribution(&result, 0);
+    let second = distribution(&result, 1);
+    assert!(first
+        .iter()
+        .all(|used| second.iter().all(|other| other.classroom != used.classroom)));
+    let vigilants = |distribution: &[ExamClassroomDistribution]| -> Vec<EntityId> {
+        distribution
+            .iter()
+            .flat_map(|used| used.vigilants.clone())
+            .collect()
+    };
+    assert!(vigilants(first)
+        .iter()
+        .all(|vigilant| !vigilants(second).contains(vigilant)));
+    let exams = result.assignation.individual_exams();
+    for (exam, specialist) in exams.iter().zip([0, 1]) {
+        assert_eq!(
+            exam.distribution.as_ref().unwrap().specialists,
+            vec![id(specialist)]
+        );
+    }
+}
+
+#[test]
+fn colliding_exams_without_a_specialist_are_reported() {
+    let mut all = examinees(0..6, &[0]);
+    all.extend(examinees(6..12, &[1]));
+    let values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "10:00", 2)],
+        vec![
+            vigilant(0, &[0]),
+            vigilant(1, &[]),
+            vigilant(2, &[]),
+            vigilant(3, &[]),
+        ],
+        vec![classroom(0, 10, 10), classroom(1, 10, 10)],
+    );
+
+    let result = run(&values);
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::MissingSpecialist { subject: id(1) }]
+    );
+}
+
+#[test]
+fn diagnosis_counts_the_duties_of_the_slots_before() {
+    use super::diagnosis::Relaxation;
+
+    let mut values = project(
+        examinees(0..2, &[0, 1]),
+        vec![subject(0, "09:00", 2), subject(1, "12:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    values.vigilants[0].max_duties = Some(1);
+
+    let result = run(&values);
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::NotEnoughAvailableVigilants { subject: id(1) }]
+    );
+    let diagnosis = result.diagnosis.unwrap();
+    assert!(diagnosis.slots[0].exhausted_vigilants.is_empty());
+    assert_eq!(diagnosis.slots[1].exhausted_vigilants, vec![id(0)]);
+    assert_eq!(diagnosis.slots[1].vigilant_shortfall, 1);
+    assert_eq!(
+        diagnosis.relaxations,
+        vec![Relaxation::RaiseMaxDuties {
+            subjects: vec![id(1)],
+            vigilants: vec![id(0)],
+        }]
+    );
+}
+
+#[test]
+fn diagnosis_needs_vigilants_until_the_extra_time_ends() {
+    use super::diagnosis::Relaxation;
+
+    let mut morning = vigilant(0, &[]);
+    morning["availability"] = json!([{
+        "start": "2024-06-04T08:00:00+02:00",
+        "end": "2024-06-04T11:30:00+02:00",
+    }]);
+    let values = project(
+        vec![examinee_with(0, &[0], json!({ "extraTimePercentage": 50 }))],
+        vec![subject(0, "09:00", 2)],
+        vec![morning],
+        vec![classroom(0, 10, 10)],
+    );
+
+    let result = run(&values);
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::NotEnoughAvailableVigilants { subject: id(0) }]
+    );
+    let diagnosis = result.diagnosis.unwrap();
+    assert_eq!(
+        diagnosis.slots[0].end.to_rfc3339(),
+        "2024-06-04T12:00:00+02:00"
+    );
+    assert_eq!(diagnosis.slots[0].unavailable_vigilants, vec![id(0)]);
+    assert_eq!(
+        diagnosis.relaxations,
+        vec![Relaxation::ExtendAvailability {
+            subjects: vec![id(0)],
+            vigilants: vec![id(0)],
+        }]
+    );
+}
+
+#[test]
+fn diagnosis_finds_the_examinees_no_vigilant_can_watch() {
+    use super::diagnosis::Relaxation;
+
+    let mut values = project(
+        examinees(0..2, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    values.vigilants[0].relatives_nifs = vec![values.examinees[1].nif.clone()];
+
+    let result = run(&values);
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::ConflictOfInterest { subject: id(0) }]
+    );
+    let diagnosis = result.diagnosis.unwrap();
+    assert_eq!(
+        diagnosis.slots[0].subjects[0].conflicted_examinees,
+        vec![id(1)]
+    );
+    assert_eq!(
+        diagnosis.relaxations,
+        vec![Relaxation::AddVigilantWithoutConflicts {
+            subject: id(0),
+            examinees: vec![id(1)],
+        }]
+    );
+}
+
+#[test]
+fn diagnosis_finds_a_shared_room_that_can_not_be_used() {
+    use super::diagnosis::Relaxation;
+
+    let mut all = examinees(0..2, &[0, 1]);
+    all.extend(examinees(2..6, &[0]));
+    all.extend(examinees(6..10, &[1]));
+    let mut values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "10:00", 2)],
+        vec![
+            vigilant(0, &[0]),
+            vigilant(1, &[1]),
+            vigilant(2, &[]),
+            vigilant(3, &[]),
+            vigilant(4, &[]),
+        ],
+        vec![
+            classroom(0, 10, 10),
+            classroom(1, 10, 10),
+            classroom(2, 10, 10),
+        ],
+    );
+    values.classrooms[2].court_location_id = Some(id(1));
+
+    let result = assign(
+        &values,
+        &ConflictResolution::SharedRoom { classroom: id(2) },
+        &InterestRules::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::NotEnoughSeatsInSharedRoom {
+            subjects: vec![id(0), id(1)]
+        }]
+    );
+    let diagnosis = result.diagnosis.unwrap();
+    assert_eq!(diagnosis.slots[0].sharing_examinees, 2);
+    assert_eq!(diagnosis.slots[0].shared_room_shortfall, 2);
+    assert_eq!(
+        diagnosis.relaxations,
+        vec![Relaxation::UseBiggerSharedRoom {
+            subjects: vec![id(0), id(1)],
+            seats: 2,
+        }]
+    );
+}
+
+#[test]
+fn shared_room_without_a_vigilant_is_reported() {
+    let mut all = examinees(0..2, &[0, 1]);
+    all.extend(examinees(2..6, &[0]));
+    let mut values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "10:00", 2)],
+        vec![vigilant(0, &[0]), vigilant(1, &[1]), vigilant(2, &[])],
+        vec![
+            classroom(0, 10, 10),
+            classroom(1, 10, 10),
+            classroom(2, 10, 10),
+        ],
+    );
+    values.vigilants[2].relatives_nifs = vec![values.examinees[0].nif.clone()];
+
+    let result = assign(
+        &values,
+        &ConflictResolution::SharedRoom { classroom: id(2) },
+        &InterestRules::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        result.errors,
+        vec![AssignmentError::NoVigilantForSharedRoom {
+            subjects: vec![id(0), id(1)]
+        }]
+    );
+}
+
+#[test]
+fn reassign_keeps_what_is_locked() {
+    let mut values = project(
+        examinees(0..12, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[]), vigilant(2, &[])],
+        vec![
+            classroom(0, 10, 10),
+            classroom(1, 10, 10),
+            classroom(2, 10, 10),
+        ],
+    );
+    let mut assignation = run(&values).assignation;
+    let ExamConfiguration::IndividualExam(exam) = &mut assignation.0[0] else {
+        panic!("A single exam is not colliding");
+    };
+    let used_classrooms = &mut exam.distribution.as_mut().unwrap().distribution;
+    for used in used_classrooms.iter_mut() {
+        used.examinees.retain(|examinee| *examinee != id(0));
+        used.vigilants.retain(|vigilant| *vigilant != id(2));
+    }
+    used_classrooms.retain(|used| used.classroom != id(2));
+    used_classrooms.push(ExamClassroomDistribution {
+        classroom: id(2),
+        examinees: vec![id(0)],
+        vigilants: vec![id(2)],
+        locked: true,
+        locked_examinees: vec![id(0)],
+        locked_vigilants: vec![id(2)],
+        seats: Vec::new(),
+    });
+    values.assignation = Some(assignation);
+
+    let result = reassign(
+        &values,
+        &ConflictResolution::default(),
+        &InterestRules::default(),
+    )
+    .unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let distribution = distribution(&result, 0);
+    let pinned = distribution
+        .iter()
+        .find(|used| used.classroom == id(2))
+        .unwrap();
+    assert!(pinned.locked);
+    assert!(pinned.examinees.contains(&id(0)));
+    assert_eq!(pinned.locked_examinees, vec![id(0)]);
+    assert_eq!(pinned.locked_vigilants, vec![id(2)]);
+    assert!(pinned.vigilants.contains(&id(2)));
+    assert_eq!(
+        distribution
+            .iter()
+            .map(|used| used.examinees.len())
+            .sum::<usize>(),
+        12
+    );
+}
+
+#[test]
+fn reassign_drops_locked_vigilants_with_a_conflict_of_interest() {
+    let mut values = project(
+        examinees(0..12, &[0]),
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[]), vigilant(2, &[])],
+        vec![
+            classroom(0, 10, 10),
+            classroom(1, 10, 10),
+            classroom(2, 10, 10),
+        ],
+    );
+    let mut assignation = run(&values).assignation;
+    let ExamConfiguration::IndividualExam(exam) = &mut assignation.0[0] else {
+        panic!("A single exam is not colliding");
+    };
+    for used in &mut exam.distribution.as_mut().unwrap().distribution {
+        if used.vigilants.contains(&id(2)) {
+            used.locked = true;
+            used.locked_examinees = used.examinees.clone();
+            used.locked_vigilants = vec![id(2)];
+        }
+    }
+    let related = exam
+        .distribution
+        .as_ref()
+        .unwrap()
+        .distribution
+        .iter()
+        .find(|used| used.vigilants.contains(&id(2)))
+        .unwrap()
+        .examinees[0]
+        .clone();
+    values.vigilants[2].relatives_nifs = vec![values
+        .examinees
+        .iter()
+        .find(|examinee| examinee.id == related)
+        .unwrap()
+        .nif
+        .clone()];
+    values.assignation = Some(assignation);
+
+    let result = reassign(
+        &values,
+        &ConflictResolution::default(),
+        &InterestRules::default(),
+    )
+    .unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let distribution = distribution(&result, 0);
+    let watching = distribution
+        .iter()
+        .find(|used| used.vigilants.contains(&id(2)))
+        .unwrap();
+    assert!(!watching.examinees.contains(&related));
+    assert!(watching.locked_vigilants.is_empty());
+}
+
+#[test]
+fn balancing_does_not_hand_duties_to_vigilants_gone_before_the_extra_time_ends() {
+    use super::workload::balance_workload;
+
+    let mut values = project(
+        vec![examinee_with(
+            0,
+            &[0, 1],
+            json!({ "extraTimePercentage": 50 }),
+        )],
+        vec![subject(0, "09:00", 2), subject(1, "15:00", 2)],
+        vec![vigilant(0, &[])],
+        vec![classroom(0, 10, 10)],
+    );
+    values.assignation = Some(run(&values).assignation);
+    values.vigilants.push(
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": "Vigilant 1",
+            "surenames": "Surenames",
+            "role": "MEMBER",
+            "specialtiesIds": [],
+            "academicCentreId": null,
+            "mainCourtId": null,
+            "availability": [{
+                "start": "2024-06-04T08:00:00+02:00",
+                "end": "2024-06-04T11:30:00+02:00",
+            }],
+        }))
+        .unwrap(),
+    );
+
+    let result = balance_workload(&values, &InterestRules::default()).unwrap();
+    assert_eq!((result.after.min, result.after.max), (0, 2));
+}
+
+#[test]
+fn optimiser_keeps_vigilants_gone_before_the_extra_time_ends_out() {
+    use super::optimiser::{optimise, OptimisationSettings};
+
+    let mut all = vec![examinee_with(
+        0,
+        &[0, 1],
+        json!({ "extraTimePercentage": 50 }),
+    )];
+    all.extend(examinees(1..16, &[0, 1]));
+    let mut morning = vigilant(2, &[]);
+    morning["availability"] = json!([{
+        "start": "2024-06-04T08:00:00+02:00",
+        "end": "2024-06-04T11:30:00+02:00",
+    }]);
+    let values = project(
+        all,
+        vec![subject(0, "09:00", 2), subject(1, "15:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[]), morning],
+        vec![classroom(0, 10, 10), classroom(1, 10, 10)],
+    );
+    let settings = OptimisationSettings {
+        time_budget: 200,
+        seed: Some(1),
+        ..Default::default()
+    };
+
+    let result = optimise(&values, &settings).unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(result
+        .assignation
+        .individual_exams()
+        .iter()
+        .flat_map(|exam| &exam.distribution.as_ref().unwrap().distribution)
+        .all(|used| !used.vigilants.contains(&id(2))));
+}
+
+#[test]
+fn optimiser_counts_the_duties_of_the_slots_it_does_not_search() {
+    use super::optimiser::{optimise, OptimisationSettings};
+
+    let mut all = examinees(0..4, &[0]);
+    all.extend(examinees(4..8, &[1]));
+    all.push(examinee(8, &[0, 1]));
+    all.extend(examinees(9..29, &[2]));
+    let mut values = project(
+        all,
+        vec![
+            subject(0, "09:00", 2),
+            subject(1, "10:00", 2),
+            subject(2, "16:00", 2),
+        ],
+        (0..10)
+            .map(|id| {
+                let mut vigilant = vigilant(id, &[]);
+                if id < 6 {
+                    vigilant["maxDuties"] = json!(1);
+                } else {
+                    // Only the afternoon exam has them, the morning one takes all it can get
+                    vigilant["availability"] = json!([{
+                        "start": "2024-06-04T15:00:00+02:00",
+                        "end": "2024-06-04T20:00:00+02:00",
+                    }]);
+                }
+                vigilant
+            })
+            .collect(),
+        (0..4).map(|id| classroom(id, 10, 10)).collect(),
+    );
+    values.vigilants[0].specialties_ids.push(id(0));
+    values.vigilants[1].specialties_ids.push(id(1));
+    let settings = OptimisationSettings {
+        time_budget: 200,
+        seed: Some(1),
+        conflicts: ConflictResolution::SharedRoom { classroom: id(3) },
+        ..Default::default()
+    };
+
+    let result = optimise(&values, &settings).unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let mut duties = HashMap::<EntityId, usize>::new();
+    for configuration in &result.assignation.0 {
+        let mut on_duty: Vec<EntityId> = configuration
+            .exams()
+            .iter()
+            .filter_map(|exam| exam.distribution.as_ref())
+            .flat_map(|distribution| {
+                distribution
+                    .specialists
+                    .iter()
+                    .chain(
+                        distribution
+                            .distribution
+                            .iter()
+                            .flat_map(|used| &used.vigilants),
+                    )
+                    .cloned()
+            })
+            .collect();
+        on_duty.sort_unstable();
+        on_duty.dedup();
+        for vigilant in on_duty {
+            *duties.entry(vigilant).or_default() += 1;
+        }
+    }
+    assert!(duties.values().all(|&duties| duties <= 1), "{duties:?}");
+}
+
+#[test]
+fn optimiser_starts_with_the_academic_centres_spread_among_the_classrooms() {
+    use super::centres::centre_spread;
+    use super::optimiser::{optimise, OptimisationSettings};
+
+    let all = (0..8)
+        .map(|id| {
+            let mut examinee = examinee(id, &[0]);
+            examinee["academicCentreId"] = json!(id / 4);
+            examinee
+        })
+        .collect();
+    let mut values = project(
+        all,
+        vec![subject(0, "09:00", 2)],
+        vec![vigilant(0, &[]), vigilant(1, &[])],
+        vec![classroom(0, 4, 4), classroom(1, 4, 4)],
+    );
+    values.academic_centres = serde_json::from_value(json!([
+        { "id": 0, "name": "Centre 0" },
+        { "id": 1, "name": "Centre 1" },
+    ]))
+    .unwrap();
+
+    // In order of name the first classroom gets all the examinees of the first centre
+    values.assignation = Some(run(&values).assignation);
+    assert!(centre_spread(&values, 50).iter().all(|used| used.exceeds));
+
+    let settings = OptimisationSettings {
+        time_budget: 0,
+        max_centre_share: 50,
+        seed: Some(1),
+        ..Default::default()
+    };
+    let result = optimise(&values, &settings).unwrap();
+    assert_eq!(result.initial_cost.centre_excess, 0);
+    values.assignation = Some(result.assignation);
+    assert!(centre_spread(&values, 50).iter().all(|used| !used.exceeds));
+}
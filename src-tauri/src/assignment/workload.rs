@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{interest::InterestRules, slot_span};
+
+use crate::models::{
+    assignation::{AllExamConfiguration, ExamConfiguration},
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    AppValues, Entity, EntityId,
+};
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum WorkloadError {
+    AssignmentNotDone,
+    /// The workload was asked for a session that is not in the project
+    UnknownSession {
+        session: EntityId,
+    },
+}
+
+/// Duties of the vigilants that watch classrooms, presidents and secretaries do not
+#[derive(Serialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct WorkloadReport {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// From the vigilant with the most duties to the one with the least
+    pub duties: Vec<VigilantDuties>,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct VigilantDuties {
+    pub vigilant: EntityId,
+    /// Exam slots in which the vigilant watches a classroom or is a specialist
+    pub duties: usize,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct BalanceResult {
+    pub assignation: AllExamConfiguration,
+    pub before: WorkloadReport,
+    pub after: WorkloadReport,
+}
+
+/// Counts the duties of every vigilant in the whole assignment
+pub fn workload(values: &AppValues) -> Result<WorkloadReport, WorkloadError> {
+    let Some(assignation) = &values.assignation else {
+        return Err(WorkloadError::AssignmentNotDone);
+    };
+    Ok(report(values, &count_duties(assignation)))
+}
+
+/// Evens the duties of the vigilants across all the exams of the assignment: the duties of the
+/// vigilants with the most are handed over to the ones with the least that are free at the time.
+/// Presidents and secretaries hand theirs over whenever some vigilant can take them. Locked
+/// vigilants and specialists keep their duties, and the availability, maximum duties and
+/// conflicts of interest of the vigilants are respected
+pub fn balance_workload(
+    values: &AppValues,
+    interest: &InterestRules,
+) -> Result<BalanceResult, WorkloadError> {
+    let Some(mut assignation) = values.assignation.clone() else {
+        return Err(WorkloadError::AssignmentNotDone);
+    };
+    let mut duties = count_duties(&assignation);
+    let before = report(values, &duties);
+
+    let vigilants: HashMap<&EntityId, &Vigilant> = values
+        .vigilants
+        .iter()
+        .map(|vigilant| (vigilant.id(), vigilant))
+        .collect();
+    let subjects: HashMap<&EntityId, &Subject> = values
+        .subjects
+        .iter()
+        .map(|subject| (subject.id(), subject))
+        .collect();
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+    let members: Vec<&Vigilant> = values
+        .vigilants
+        .iter()
+        .filter(|vigilant| vigilant.role == VigilantRole::MEMBER)
+        .collect();
+    let spans: Vec<Option<Span>> = assignation
+        .0
+        .iter()
+        .map(|configuration| span(configuration, &subjects, &values.examinees))
+        .collect();
+    let load = |duties: &HashMap<EntityId, usize>, vigilant: &EntityId| {
+        duties.get(vigilant).copied().unwrap_or(0)
+    };
+
+    // Each hand over lowers the sum of the squares of the duties, so this ends
+    loop {
+        let mut best: Option<(Duty, &Vigilant, usize)> = None;
+        for duty in movable_duties(&assignation) {
+            let Some(current) = vigilants.get(&duty.vigilant) else {
+                continue;
+            };
+            let is_member = current.role == VigilantRole::MEMBER;
+            // Presidents and secretaries always leave the classroom if someone can take it
+            let gap_from = if is_member {
+                load(&duties, &duty.vigilant)
+            } else {
+                usize::MAX
+            };
+            let configuration = &assignation.0[duty.configuration];
+            let exam = &configuration.exams()[duty.exam];
+            let subject = &exam.subject;
+            let watched: Vec<&Examinee> = exam
+                .distribution
+                .iter()
+                .flat_map(|distribution| &distribution.distribution[duty.classroom].examinees)
+                .filter_map(|id| examinees.get(id).copied())
+                .collect();
+            let candidate = members
+                .iter()
+                .filter(|member| {
+                    !on_duty(configuration, &member.id)
+                        && !member.specialties_ids.contains(subject)
+                        && interest.allows(member, &watched)
+                        && spans[duty.configuration]
+                            .map_or(true, |(start, end)| member.is_available(start, end))
+                        && member.max_duties.map_or(true, |max_duties| {
+                            load(&duties, &member.id) < usize::from(max_duties)
+                        })
+                })
+                .min_by_key(|member| load(&duties, &member.id));
+            let Some(candidate) = candidate else {
+                continue;
+            };
+            let gap = gap_from.saturating_sub(load(&duties, &candidate.id));
+            if gap >= 2
+                && best
+                    .as_ref()
+                    .map_or(true, |(_, _, best_gap)| gap > *best_gap)
+            {
+                best = Some((duty, candidate, gap));
+            }
+        }
+        let Some((duty, candidate, _)) = best else {
+            break;
+        };
+        *duties.entry(duty.vigilant.clone()).or_default() -= 1;
+        *duties.entry(candidate.id.clone()).or_default() += 1;
+        hand_over(&mut assignation.0[duty.configuration], &duty, &candidate.id);
+    }
+
+    let after = report(values, &duties);
+    Ok(BalanceResult {
+        assignation,
+        before,
+        after,
+    })
+}
+
+type Span = (DateTime<FixedOffset>, DateTime<FixedOffset>);
+
+/// A vigilant watching a classroom
+struct Duty {
+    configuration: usize,
+    exam: usize,
+    classroom: usize,
+    vigilant: EntityId,
+}
+
+/// From the first exam of the slot that starts to the last one that ends, extra time included
+fn span(
+    configuration: &ExamConfiguration,
+    subjects: &HashMap<&EntityId, &Subject>,
+    examinees: &[Examinee],
+) -> Option<Span> {
+    let subjects: Vec<&Subject> = configuration
+        .exams()
+        .iter()
+        .filter_map(|exam| subjects.get(&exam.subject).copied())
+        .collect();
+    slot_span(&subjects, examinees)
+}
+
+/// The vigilants with some duty in the slot, as many times as they are listed
+fn duties_of(configuration: &ExamConfiguration) -> impl Iterator<Item = &EntityId> {
+    configuration
+        .exams()
+        .iter()
+        .filter_map(|exam| exam.distribution.as_ref())
+        .flat_map(|distribution| {
+            distribution.specialists.iter().chain(
+                distribution
+                    .distribution
+                    .iter()
+                    .flat_map(|classroom| &classroom.vigilants),
+            )
+        })
+}
+
+fn on_duty(configuration: &ExamConfiguration, vigilant: &EntityId) -> bool {
+    duties_of(configuration).any(|id| id == vigilant)
+}
+
+/// Every slot a vigilant takes part in is a duty
+fn count_duties(assignation: &AllExamConfiguration) -> HashMap<EntityId, usize> {
+    let mut duties = HashMap::new();
+    for configuration in &assignation.0 {
+        let mut seen: Vec<&EntityId> = duties_of(configuration).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for vigilant in seen {
+            *duties.entry(vigilant.clone()).or_default() += 1;
+        }
+    }
+    duties
+}
+
+/// Classroom duties that can be handed over: the vigilant is not locked there and has no other
+/// duty in the slot, so giving it up frees the whole slot
+fn movable_duties(assignation: &AllExamConfiguration) -> Vec<Duty> {
+    let mut movable = Vec::new();
+    for (index, configuration) in assignation.0.iter().enumerate() {
+        for (exam_index, exam) in configuration.exams().iter().enumerate() {
+            let Some(distribution) = &exam.distribution else {
+                continue;
+            };
+            for (classroom_index, classroom) in distribution.distribution.iter().enumerate() {
+                for vigilant in &classroom.vigilants {
+                    if classroom.locked_vigilants.contains(vigilant)
+                        || duties_of(configuration)
+                            .filter(|id| *id == vigilant)
+                            .count()
+                            > 1
+                    {
+                        continue;
+                    }
+                    movable.push(Duty {
+                        configuration: index,
+                        exam: exam_index,
+                        classroom: classroom_index,
+                        vigilant: vigilant.clone(),
+                    });
+                }
+            }
+        }
+    }
+    movable
+}
+
+fn hand_over(configuration: &mut ExamConfiguration, duty: &Duty, to: &EntityId) {
+    if let ExamConfiguration::CollidingExams { vigilants, .. } = configuration {
+        if !vigilants.contains(to) {
+            vigilants.push(to.clone());
+        }
+    }
+    let exam = &mut configuration.exams_mut()[duty.exam];
+    if !exam.vigilants.contains(to) {
+        exam.vigilants.push(to.clone());
+    }
+    let Some(distribution) = &mut exam.distribution else {
+        return;
+    };
+    for vigilant in &mut distribution.distribution[duty.classroom].vigilants {
+        if *vigilant == duty.vigilant {
+            *vigilant = to.clone();
+        }
+    }
+}
+
+fn report(values: &AppValues, duties: &HashMap<EntityId, usize>) -> WorkloadReport {
+    let mut members: Vec<VigilantDuties> = values
+        .vigilants
+        .iter()
+        .filter(|vigilant| vigilant.role == VigilantRole::MEMBER)
+        .map(|vigilant| VigilantDuties {
+            vigilant: vigilant.id.clone(),
+            duties: duties.get(&vigilant.id).copied().unwrap_or(0),
+        })
+        .collect();
+    members.sort_by(|a, b| {
+        b.duties
+            .cmp(&a.duties)
+            .then_with(|| a.vigilant.cmp(&b.vigilant))
+    });
+    let total: usize = members.iter().map(|member| member.duties).sum();
+    WorkloadReport {
+        min: members
+            .iter()
+            .map(|member| member.duties)
+            .min()
+            .unwrap_or(0),
+        max: members
+            .iter()
+            .map(|member| member.duties)
+            .max()
+            .unwrap_or(0),
+        mean: if members.is_empty() {
+            0.0
+        } else {
+            total as f64 / members.len() as f64
+        },
+        duties: members,
+    }
+}
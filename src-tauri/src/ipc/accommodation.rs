@@ -0,0 +1,11 @@
+use tauri::command;
+
+use crate::models::{
+    accommodation::{check_accommodations, AccommodationIssue},
+    AppValues,
+};
+
+#[command]
+pub async fn check_assignment_accommodations(values: AppValues) -> Vec<AccommodationIssue> {
+    check_accommodations(&values)
+}
@@ -0,0 +1,179 @@
+use log::{error, info};
+use tauri::command;
+
+use crate::{
+    assignment::{
+        assign,
+        centres::{centre_spread, ClassroomCentres},
+        conflicts::{schedule_conflicts, ConflictResolution, ScheduleConflict},
+        diagnosis::{diagnose, Diagnosis},
+        interest::{interest_conflicts, InterestConflict, InterestRules},
+        optimiser::{optimise, OptimisationResult, OptimisationSettings},
+        reassign,
+        seating::{assign_seats, SeatingError, SeatingResult, SeatingStrategy},
+        stability::{room_changes, ExamineeRoomChanges},
+        workload::{balance_workload, workload, BalanceResult, WorkloadError, WorkloadReport},
+        AssignmentError, AssignmentResult,
+    },
+    models::{AppValues, EntityId},
+};
+
+/// The values an assignment is made on: the whole project, or only the given session of it.
+/// `unknown` makes the error of the command for a session that is not in the project
+fn session_values<E>(
+    values: AppValues,
+    session: Option<EntityId>,
+    unknown: impl FnOnce(EntityId) -> E,
+) -> Result<AppValues, E> {
+    match session {
+        Some(session) => values.in_session(&session).ok_or_else(|| unknown(session)),
+        None => Ok(values),
+    }
+}
+
+fn unknown_session(session: EntityId) -> AssignmentError {
+    AssignmentError::UnknownSession { session }
+}
+
+#[command]
+pub async fn make_assignment(
+    values: AppValues,
+    session: Option<EntityId>,
+    conflicts: ConflictResolution,
+    interest: InterestRules,
+) -> Result<AssignmentResult, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    info!(
+        "Assigning {} subjects with {} examinees, {} classrooms and {} vigilants",
+        values.subjects.len(),
+        values.examinees.len(),
+        values.classrooms.len(),
+        values.vigilants.len()
+    );
+    assign(&values, &conflicts, &interest)
+}
+
+#[command]
+pub async fn reassign_assignment(
+    values: AppValues,
+    session: Option<EntityId>,
+    conflicts: ConflictResolution,
+    interest: InterestRules,
+) -> Result<AssignmentResult, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    info!(
+        "Assigning again {} subjects keeping what is locked",
+        values.subjects.len()
+    );
+    reassign(&values, &conflicts, &interest)
+}
+
+#[command]
+pub async fn optimise_assignment(
+    values: AppValues,
+    session: Option<EntityId>,
+    settings: OptimisationSettings,
+) -> Result<OptimisationResult, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    info!(
+        "Optimising the assignment of {} subjects for {}ms",
+        values.subjects.len(),
+        settings.time_budget
+    );
+    // The search keeps a thread busy for the whole time budget
+    tauri::async_runtime::spawn_blocking(move || optimise(&values, &settings))
+        .await
+        .unwrap_or_else(|error| {
+            error!("The search for a better assignment stopped: {error}");
+            Err(AssignmentError::SearchFailed)
+        })
+}
+
+#[command]
+pub async fn diagnose_assignment(
+    values: AppValues,
+    session: Option<EntityId>,
+    conflicts: ConflictResolution,
+    interest: InterestRules,
+) -> Result<Diagnosis, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    Ok(diagnose(&values, &conflicts, &interest))
+}
+
+#[command]
+pub async fn report_centre_spread(
+    values: AppValues,
+    session: Option<EntityId>,
+    max_share: u32,
+) -> Result<Vec<ClassroomCentres>, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    Ok(centre_spread(&values, max_share))
+}
+
+#[command]
+pub async fn check_schedule_conflicts(
+    values: AppValues,
+    session: Option<EntityId>,
+) -> Result<Vec<ScheduleConflict>, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    Ok(schedule_conflicts(&values))
+}
+
+#[command]
+pub async fn check_interest_conflicts(
+    values: AppValues,
+    session: Option<EntityId>,
+    interest: InterestRules,
+) -> Result<Vec<InterestConflict>, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    Ok(interest_conflicts(&values, &interest))
+}
+
+#[command]
+pub async fn seat_examinees(
+    values: AppValues,
+    session: Option<EntityId>,
+    strategy: SeatingStrategy,
+) -> Result<SeatingResult, SeatingError> {
+    let values = session_values(values, session, |session| SeatingError::UnknownSession {
+        session,
+    })?;
+    info!("Assigning the seats of the examinees with {strategy:?}");
+    assign_seats(&values, &strategy)
+}
+
+#[command]
+pub async fn report_room_changes(
+    values: AppValues,
+    session: Option<EntityId>,
+) -> Result<Vec<ExamineeRoomChanges>, AssignmentError> {
+    let values = session_values(values, session, unknown_session)?;
+    Ok(room_changes(&values))
+}
+
+#[command]
+pub async fn report_vigilant_workload(
+    values: AppValues,
+    session: Option<EntityId>,
+) -> Result<WorkloadReport, WorkloadError> {
+    let values = session_values(values, session, |session| WorkloadError::UnknownSession {
+        session,
+    })?;
+    workload(&values)
+}
+
+#[command]
+pub async fn balance_vigilant_workload(
+    values: AppValues,
+    session: Option<EntityId>,
+    interest: InterestRules,
+) -> Result<BalanceResult, WorkloadError> {
+    let values = session_values(values, session, |session| WorkloadError::UnknownSession {
+        session,
+    })?;
+    info!(
+        "Balancing the duties of {} vigilants",
+        values.vigilants.len()
+    );
+    balance_workload(&values, &interest)
+}
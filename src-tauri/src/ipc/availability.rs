@@ -0,0 +1,11 @@
+use tauri::command;
+
+use crate::models::{
+    availability::{check_availability, AvailabilityIssue},
+    AppValues,
+};
+
+#[command]
+pub async fn check_assignment_availability(values: AppValues) -> Vec<AvailabilityIssue> {
+    check_availability(&values)
+}
@@ -0,0 +1,53 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+use ts_rs::TS;
+
+use crate::{
+    models::{
+        diff::{diff, ProjectDiff, ReportLabels},
+        AppValues,
+    },
+    storage::read::{load_from_file, ProjectFile, ReadFromFileError},
+};
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ProjectSource {
+    File(ProjectFile),
+    Values { values: AppValues },
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum DiffProjectsError {
+    Before { error: ReadFromFileError },
+    After { error: ReadFromFileError },
+}
+
+impl ProjectSource {
+    fn load(self) -> Result<AppValues, ReadFromFileError> {
+        match self {
+            ProjectSource::File(file) => load_from_file(file.file, file.password),
+            ProjectSource::Values { values } => Ok(values),
+        }
+    }
+}
+
+#[command]
+pub async fn diff_projects(
+    before: ProjectSource,
+    after: ProjectSource,
+    labels: Option<ReportLabels>,
+) -> Result<ProjectDiff, DiffProjectsError> {
+    info!("Comparing projects");
+    let before = before
+        .load()
+        .map_err(|error| DiffProjectsError::Before { error })?;
+    let after = after
+        .load()
+        .map_err(|error| DiffProjectsError::After { error })?;
+    Ok(diff(&before, &after, &labels.unwrap_or_default()))
+}
@@ -47,3 +47,25 @@ pub async fn export_assignment(
 
     Ok(())
 }
+
+#[command]
+pub async fn export_report(file: String, report: String) -> Result<(), ExportAssignmentError> {
+    if let Some(parent) = Path::new(&file).parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return Err(ExportAssignmentError::CreateDirectories {
+                path: parent.to_string_lossy().into_owned(),
+            });
+        }
+    }
+
+    let mut output = match File::create(&file) {
+        Ok(output) => output,
+        Err(_) => return Err(ExportAssignmentError::CreateFile { file }),
+    };
+
+    if write!(output, "{}", report).is_err() {
+        return Err(ExportAssignmentError::WriteFile { file });
+    }
+
+    Ok(())
+}
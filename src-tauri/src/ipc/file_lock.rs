@@ -0,0 +1,22 @@
+use log::{info, warn};
+use tauri::command;
+
+use crate::storage::lock::{release_lock, take_over_lock, FileLock, FileLockError};
+
+#[command]
+pub async fn take_over_file_lock(file: String) -> Result<Option<FileLock>, FileLockError> {
+    let previous = take_over_lock(&file)?;
+    if let Some(lock) = &previous {
+        warn!(
+            "Took over the lock of {file} held by {} at {} (pid {})",
+            lock.owner, lock.host, lock.pid
+        );
+    }
+    Ok(previous)
+}
+
+#[command]
+pub async fn release_file_lock(file: String) -> Result<(), FileLockError> {
+    info!("Releasing the lock of {file}");
+    release_lock(&file)
+}
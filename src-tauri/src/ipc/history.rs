@@ -0,0 +1,62 @@
+use log::info;
+use serde::Serialize;
+use tauri::command;
+use ts_rs::TS;
+
+use crate::models::{
+    history::{values_at, HistoryEntry, HistoryOperation, ProjectHistories},
+    AppValues,
+};
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum RevertHistoryError {
+    /// The file was not loaded or saved in this session
+    NotOpened,
+    UnknownEntry,
+    /// The history does not match the values of the file
+    Corrupt {
+        message: String,
+    },
+}
+
+#[command]
+pub async fn list_history(
+    histories: tauri::State<'_, ProjectHistories>,
+    file: String,
+) -> Result<Vec<HistoryEntry>, RevertHistoryError> {
+    histories
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&file)
+        .map(|history| history.entries.clone())
+        .ok_or(RevertHistoryError::NotOpened)
+}
+
+/// Values of the file as they were after the given entry of its history. The revert is recorded
+/// the next time the file is saved
+#[command]
+pub async fn revert_to_history(
+    histories: tauri::State<'_, ProjectHistories>,
+    file: String,
+    entry: usize,
+) -> Result<AppValues, RevertHistoryError> {
+    info!("Reverting {file} to entry {entry} of its history");
+    let mut histories = histories
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let history = histories
+        .get_mut(&file)
+        .ok_or(RevertHistoryError::NotOpened)?;
+    if entry >= history.entries.len() {
+        return Err(RevertHistoryError::UnknownEntry);
+    }
+    let values = values_at(&history.persisted, &history.entries, entry).map_err(|err| {
+        RevertHistoryError::Corrupt {
+            message: err.to_string(),
+        }
+    })?;
+    history.pending = Some(HistoryOperation::Revert { to: entry });
+    Ok(values)
+}
@@ -3,15 +3,19 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use calamine::{Data, DataType, Reader};
+use calamine::{Data, DataType, ExcelDateTime, Reader};
+use chrono::{Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use tauri::command;
 use ts_rs::TS;
 
 use crate::models::{
-    examinee::ImportedExaminee,
+    building::ImportedBuilding,
+    classroom::ImportedClassroom,
+    examinee::{Accommodations, ImportedExaminee},
     subject::{ImportedSubject, SubjectKind},
+    vigilant::{AvailabilityWindow, ImportedAvailability, WindowPart},
 };
 
 #[derive(Deserialize, TS)]
@@ -30,6 +34,9 @@ pub struct ExamineeImportSettings {
     subject_kind_column: usize,
     origin_column: usize,
     academic_centre_column: usize,
+    #[serde(default)]
+    #[ts(optional)]
+    accommodations_column: Option<usize>,
 }
 
 #[derive(Serialize, Clone, TS)]
@@ -52,6 +59,7 @@ pub enum ExamineeImportColumn {
     ExamineeOrigin,
     ExamineeCourt,
     ExamineeAcademicCentre,
+    ExamineeAccommodations,
 }
 
 #[derive(Serialize, TS)]
@@ -59,6 +67,7 @@ pub enum ExamineeImportColumn {
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub enum ExamineeImportInvalidValueError {
     CourtIsNotNumber,
+    AccommodationsNotUnderstood,
 }
 
 #[derive(Serialize, TS)]
@@ -105,6 +114,7 @@ pub struct ExamineeForImport {
     origin: Option<String>,
     court: Option<i16>,
     academic_centre: Option<String>,
+    accommodations: Option<Accommodations>,
     subjects: HashSet<String>,
 }
 
@@ -149,6 +159,7 @@ impl ExamineeForImport {
             .clone();
         let subjects = self.subjects.into_iter().collect();
         let academic_centre = self.academic_centre;
+        let accommodations = self.accommodations.unwrap_or_default();
         Ok(ImportedExaminee {
             nif,
             name,
@@ -157,6 +168,7 @@ impl ExamineeForImport {
             court,
             subjects,
             academic_centre,
+            accommodations,
         })
     }
 }
@@ -208,6 +220,8 @@ pub async fn start_examinee_import_process(
                 .into_iter()
                 .filter_map(|cell| match cell.2 {
                     Data::Empty | Data::Error(_) => None,
+                    Data::DateTime(date) => Some((cell.0, cell.1, excel_date_time(date))),
+                    Data::DateTimeIso(date) => Some((cell.0, cell.1, date.clone())),
                     _ => Some((cell.0, cell.1, cell.2.as_string().unwrap_or("".to_owned()))),
                 })
                 .fold(Vec::new(), |mut acc, cell| {
@@ -348,6 +362,7 @@ struct ExamineeRowValues<'a> {
     surenames: String,
     origin: &'a String,
     court: i16,
+    accommodations: Option<Accommodations>,
     identifier: &'a String,
 }
 fn extract_examinee_values_from_row<'a>(
@@ -401,6 +416,20 @@ fn extract_examinee_values_from_row<'a>(
             invalid_value: row[settings.court_column].to_owned(),
             reason: ExamineeImportInvalidValueError::CourtIsNotNumber,
         })?;
+    let accommodations = match settings
+        .accommodations_column
+        .and_then(|column| row.get(column))
+        .filter(|s| !s.trim().is_empty())
+    {
+        Some(accommodations) => Some(Accommodations::parse(accommodations).ok_or_else(|| {
+            ExamineeImportError::InvalidValue {
+                row: index + 1,
+                invalid_value: accommodations.to_owned(),
+                reason: ExamineeImportInvalidValueError::AccommodationsNotUnderstood,
+            }
+        })?),
+        None => None,
+    };
     Ok(ExamineeRowValues {
         identifier,
         nif,
@@ -408,6 +437,7 @@ fn extract_examinee_values_from_row<'a>(
         surenames,
         origin,
         court,
+        accommodations,
     })
 }
 
@@ -487,6 +517,22 @@ fn check_and_update_examinee(
         });
     }
 
+    if let Some(row_accommodations) = row_values.accommodations {
+        if *row_examinee
+            .accommodations
+            .get_or_insert_with(|| row_accommodations.clone())
+            != row_accommodations
+        {
+            return Err(ExamineeImportError::MissmatchValue {
+                row: index + 1,
+                identifier: row_values.identifier.clone(),
+                missmatch: ExamineeImportColumn::ExamineeAccommodations,
+                established_value: format!("{:?}", row_examinee.accommodations.clone().unwrap()),
+                found_value: format!("{:?}", row_accommodations),
+            });
+        }
+    }
+
     if let Option::Some(row_academic_centre) = row_academic_centre {
         if row_examinee
             .academic_centre
@@ -552,3 +598,429 @@ fn extract_import_examinees_state(
         Err(_) => Err(ExamineeImportError::Lock),
     }
 }
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ClassroomImportSettings {
+    selected_sheet: String,
+    first_row_is_header: bool,
+
+    code_column: usize,
+    location_code_column: usize,
+    total_capacity_column: usize,
+    /// Without it, the exam capacity is a third of the total one
+    exam_capacity_column: Option<usize>,
+    building_column: Option<usize>,
+    floor_column: Option<usize>,
+    campus_column: Option<usize>,
+    address_column: Option<usize>,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ClassroomImportValues {
+    classrooms: Vec<ImportedClassroom>,
+    buildings: Vec<ImportedBuilding>,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ClassroomImportColumn {
+    ClassroomCode,
+    ClassroomLocationCode,
+    ClassroomTotalCapacity,
+    ClassroomExamCapacity,
+    ClassroomFloor,
+    BuildingCampus,
+    BuildingAddress,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ClassroomImportError {
+    Lock,
+    NoValuesLoaded,
+    NoSheet,
+    MissingValue {
+        row: usize,
+        missing: ClassroomImportColumn,
+    },
+    #[serde(rename_all = "camelCase")]
+    InvalidValue {
+        row: usize,
+        column: ClassroomImportColumn,
+        invalid_value: String,
+    },
+    /// The building appears in more than one row with different data
+    #[serde(rename_all = "camelCase")]
+    MissmatchValue {
+        row: usize,
+        building: String,
+        missmatch: ClassroomImportColumn,
+        established_value: String,
+        found_value: String,
+    },
+}
+
+/// Reads the classrooms, and the buildings they are in, of a sheet loaded with
+/// `start_examinee_import_process`
+#[command]
+pub async fn perform_classroom_import(
+    state: tauri::State<'_, Arc<Mutex<Option<Vec<SheetData>>>>>,
+    import_settings: ClassroomImportSettings,
+) -> Result<ClassroomImportValues, ClassroomImportError> {
+    let sheets = match state.lock() {
+        Ok(mut guard) => guard.take().ok_or(ClassroomImportError::NoValuesLoaded)?,
+        Err(_) => return Err(ClassroomImportError::Lock),
+    };
+    let sheet = sheets
+        .iter()
+        .find(|sheet| sheet.name == import_settings.selected_sheet)
+        .ok_or(ClassroomImportError::NoSheet)?;
+
+    let start_index = if import_settings.first_row_is_header {
+        1
+    } else {
+        0
+    };
+
+    let mut classrooms = Vec::new();
+    let mut buildings = Vec::<ImportedBuilding>::new();
+    for index in start_index..sheet.values.len() {
+        let row = &sheet.values[index];
+        if row.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let classroom = extract_classroom_from_row(row, index, &import_settings)?;
+        if let Some(building) = &classroom.building {
+            update_buildings_list(&mut buildings, building, row, index, &import_settings)?;
+        }
+        classrooms.push(classroom);
+    }
+
+    Ok(ClassroomImportValues {
+        classrooms,
+        buildings,
+    })
+}
+
+fn extract_classroom_from_row(
+    row: &[String],
+    index: usize,
+    settings: &ClassroomImportSettings,
+) -> Result<ImportedClassroom, ClassroomImportError> {
+    let required = |column: usize, missing: ClassroomImportColumn| {
+        row.get(column)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .ok_or(ClassroomImportError::MissingValue {
+                row: index + 1,
+                missing,
+            })
+    };
+    let optional = |column: Option<usize>| {
+        column
+            .and_then(|column| row.get(column))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+    };
+    let invalid = |value: &str, column: ClassroomImportColumn| ClassroomImportError::InvalidValue {
+        row: index + 1,
+        column,
+        invalid_value: value.to_owned(),
+    };
+
+    let code = required(settings.code_column, ClassroomImportColumn::ClassroomCode)?;
+    let location_code = required(
+        settings.location_code_column,
+        ClassroomImportColumn::ClassroomLocationCode,
+    )?;
+    let total_capacity = required(
+        settings.total_capacity_column,
+        ClassroomImportColumn::ClassroomTotalCapacity,
+    )?;
+    let total_capacity = whole_number::<u32>(total_capacity).ok_or_else(|| {
+        invalid(
+            total_capacity,
+            ClassroomImportColumn::ClassroomTotalCapacity,
+        )
+    })?;
+    let exam_capacity = match optional(settings.exam_capacity_column) {
+        Some(exam_capacity) => whole_number::<u32>(exam_capacity)
+            .ok_or_else(|| invalid(exam_capacity, ClassroomImportColumn::ClassroomExamCapacity))?,
+        None => total_capacity / 3,
+    };
+    let floor = optional(settings.floor_column)
+        .map(|floor| {
+            whole_number::<i16>(floor)
+                .ok_or_else(|| invalid(floor, ClassroomImportColumn::ClassroomFloor))
+        })
+        .transpose()?;
+
+    Ok(ImportedClassroom {
+        code: code.to_owned(),
+        location_code: location_code.to_owned(),
+        total_capacity,
+        exam_capacity,
+        building: optional(settings.building_column).map(str::to_owned),
+        floor,
+    })
+}
+
+/// The value as a whole number of the type, like `12` or `12.0`. `None` if it is not a whole
+/// number or does not fit the type, like a negative capacity
+fn whole_number<T: TryFrom<i64>>(value: &str) -> Option<T> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.fract() == 0.0 && number.abs() <= i64::MAX as f64)
+        .and_then(|number| T::try_from(number as i64).ok())
+}
+
+fn update_buildings_list(
+    buildings: &mut Vec<ImportedBuilding>,
+    name: &str,
+    row: &[String],
+    index: usize,
+    settings: &ClassroomImportSettings,
+) -> Result<(), ClassroomImportError> {
+    let value = |column: Option<usize>| {
+        column
+            .and_then(|column| row.get(column))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(str::to_owned)
+    };
+    let campus = value(settings.campus_column);
+    let address = value(settings.address_column);
+
+    let Some(building) = buildings.iter_mut().find(|building| building.name == name) else {
+        buildings.push(ImportedBuilding {
+            name: name.to_owned(),
+            campus,
+            address,
+        });
+        return Ok(());
+    };
+    for (established, found, missmatch) in [
+        (
+            &mut building.campus,
+            campus,
+            ClassroomImportColumn::BuildingCampus,
+        ),
+        (
+            &mut building.address,
+            address,
+            ClassroomImportColumn::BuildingAddress,
+        ),
+    ] {
+        let Some(found) = found else {
+            continue;
+        };
+        match established {
+            Some(established) if *established != found => {
+                return Err(ClassroomImportError::MissmatchValue {
+                    row: index + 1,
+                    building: name.to_owned(),
+                    missmatch,
+                    established_value: established.clone(),
+                    found_value: found,
+                });
+            }
+            Some(_) => {}
+            None => *established = Some(found),
+        }
+    }
+    Ok(())
+}
+
+/// Excel stores dates as the days since 1899-12-30, they are turned into dates without offset
+fn excel_date_time(date: &ExcelDateTime) -> String {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|epoch| epoch.and_hms_opt(0, 0, 0))
+        .unwrap();
+    let milliseconds = (date.as_f64() * 86_400_000.0).round() as i64;
+    (epoch + Duration::milliseconds(milliseconds))
+        .format("%Y-%m-%dT%H:%M:%S")
+        .to_string()
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct AvailabilityImportSettings {
+    selected_sheet: String,
+    first_row_is_header: bool,
+
+    name_column: usize,
+    surenames_column: usize,
+    /// A vigilant can have many rows, one for each window
+    start_column: usize,
+    end_column: usize,
+    max_duties_column: Option<usize>,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct AvailabilityImportValues {
+    vigilants: Vec<ImportedAvailability>,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AvailabilityImportColumn {
+    VigilantName,
+    AvailabilityStart,
+    AvailabilityEnd,
+    MaxDuties,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AvailabilityImportError {
+    Lock,
+    NoValuesLoaded,
+    NoSheet,
+    MissingValue {
+        row: usize,
+        missing: AvailabilityImportColumn,
+    },
+    /// The value can not be read, or the window ends before it starts
+    #[serde(rename_all = "camelCase")]
+    InvalidValue {
+        row: usize,
+        column: AvailabilityImportColumn,
+        invalid_value: String,
+    },
+    /// The vigilant appears in more than one row with a different maximum of duties
+    #[serde(rename_all = "camelCase")]
+    MissmatchValue {
+        row: usize,
+        vigilant: String,
+        established_value: String,
+        found_value: String,
+    },
+}
+
+/// Reads the availability windows and maximum duties of the vigilants of a sheet loaded with
+/// `start_examinee_import_process`
+#[command]
+pub async fn perform_availability_import(
+    state: tauri::State<'_, Arc<Mutex<Option<Vec<SheetData>>>>>,
+    import_settings: AvailabilityImportSettings,
+) -> Result<AvailabilityImportValues, AvailabilityImportError> {
+    let sheets = match state.lock() {
+        Ok(mut guard) => guard
+            .take()
+            .ok_or(AvailabilityImportError::NoValuesLoaded)?,
+        Err(_) => return Err(AvailabilityImportError::Lock),
+    };
+    let sheet = sheets
+        .iter()
+        .find(|sheet| sheet.name == import_settings.selected_sheet)
+        .ok_or(AvailabilityImportError::NoSheet)?;
+
+    let start_index = if import_settings.first_row_is_header {
+        1
+    } else {
+        0
+    };
+
+    let mut vigilants = Vec::<ImportedAvailability>::new();
+    for index in start_index..sheet.values.len() {
+        let row = &sheet.values[index];
+        if row.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        update_availability_list(&mut vigilants, row, index, &import_settings)?;
+    }
+
+    Ok(AvailabilityImportValues { vigilants })
+}
+
+fn update_availability_list(
+    vigilants: &mut Vec<ImportedAvailability>,
+    row: &[String],
+    index: usize,
+    settings: &AvailabilityImportSettings,
+) -> Result<(), AvailabilityImportError> {
+    let value = |column: usize| row.get(column).map(|s| s.trim()).filter(|s| !s.is_empty());
+    let required = |column: usize, missing: AvailabilityImportColumn| {
+        value(column).ok_or(AvailabilityImportError::MissingValue {
+            row: index + 1,
+            missing,
+        })
+    };
+
+    let name = required(settings.name_column, AvailabilityImportColumn::VigilantName)?;
+    let surenames = value(settings.surenames_column).unwrap_or_default();
+    let start = required(
+        settings.start_column,
+        AvailabilityImportColumn::AvailabilityStart,
+    )?;
+    let end = required(
+        settings.end_column,
+        AvailabilityImportColumn::AvailabilityEnd,
+    )?;
+    let window = AvailabilityWindow::parse(start, end).map_err(|part| {
+        let (column, invalid_value) = match part {
+            WindowPart::Start => (AvailabilityImportColumn::AvailabilityStart, start),
+            WindowPart::End => (AvailabilityImportColumn::AvailabilityEnd, end),
+        };
+        AvailabilityImportError::InvalidValue {
+            row: index + 1,
+            column,
+            invalid_value: invalid_value.to_owned(),
+        }
+    })?;
+    let max_duties = settings
+        .max_duties_column
+        .and_then(value)
+        .map(|max_duties| {
+            max_duties
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.fract() == 0.0 && (0.0..=u16::MAX as f64).contains(number))
+                .map(|number| number as u16)
+                .ok_or_else(|| AvailabilityImportError::InvalidValue {
+                    row: index + 1,
+                    column: AvailabilityImportColumn::MaxDuties,
+                    invalid_value: max_duties.to_owned(),
+                })
+        })
+        .transpose()?;
+
+    let Some(vigilant) = vigilants
+        .iter_mut()
+        .find(|vigilant| vigilant.name == name && vigilant.surenames == surenames)
+    else {
+        vigilants.push(ImportedAvailability {
+            name: name.to_owned(),
+            surenames: surenames.to_owned(),
+            availability: vec![window],
+            max_duties,
+        });
+        return Ok(());
+    };
+    vigilant.availability.push(window);
+    match (vigilant.max_duties, max_duties) {
+        (Some(established), Some(found)) if established != found => {
+            return Err(AvailabilityImportError::MissmatchValue {
+                row: index + 1,
+                vigilant: format!("{surenames}, {name}"),
+                established_value: established.to_string(),
+                found_value: found.to_string(),
+            });
+        }
+        (None, Some(found)) => vigilant.max_duties = Some(found),
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use log::info;
+use serde::Serialize;
+use tauri::command;
+use ts_rs::TS;
+
+use crate::{
+    models::{
+        merge::{merge, MergeResult},
+        AppValues,
+    },
+    storage::read::{load_from_file, ProjectFile, ReadFromFileError},
+};
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum MergeFilesError {
+    Ancestor { error: ReadFromFileError },
+    Theirs { error: ReadFromFileError },
+}
+
+#[command]
+pub async fn merge_files(
+    ancestor: ProjectFile,
+    ours: AppValues,
+    theirs: ProjectFile,
+) -> Result<MergeResult, MergeFilesError> {
+    info!(
+        "Merging changes from {} since {}",
+        theirs.file, ancestor.file
+    );
+    let ancestor = load_from_file(ancestor.file, ancestor.password)
+        .map_err(|error| MergeFilesError::Ancestor { error })?;
+    let theirs = load_from_file(theirs.file, theirs.password)
+        .map_err(|error| MergeFilesError::Theirs { error })?;
+    Ok(merge(&ancestor, &ours, &theirs))
+}
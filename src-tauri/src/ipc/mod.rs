@@ -1,5 +1,13 @@
+pub mod accommodation;
+pub mod assignment;
+pub mod availability;
+pub mod diff;
 pub mod export;
+pub mod file_lock;
+pub mod history;
 pub mod import;
+pub mod merge;
 pub mod open;
 pub mod open_file;
 pub mod save_file;
+pub mod verify_file;
@@ -1,11 +1,20 @@
-use std::env;
+use std::{env, mem};
 
-use log::info;
+use log::{info, warn};
 use tauri::command;
 
 use crate::{
-    models::AppValues,
-    storage::read::{load_from_file, ReadFromFileError},
+    models::{
+        court::DEFAULT_COURT_NAME,
+        history::{ProjectHistories, ProjectHistory},
+        validation::{repair as repair_values, validate},
+        AppValues,
+    },
+    storage::{
+        fingerprint::{record_fingerprint, OpenedFiles},
+        lock::{acquire_lock, check_lock},
+        read::{load_project, ReadFromFileError},
+    },
 };
 
 #[command]
@@ -14,7 +23,54 @@ pub async fn open_file_from_open_with() -> Result<Vec<String>, ()> {
 }
 
 #[command]
-pub async fn load_file(file: String, password: String) -> Result<AppValues, ReadFromFileError> {
+pub async fn load_file(
+    opened_files: tauri::State<'_, OpenedFiles>,
+    histories: tauri::State<'_, ProjectHistories>,
+    file: String,
+    password: String,
+    repair: Option<bool>,
+    court_name: Option<String>,
+) -> Result<AppValues, ReadFromFileError> {
     info!("Opening file {file}");
-    load_from_file(file, password)
+    check_lock(&file).map_err(|case| ReadFromFileError::Lock { case })?;
+    let mut project = load_project(
+        file.clone(),
+        password,
+        court_name.as_deref().unwrap_or(DEFAULT_COURT_NAME),
+    )?;
+    if !project.created_courts.is_empty() {
+        info!(
+            "Created the courts {:?} used in {file}",
+            project.created_courts
+        );
+    }
+    if repair.unwrap_or(false) {
+        let issues = repair_values(&mut project.values);
+        if !issues.is_empty() || !project.unreadable.is_empty() {
+            warn!(
+                "Repaired {} integrity issues of {file}",
+                issues.len() + project.unreadable.len()
+            );
+        }
+    } else {
+        let mut issues = mem::take(&mut project.unreadable);
+        issues.extend(validate(&project.values));
+        if !issues.is_empty() {
+            return Err(ReadFromFileError::Integrity { issues });
+        }
+    }
+    acquire_lock(&file).map_err(|case| ReadFromFileError::Lock { case })?;
+    record_fingerprint(&opened_files, &file);
+    histories
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(
+            file,
+            ProjectHistory {
+                persisted: project.values.clone(),
+                entries: project.history,
+                pending: None,
+            },
+        );
+    Ok(project.values)
 }
@@ -1,17 +1,95 @@
-use log::info;
+use log::{info, warn};
 use tauri::command;
 
 use crate::{
-    models::AppValues,
-    storage::write::{save_to_file, SaveToFileError},
+    models::{
+        history::{HistoryEntry, HistoryOperation, ProjectHistories, ProjectHistory},
+        validation::{repair as repair_values, validate},
+        AppValues,
+    },
+    storage::{
+        fingerprint::{copy_path, record_fingerprint, was_modified, OpenedFiles},
+        lock::{acquire_lock, current_user},
+        write::{save_to_file, SaveToFileError},
+        StoredProject,
+    },
 };
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn save_file(
-    values: AppValues,
+    opened_files: tauri::State<'_, OpenedFiles>,
+    histories: tauri::State<'_, ProjectHistories>,
+    mut values: AppValues,
     file: String,
     password: String,
+    overwrite: Option<bool>,
+    operator: Option<String>,
+    repair: Option<bool>,
 ) -> Result<(), SaveToFileError> {
     info!("Saving to file {file}");
-    save_to_file(values, file, password)
+    if repair.unwrap_or(false) {
+        let issues = repair_values(&mut values);
+        if !issues.is_empty() {
+            warn!(
+                "Repaired {} integrity issues before saving {file}",
+                issues.len()
+            );
+        }
+    } else {
+        let issues = validate(&values);
+        if !issues.is_empty() {
+            return Err(SaveToFileError::Integrity { issues });
+        }
+    }
+    if was_modified(&opened_files, &file) {
+        if !overwrite.unwrap_or(false) {
+            return Err(SaveToFileError::ModifiedExternally {
+                copy_path: copy_path(&file),
+            });
+        }
+        warn!("Overwriting {file}, which was modified by someone else");
+    }
+    acquire_lock(&file).map_err(|case| SaveToFileError::Lock { case })?;
+
+    let mut histories = histories
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let operator = operator.unwrap_or_else(current_user);
+    let history = match histories.get(&file) {
+        Some(previous) => {
+            let mut entries = previous.entries.clone();
+            let operation = previous.pending.clone().unwrap_or(HistoryOperation::Save);
+            let entry =
+                HistoryEntry::record(Some(&previous.persisted), &values, operator, operation);
+            if !entry.changes.is_empty() || previous.pending.is_some() {
+                entries.push(entry);
+            }
+            entries
+        }
+        _ => vec![HistoryEntry::record(
+            None,
+            &values,
+            operator,
+            HistoryOperation::Create,
+        )],
+    };
+
+    let project = StoredProject {
+        values,
+        history,
+        unreadable: Vec::new(),
+        created_courts: Vec::new(),
+    };
+    save_to_file(&project, file.clone(), password)?;
+    record_fingerprint(&opened_files, &file);
+    histories.insert(
+        file,
+        ProjectHistory {
+            persisted: project.values,
+            entries: project.history,
+            pending: None,
+        },
+    );
+    Ok(())
 }
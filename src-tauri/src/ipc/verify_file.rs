@@ -0,0 +1,25 @@
+use log::info;
+use tauri::command;
+
+use crate::storage::{
+    read::ReadFromFileError,
+    verify::{salvage_from_file, verify_file as verify, FileVerification, SalvagedValues},
+};
+
+#[command]
+pub async fn verify_file(
+    file: String,
+    password: String,
+) -> Result<FileVerification, ReadFromFileError> {
+    info!("Verifying file {file}");
+    verify(file, password)
+}
+
+#[command]
+pub async fn salvage_file(
+    file: String,
+    password: String,
+) -> Result<SalvagedValues, ReadFromFileError> {
+    info!("Salvaging file {file}");
+    salvage_from_file(file, password)
+}
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![warn(unused_extern_crates)]
 
+mod assignment;
 mod event;
 mod ipc;
 mod models;
@@ -28,10 +29,34 @@ async fn main() -> Result<(), ()> {
             crate::ipc::import::start_examinee_import_process,
             crate::ipc::import::perform_examinee_import,
             crate::ipc::import::cancel_examinee_import,
+            crate::ipc::import::perform_classroom_import,
+            crate::ipc::import::perform_availability_import,
             crate::ipc::export::export_assignment,
+            crate::ipc::export::export_report,
             crate::ipc::open_file::open_file_from_open_with,
             crate::ipc::open_file::load_file,
             crate::ipc::save_file::save_file,
+            crate::ipc::file_lock::take_over_file_lock,
+            crate::ipc::file_lock::release_file_lock,
+            crate::ipc::history::list_history,
+            crate::ipc::history::revert_to_history,
+            crate::ipc::merge::merge_files,
+            crate::ipc::accommodation::check_assignment_accommodations,
+            crate::ipc::availability::check_assignment_availability,
+            crate::ipc::assignment::make_assignment,
+            crate::ipc::assignment::reassign_assignment,
+            crate::ipc::assignment::optimise_assignment,
+            crate::ipc::assignment::diagnose_assignment,
+            crate::ipc::assignment::check_schedule_conflicts,
+            crate::ipc::assignment::check_interest_conflicts,
+            crate::ipc::assignment::seat_examinees,
+            crate::ipc::assignment::report_centre_spread,
+            crate::ipc::assignment::report_room_changes,
+            crate::ipc::assignment::report_vigilant_workload,
+            crate::ipc::assignment::balance_vigilant_workload,
+            crate::ipc::diff::diff_projects,
+            crate::ipc::verify_file::verify_file,
+            crate::ipc::verify_file::salvage_file,
             crate::ipc::open::open_file,
         ])
         .plugin(
@@ -45,6 +70,8 @@ async fn main() -> Result<(), ()> {
         .manage(Arc::new(Mutex::new(
             Option::<Vec<ipc::import::SheetData>>::None,
         )))
+        .manage(storage::fingerprint::OpenedFiles::default())
+        .manage(models::history::ProjectHistories::default())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
     Ok(())
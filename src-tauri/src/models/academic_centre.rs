@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
 
-use super::EntityId;
+use super::{Entity, EntityId, EntityKind};
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
@@ -13,3 +13,19 @@ pub struct AcademicCentre {
     pub id: EntityId,
     pub name: String,
 }
+
+impl Entity for AcademicCentre {
+    const KIND: EntityKind = EntityKind::AcademicCentre;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+}
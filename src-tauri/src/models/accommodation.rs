@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{
+    classroom::{Classroom, ClassroomFeature},
+    examinee::Examinee,
+    AppValues, Entity, EntityId,
+};
+
+#[derive(Serialize, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AccommodationIssue {
+    /// The examinee needs wheelchair access but the classroom does not have it
+    InaccessibleClassroom {
+        examinee: EntityId,
+        subject: EntityId,
+        classroom: EntityId,
+    },
+    /// The examinee has to take the exam alone but shares the classroom with others
+    SharedRoom {
+        examinee: EntityId,
+        subject: EntityId,
+        classroom: EntityId,
+    },
+}
+
+/// Looks for examinees of the assignment whose classroom does not meet their accommodations
+pub fn check_accommodations(values: &AppValues) -> Vec<AccommodationIssue> {
+    let Some(assignation) = &values.assignation else {
+        return Vec::new();
+    };
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+    let classrooms: HashMap<&EntityId, &Classroom> = values
+        .classrooms
+        .iter()
+        .map(|classroom| (classroom.id(), classroom))
+        .collect();
+
+    let mut issues = Vec::new();
+    for exam in assignation.individual_exams() {
+        let Some(distribution) = &exam.distribution else {
+            continue;
+        };
+        for assigned in &distribution.distribution {
+            let accessible = classrooms
+                .get(&assigned.classroom)
+                .is_some_and(|classroom| {
+                    classroom
+                        .features
+                        .contains(&ClassroomFeature::WheelchairAccess)
+                });
+            for examinee_id in &assigned.examinees {
+                let Some(examinee) = examinees.get(examinee_id) else {
+                    continue;
+                };
+                if examinee.accommodations.accessible_room && !accessible {
+                    issues.push(AccommodationIssue::InaccessibleClassroom {
+                        examinee: examinee_id.clone(),
+                        subject: exam.subject.clone(),
+                        classroom: assigned.classroom.clone(),
+                    });
+                }
+                if examinee.accommodations.separate_room && assigned.examinees.len() > 1 {
+                    issues.push(AccommodationIssue::SharedRoom {
+                        examinee: examinee_id.clone(),
+                        subject: exam.subject.clone(),
+                        classroom: assigned.classroom.clone(),
+                    });
+                }
+            }
+        }
+    }
+    issues
+}
@@ -2,12 +2,57 @@ use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
 
-use super::EntityId;
+use super::{EntityId, EntityKind};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 
-pub struct AllExamConfiguration(Vec<ExamConfiguration>);
+pub struct AllExamConfiguration(pub Vec<ExamConfiguration>);
+
+impl AllExamConfiguration {
+    /// Every exam of the assignment, including the ones that collide with others
+    pub fn individual_exams(&self) -> Vec<&IndividualExam> {
+        self.0.iter().flat_map(ExamConfiguration::exams).collect()
+    }
+
+    /// Visits every id of an entity used by the assignment
+    pub fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        for configuration in &mut self.0 {
+            match configuration {
+                ExamConfiguration::IndividualExam(exam) => exam.for_each_reference_mut(visit),
+                ExamConfiguration::CollidingExams {
+                    exams,
+                    classrooms,
+                    vigilants,
+                } => {
+                    for exam in exams {
+                        exam.for_each_reference_mut(visit);
+                    }
+                    visit_all(EntityKind::Classroom, classrooms, visit);
+                    visit_all(EntityKind::Vigilant, vigilants, visit);
+                }
+            }
+        }
+    }
+
+    /// Drops the ids of entities for which `keep` returns false, together with the exams of
+    /// subjects that are dropped
+    pub fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.0.retain_mut(|configuration| match configuration {
+            ExamConfiguration::IndividualExam(exam) => exam.retain_references(keep),
+            ExamConfiguration::CollidingExams {
+                exams,
+                classrooms,
+                vigilants,
+            } => {
+                exams.retain_mut(|exam| exam.retain_references(keep));
+                classrooms.retain(|id| keep(EntityKind::Classroom, id));
+                vigilants.retain(|id| keep(EntityKind::Vigilant, id));
+                !exams.is_empty()
+            }
+        });
+    }
+}
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
@@ -22,6 +67,23 @@ pub enum ExamConfiguration {
     },
 }
 
+impl ExamConfiguration {
+    /// The exams of the slot, only one if it does not collide with others
+    pub fn exams(&self) -> &[IndividualExam] {
+        match self {
+            ExamConfiguration::IndividualExam(exam) => std::slice::from_ref(exam),
+            ExamConfiguration::CollidingExams { exams, .. } => exams.as_slice(),
+        }
+    }
+
+    pub fn exams_mut(&mut self) -> &mut [IndividualExam] {
+        match self {
+            ExamConfiguration::IndividualExam(exam) => std::slice::from_mut(exam),
+            ExamConfiguration::CollidingExams { exams, .. } => exams.as_mut_slice(),
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
 #[serde(rename_all = "camelCase")]
@@ -34,14 +96,78 @@ pub struct IndividualExam {
     pub distribution: Option<ExamDistribution>,
 }
 
+impl IndividualExam {
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        visit(EntityKind::Subject, &mut self.subject);
+        visit_all(EntityKind::Examinee, &mut self.examinees, visit);
+        visit_all(EntityKind::Classroom, &mut self.classrooms, visit);
+        visit_all(EntityKind::Vigilant, &mut self.vigilants, visit);
+        if let Some(distribution) = &mut self.distribution {
+            visit(EntityKind::Subject, &mut distribution.subject);
+            visit_all(EntityKind::Vigilant, &mut distribution.specialists, visit);
+            for classroom in &mut distribution.distribution {
+                visit(EntityKind::Classroom, &mut classroom.classroom);
+                visit_all(EntityKind::Examinee, &mut classroom.examinees, visit);
+                visit_all(EntityKind::Vigilant, &mut classroom.vigilants, visit);
+                visit_all(EntityKind::Examinee, &mut classroom.locked_examinees, visit);
+                visit_all(EntityKind::Vigilant, &mut classroom.locked_vigilants, visit);
+                for seat in &mut classroom.seats {
+                    visit(EntityKind::Examinee, &mut seat.examinee);
+                }
+            }
+        }
+    }
+
+    /// Returns false if the subject of the exam is dropped
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) -> bool {
+        if !keep(EntityKind::Subject, &self.subject) {
+            return false;
+        }
+        self.examinees.retain(|id| keep(EntityKind::Examinee, id));
+        self.classrooms.retain(|id| keep(EntityKind::Classroom, id));
+        self.vigilants.retain(|id| keep(EntityKind::Vigilant, id));
+        self.distribution = self
+            .distribution
+            .take()
+            .filter(|distribution| keep(EntityKind::Subject, &distribution.subject));
+        if let Some(distribution) = &mut self.distribution {
+            distribution
+                .specialists
+                .retain(|id| keep(EntityKind::Vigilant, id));
+            distribution.distribution.retain_mut(|classroom| {
+                if !keep(EntityKind::Classroom, &classroom.classroom) {
+                    return false;
+                }
+                classroom
+                    .examinees
+                    .retain(|id| keep(EntityKind::Examinee, id));
+                classroom
+                    .vigilants
+                    .retain(|id| keep(EntityKind::Vigilant, id));
+                classroom
+                    .locked_examinees
+                    .retain(|id| keep(EntityKind::Examinee, id));
+                classroom
+                    .locked_vigilants
+                    .retain(|id| keep(EntityKind::Vigilant, id));
+                classroom
+                    .seats
+                    .retain(|seat| keep(EntityKind::Examinee, &seat.examinee));
+                true
+            });
+        }
+        true
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub struct ExamDistribution {
-    subject: EntityId,
-    specialists: Vec<EntityId>,
-    distribution: Vec<ExamClassroomDistribution>,
+    pub subject: EntityId,
+    pub specialists: Vec<EntityId>,
+    pub distribution: Vec<ExamClassroomDistribution>,
 }
 
 #[skip_serializing_none]
@@ -49,7 +175,45 @@ pub struct ExamDistribution {
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub struct ExamClassroomDistribution {
-    classroom: EntityId,
-    examinees: Vec<EntityId>,
-    vigilants: Vec<EntityId>,
+    pub classroom: EntityId,
+    pub examinees: Vec<EntityId>,
+    pub vigilants: Vec<EntityId>,
+    /// The classroom is kept for the subject when the assignment is done again
+    #[serde(default)]
+    pub locked: bool,
+    /// Examinees that stay in the classroom when the assignment is done again
+    #[serde(default)]
+    pub locked_examinees: Vec<EntityId>,
+    /// Vigilants that stay in the classroom when the assignment is done again
+    #[serde(default)]
+    pub locked_vigilants: Vec<EntityId>,
+    /// Where the examinees sit, empty until the seats are assigned
+    #[serde(default)]
+    pub seats: Vec<ExamineeSeat>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ExamineeSeat {
+    pub examinee: EntityId,
+    /// Number of the seat in the grid of the classroom
+    pub seat: u32,
+}
+
+impl ExamClassroomDistribution {
+    /// If something of the classroom has to be kept when the assignment is done again
+    pub fn is_pinned(&self) -> bool {
+        self.locked || !self.locked_examinees.is_empty() || !self.locked_vigilants.is_empty()
+    }
+}
+
+fn visit_all(
+    kind: EntityKind,
+    ids: &mut [EntityId],
+    visit: &mut dyn FnMut(EntityKind, &mut EntityId),
+) {
+    for id in ids {
+        visit(kind, id);
+    }
 }
@@ -0,0 +1,151 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{
+    assignation::IndividualExam,
+    examinee::Examinee,
+    subject::Subject,
+    vigilant::{Vigilant, VigilantRole},
+    AppValues, Entity, EntityId,
+};
+
+#[derive(Serialize, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AvailabilityIssue {
+    /// The vigilant is assigned to an exam held outside their availability
+    Unavailable {
+        vigilant: EntityId,
+        subject: EntityId,
+    },
+    /// The vigilant is assigned to more exam slots than they can attend
+    #[serde(rename_all = "camelCase")]
+    TooManyDuties {
+        vigilant: EntityId,
+        duties: usize,
+        max_duties: u16,
+    },
+    /// Not enough vigilants can attend the slot to have one in each of its classrooms
+    UnsatisfiableSlot {
+        subjects: Vec<EntityId>,
+        needed: usize,
+        available: usize,
+    },
+}
+
+/// Looks for vigilants of the assignment that can not attend their exams and for exam slots
+/// that do not have enough available vigilants. Every entry of the assignment is a slot
+pub fn check_availability(values: &AppValues) -> Vec<AvailabilityIssue> {
+    let Some(assignation) = &values.assignation else {
+        return Vec::new();
+    };
+    let subjects: HashMap<&EntityId, &Subject> = values
+        .subjects
+        .iter()
+        .map(|subject| (subject.id(), subject))
+        .collect();
+    let vigilants: HashMap<&EntityId, &Vigilant> = values
+        .vigilants
+        .iter()
+        .map(|vigilant| (vigilant.id(), vigilant))
+        .collect();
+    let examinees: HashMap<&EntityId, &Examinee> = values
+        .examinees
+        .iter()
+        .map(|examinee| (examinee.id(), examinee))
+        .collect();
+    let exam_times = |exam: &IndividualExam| {
+        let subject = subjects.get(&exam.subject)?;
+        let examinees = exam
+            .examinees
+            .iter()
+            .filter_map(|id| examinees.get(id).copied());
+        Some((subject.exam_date?, subject.exam_end_for(examinees)?))
+    };
+
+    let mut issues = Vec::new();
+    let mut duties = HashMap::<&EntityId, usize>::new();
+    for configuration in &assignation.0 {
+        let exams = configuration.exams();
+        let mut slot_vigilants = BTreeSet::new();
+        for exam in exams {
+            let times = exam_times(exam);
+            for vigilant_id in assigned_vigilants(exam) {
+                slot_vigilants.insert(vigilant_id);
+                let (Some((start, end)), Some(vigilant)) = (times, vigilants.get(vigilant_id))
+                else {
+                    continue;
+                };
+                if !vigilant.is_available(start, end) {
+                    issues.push(AvailabilityIssue::Unavailable {
+                        vigilant: vigilant_id.clone(),
+                        subject: exam.subject.clone(),
+                    });
+                }
+            }
+        }
+        for vigilant_id in slot_vigilants {
+            *duties.entry(vigilant_id).or_default() += 1;
+        }
+
+        let Some((start, end)) =
+            exams
+                .iter()
+                .filter_map(exam_times)
+                .reduce(|(start, end), (other_start, other_end)| {
+                    (start.min(other_start), end.max(other_end))
+                })
+        else {
+            continue;
+        };
+        let needed = exams
+            .iter()
+            .map(|exam| match &exam.distribution {
+                Some(distribution) => distribution.distribution.len(),
+                None => exam.classrooms.len(),
+            })
+            .sum();
+        let available = values
+            .vigilants
+            .iter()
+            .filter(|vigilant| vigilant.role == VigilantRole::MEMBER)
+            .filter(|vigilant| vigilant.is_available(start, end))
+            .count();
+        if available < needed {
+            issues.push(AvailabilityIssue::UnsatisfiableSlot {
+                subjects: exams.iter().map(|exam| exam.subject.clone()).collect(),
+                needed,
+                available,
+            });
+        }
+    }
+
+    for vigilant in &values.vigilants {
+        let (Some(max_duties), Some(&duties)) = (vigilant.max_duties, duties.get(vigilant.id()))
+        else {
+            continue;
+        };
+        if duties > usize::from(max_duties) {
+            issues.push(AvailabilityIssue::TooManyDuties {
+                vigilant: vigilant.id().clone(),
+                duties,
+                max_duties,
+            });
+        }
+    }
+    issues
+}
+
+/// Vigilants of the exam, specialists and the ones in its classrooms included
+fn assigned_vigilants(exam: &IndividualExam) -> BTreeSet<&EntityId> {
+    let mut assigned: BTreeSet<&EntityId> = exam.vigilants.iter().collect();
+    if let Some(distribution) = &exam.distribution {
+        assigned.extend(&distribution.specialists);
+        for classroom in &distribution.distribution {
+            assigned.extend(&classroom.vigilants);
+        }
+    }
+    assigned
+}
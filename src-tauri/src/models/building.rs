@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use serde_with_macros::skip_serializing_none;
+use ts_rs::TS;
+
+use super::{Entity, EntityId, EntityKind};
+
+/// A building where there are classrooms used for the exams
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct Building {
+    pub id: EntityId,
+    pub name: String,
+    pub campus: Option<String>,
+    pub address: Option<String>,
+    /// Vigilant in charge of the building
+    pub president_id: Option<EntityId>,
+}
+
+impl Entity for Building {
+    const KIND: EntityKind = EntityKind::Building;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        match &self.campus {
+            Some(campus) => format!("{} ({campus})", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        if let Some(president_id) = &mut self.president_id {
+            visit(EntityKind::Vigilant, president_id);
+        }
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.president_id = self
+            .president_id
+            .take()
+            .filter(|president_id| keep(EntityKind::Vigilant, president_id));
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ImportedBuilding {
+    pub name: String,
+    pub campus: Option<String>,
+    pub address: Option<String>,
+}
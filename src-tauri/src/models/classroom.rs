@@ -1,8 +1,10 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
 
-use super::EntityId;
+use super::{subject::Subject, Entity, EntityId, EntityKind};
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, TS)]
@@ -16,7 +18,147 @@ pub struct Classroom {
     pub total_capacity: u32,
     pub exam_capacity: u32,
     pub priority: u32,
-    pub court_location: Option<i16>,
+    /// Court the classroom is reserved for, which keeps it out of the exams
+    pub court_location_id: Option<EntityId>,
+    /// Building the classroom is in and its floor there
+    pub building_id: Option<EntityId>,
+    pub floor: Option<i16>,
     pub kind: String,
     pub notes: Vec<String>,
+    #[serde(default)]
+    pub features: HashSet<ClassroomFeature>,
+    /// How the seats are laid out, without it the seats are just numbered
+    #[serde(default)]
+    pub seat_grid: Option<SeatGrid>,
+}
+
+/// Equipment of a classroom that some exams or examinees need
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum ClassroomFeature {
+    WheelchairAccess,
+    PowerOutlets,
+    DrawingTables,
+    /// Speakers for the listening part of language exams
+    AudioEquipment,
+    Laboratory,
+}
+
+/// Seats of a classroom in rows, numbered from 1 row by row starting at the front
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SeatGrid {
+    pub rows: u16,
+    pub seats_per_row: u16,
+    /// Seats that can not be used, like broken ones or the ones behind a column
+    #[serde(default)]
+    pub unusable_seats: Vec<u32>,
+    /// Seats left free between examinees as long as there is room for it
+    #[serde(default)]
+    pub spacing: SeatSpacing,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum SeatSpacing {
+    /// Every seat is used
+    #[default]
+    None,
+    /// A free seat between the examinees of a row
+    AlternateSeats,
+    /// A free row between rows of examinees
+    AlternateRows,
+    /// Nobody has an examinee in front, behind or beside
+    Checkerboard,
+}
+
+impl SeatGrid {
+    /// The usable seats in the order they are taken: first the ones of the spacing pattern, by
+    /// number, and then the rest
+    pub fn seat_order(&self) -> Vec<u32> {
+        let seats_per_row = u32::from(self.seats_per_row);
+        let mut spaced = Vec::new();
+        let mut rest = Vec::new();
+        for row in 0..u32::from(self.rows) {
+            for seat in 0..seats_per_row {
+                let number = row * seats_per_row + seat + 1;
+                if self.unusable_seats.contains(&number) {
+                    continue;
+                }
+                let in_pattern = match self.spacing {
+                    SeatSpacing::None => true,
+                    SeatSpacing::AlternateSeats => seat % 2 == 0,
+                    SeatSpacing::AlternateRows => row % 2 == 0,
+                    SeatSpacing::Checkerboard => (row + seat) % 2 == 0,
+                };
+                if in_pattern {
+                    spaced.push(number);
+                } else {
+                    rest.push(number);
+                }
+            }
+        }
+        spaced.extend(rest);
+        spaced
+    }
+}
+
+impl Classroom {
+    /// If the classroom has every feature the exam of the subject requires
+    pub fn suits(&self, subject: &Subject) -> bool {
+        subject.required_features.is_subset(&self.features)
+    }
+}
+
+impl Entity for Classroom {
+    const KIND: EntityKind = EntityKind::Classroom;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        self.code.clone()
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        if let Some(court_location_id) = &mut self.court_location_id {
+            visit(EntityKind::Court, court_location_id);
+        }
+        if let Some(building_id) = &mut self.building_id {
+            visit(EntityKind::Building, building_id);
+        }
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.court_location_id = self
+            .court_location_id
+            .take()
+            .filter(|court_location_id| keep(EntityKind::Court, court_location_id));
+        self.building_id = self
+            .building_id
+            .take()
+            .filter(|building_id| keep(EntityKind::Building, building_id));
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ImportedClassroom {
+    pub code: String,
+    pub location_code: String,
+    pub total_capacity: u32,
+    pub exam_capacity: u32,
+    /// Name of the building
+    pub building: Option<String>,
+    pub floor: Option<i16>,
 }
@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use serde_with_macros::skip_serializing_none;
+use ts_rs::TS;
+
+use super::{Entity, EntityId, EntityKind};
+
+/// Name given to the courts created for files without them when the frontend does not give one
+pub const DEFAULT_COURT_NAME: &str = "Court {number}";
+
+/// A tribunal in charge of the exams of some academic centres. Examinees, vigilants and
+/// classrooms refer to it by its id, files made before courts were entities by its number
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct Court {
+    pub id: EntityId,
+    pub number: i16,
+    pub name: String,
+    /// Where the court has its headquarters
+    pub venue: Option<String>,
+    /// Vigilant that presides the court
+    pub president_id: Option<EntityId>,
+    pub academic_centres_ids: Vec<EntityId>,
+}
+
+impl Entity for Court {
+    const KIND: EntityKind = EntityKind::Court;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        format!("{} ({})", self.name, self.number)
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        if let Some(president_id) = &mut self.president_id {
+            visit(EntityKind::Vigilant, president_id);
+        }
+        for academic_centre_id in &mut self.academic_centres_ids {
+            visit(EntityKind::AcademicCentre, academic_centre_id);
+        }
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.president_id = self
+            .president_id
+            .take()
+            .filter(|president_id| keep(EntityKind::Vigilant, president_id));
+        self.academic_centres_ids
+            .retain(|academic_centre_id| keep(EntityKind::AcademicCentre, academic_centre_id));
+    }
+}
+
+/// Fields that referred to courts by number in files made before courts were entities, with the
+/// list they are in and the field that refers to the court by id now
+const NUMBER_REFERENCES: [(&str, &str, &str); 3] = [
+    ("examinees", "court", "courtId"),
+    ("vigilants", "mainCourt", "mainCourtId"),
+    ("classrooms", "courtLocation", "courtLocationId"),
+];
+
+/// Turns the court numbers of examinees, vigilants and classrooms of files made before courts were
+/// entities into references to the courts with those numbers. The courts that do not exist are
+/// created, linked to the academic centres of their examinees and named after `name`, where
+/// `{number}` is replaced with their number. Works on the stored values so it can run before they
+/// are read. Returns the numbers of the created courts
+pub fn migrate_court_numbers(values: &mut Map<String, Value>, name: &str) -> Vec<i16> {
+    let mut courts: BTreeMap<i16, EntityId> = BTreeMap::new();
+    let mut ids = Vec::new();
+    for court in values
+        .get("courts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if let Ok(court) = serde_json::from_value::<Court>(court.clone()) {
+            courts.entry(court.number).or_insert(court.id.clone());
+            ids.push(court.id);
+        }
+    }
+
+    let mut missing: BTreeMap<i16, BTreeSet<EntityId>> = BTreeMap::new();
+    for (list, field, _) in NUMBER_REFERENCES {
+        for entity in values
+            .get(list)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(number) = court_number(entity.get(field)) else {
+                continue;
+            };
+            if courts.contains_key(&number) {
+                continue;
+            }
+            let academic_centres = missing.entry(number).or_default();
+            if list != "examinees" {
+                continue;
+            }
+            if let Some(academic_centre) = entity
+                .get("academicCentreId")
+                .and_then(|id| serde_json::from_value(id.clone()).ok())
+            {
+                academic_centres.insert(academic_centre);
+            }
+        }
+    }
+
+    let counter = values
+        .get("ids")
+        .and_then(|ids| ids.get("courts"))
+        .and_then(|counter| serde_json::from_value(counter.clone()).ok())
+        .unwrap_or_default();
+    let mut next = EntityId::next_after(ids.iter()).max(counter);
+    let mut created = Vec::new();
+    for (number, academic_centres) in missing {
+        let court = Court {
+            id: next.clone(),
+            number,
+            name: name.replace("{number}", &number.to_string()),
+            venue: None,
+            president_id: None,
+            academic_centres_ids: academic_centres.into_iter().collect(),
+        };
+        next = EntityId(next.0 + 1);
+        courts.insert(number, court.id.clone());
+        let Ok(court) = serde_json::to_value(court) else {
+            continue;
+        };
+        match values
+            .entry("courts")
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(courts) => courts.push(court),
+            other => *other = Value::Array(vec![court]),
+        }
+        created.push(number);
+    }
+    if let Some(Value::Object(ids)) = values.get_mut("ids") {
+        if !created.is_empty() {
+            ids.insert("courts".to_owned(), Value::from(next.0));
+        }
+    }
+
+    for (list, field, id_field) in NUMBER_REFERENCES {
+        let Some(Value::Array(entities)) = values.get_mut(list) else {
+            continue;
+        };
+        for entity in entities.iter_mut().filter_map(Value::as_object_mut) {
+            let Some(number) = entity.remove(field) else {
+                continue;
+            };
+            let id = court_number(Some(&number)).and_then(|number| courts.get(&number));
+            entity
+                .entry(id_field)
+                .or_insert_with(|| id.map_or(Value::Null, |id| Value::from(id.0)));
+        }
+    }
+    created
+}
+
+fn court_number(value: Option<&Value>) -> Option<i16> {
+    value?.as_i64()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::AppValues;
+
+    fn examinee(id: i32, court: Value, academic_centre: Value) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": format!("Examinee {id}"),
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "court": court,
+            "academicCentreId": academic_centre,
+            "subjectsIds": [],
+        })
+    }
+
+    fn vigilant(id: i32, court: i16) -> Value {
+        json!({
+            "id": id,
+            "name": format!("Vigilant {id}"),
+            "surenames": "Surenames",
+            "role": "MEMBER",
+            "specialtiesIds": [],
+            "academicCentreId": null,
+            "mainCourt": court,
+        })
+    }
+
+    fn classroom(id: i32, court: Value) -> Value {
+        json!({
+            "id": id,
+            "code": format!("C{id}"),
+            "locationCode": "L",
+            "totalCapacity": 10,
+            "examCapacity": 10,
+            "priority": id,
+            "courtLocation": court,
+            "kind": "",
+            "notes": [],
+        })
+    }
+
+    fn court(id: i32, number: i16) -> Value {
+        json!({ "id": id, "number": number, "name": format!("Court {number}"), "academicCentresIds": [] })
+    }
+
+    fn stored(courts: Vec<Value>, courts_counter: i32) -> Map<String, Value> {
+        let Value::Object(values) = json!({
+            "examinees": [
+                examinee(0, json!(1), json!(0)),
+                examinee(1, json!(2), json!(0)),
+                examinee(2, json!(2), json!(1)),
+            ],
+            "academicCentres": [{ "id": 0, "name": "Centre 0" }, { "id": 1, "name": "Centre 1" }],
+            "subjects": [],
+            "vigilants": [vigilant(0, 3)],
+            "classrooms": [classroom(0, json!(1)), classroom(1, Value::Null)],
+            "courts": courts,
+            "assignation": null,
+            "ids": {
+                "examinees": 3,
+                "academicCentres": 2,
+                "subjects": 0,
+                "vigilants": 1,
+                "classrooms": 2,
+                "courts": courts_counter,
+            },
+        }) else {
+            unreachable!()
+        };
+        values
+    }
+
+    type CourtIds = Vec<Option<EntityId>>;
+
+    /// Courts of the examinees, vigilants and classrooms
+    fn court_ids(values: &AppValues) -> (CourtIds, CourtIds, CourtIds) {
+        (
+            values
+                .examinees
+                .iter()
+                .map(|e| e.court_id.clone())
+                .collect(),
+            values
+                .vigilants
+                .iter()
+                .map(|v| v.main_court_id.clone())
+                .collect(),
+            values
+                .classrooms
+                .iter()
+                .map(|c| c.court_location_id.clone())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn court_numbers_refer_to_the_courts_with_those_numbers() {
+        let mut stored = stored(vec![court(5, 1), court(6, 2), court(7, 3)], 8);
+
+        let created = migrate_court_numbers(&mut stored, DEFAULT_COURT_NAME);
+        let values: AppValues = serde_json::from_value(Value::Object(stored)).unwrap();
+
+        assert!(created.is_empty());
+        assert_eq!(values.courts.len(), 3);
+        assert_eq!(
+            court_ids(&values),
+            (
+                vec![Some(EntityId(5)), Some(EntityId(6)), Some(EntityId(6))],
+                vec![Some(EntityId(7))],
+                vec![Some(EntityId(5)), None],
+            )
+        );
+    }
+
+    #[test]
+    fn courts_of_numbers_without_one_are_created() {
+        let mut stored = stored(vec![court(4, 2)], 5);
+
+        let created = migrate_court_numbers(&mut stored, "Tribunal {number}");
+        let values: AppValues = serde_json::from_value(Value::Object(stored)).unwrap();
+
+        assert_eq!(created, [1, 3]);
+        let courts: Vec<(EntityId, i16, &str, &[EntityId])> = values
+            .courts
+            .iter()
+            .map(|court| {
+                (
+                    court.id.clone(),
+                    court.number,
+                    court.name.as_str(),
+                    court.academic_centres_ids.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            courts,
+            [
+                (EntityId(4), 2, "Court 2", &[][..]),
+                (EntityId(5), 1, "Tribunal 1", &[EntityId(0)][..]),
+                (EntityId(6), 3, "Tribunal 3", &[][..]),
+            ]
+        );
+        assert_eq!(*values.ids.counter(EntityKind::Court), EntityId(7));
+        assert_eq!(
+            court_ids(&values),
+            (
+                vec![Some(EntityId(5)), Some(EntityId(4)), Some(EntityId(4))],
+                vec![Some(EntityId(6))],
+                vec![Some(EntityId(5)), None],
+            )
+        );
+    }
+
+    #[test]
+    fn values_that_refer_to_courts_by_id_are_left_alone() {
+        let mut stored = stored(vec![court(0, 1)], 1);
+        migrate_court_numbers(&mut stored, DEFAULT_COURT_NAME);
+        let migrated = stored.clone();
+
+        let created = migrate_court_numbers(&mut stored, DEFAULT_COURT_NAME);
+
+        assert!(created.is_empty());
+        assert_eq!(stored, migrated);
+    }
+}
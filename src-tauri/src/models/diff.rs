@@ -0,0 +1,804 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use super::{
+    assignation::{AllExamConfiguration, IndividualExam},
+    same, AppValues, Entity, EntityId, EntityKind,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct FieldChange {
+    pub field: String,
+    #[ts(type = "unknown")]
+    pub before: Value,
+    #[ts(type = "unknown")]
+    pub after: Value,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum EntityChange {
+    Added {
+        kind: EntityKind,
+        id: EntityId,
+        label: String,
+    },
+    Removed {
+        kind: EntityKind,
+        id: EntityId,
+        label: String,
+    },
+    Modified {
+        kind: EntityKind,
+        id: EntityId,
+        label: String,
+        changes: Vec<FieldChange>,
+    },
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum AssignationChange {
+    ExamAdded {
+        subject: EntityId,
+    },
+    ExamRemoved {
+        subject: EntityId,
+    },
+    /// Examinees, classrooms or vigilants that were added to or removed from an exam
+    MembersChanged {
+        subject: EntityId,
+        kind: EntityKind,
+        added: Vec<EntityId>,
+        removed: Vec<EntityId>,
+    },
+    SpecialistsChanged {
+        subject: EntityId,
+        added: Vec<EntityId>,
+        removed: Vec<EntityId>,
+    },
+    /// The examinee or vigilant is in another classroom for the exam
+    Moved {
+        subject: EntityId,
+        kind: EntityKind,
+        id: EntityId,
+        from: Option<EntityId>,
+        to: Option<EntityId>,
+    },
+}
+
+/// Changes of the assignment of one of the sessions of the project
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SessionAssignationChanges {
+    pub session: EntityId,
+    pub changes: Vec<AssignationChange>,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ProjectDiff {
+    /// Changes of the entities, the assignments of the sessions are in `sessions`
+    pub entities: Vec<EntityChange>,
+    pub assignation: Vec<AssignationChange>,
+    /// Sessions whose assignment changed
+    pub sessions: Vec<SessionAssignationChanges>,
+    /// The changes written for people
+    pub report: String,
+}
+
+/// Texts the report is written with, in the language of the user. In the templates `{subject}`,
+/// `{vigilant}`, `{item}`, `{from}` and `{to}` are replaced with the labels of the entities
+#[derive(Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ReportLabels {
+    /// Title of the changes of each kind of entity
+    pub kinds: HashMap<EntityKind, String>,
+    /// Title of the changes of the assignment
+    pub assignment: String,
+    /// Title of the changes of the assignment of a session, with `{session}`
+    pub session_assignment: String,
+    /// Stands for a missing classroom
+    pub nowhere: String,
+    /// An exam, with `{subject}`
+    pub exam: String,
+    /// A specialist of an exam, with `{vigilant}`
+    pub specialist: String,
+    /// Someone moved between classrooms, with `{item}`, `{from}` and `{to}`
+    pub moved: String,
+}
+
+impl Default for ReportLabels {
+    fn default() -> Self {
+        ReportLabels {
+            kinds: EntityKind::ALL
+                .into_iter()
+                .map(|kind| (kind, kind_title(kind).to_owned()))
+                .collect(),
+            assignment: "Assignment".to_owned(),
+            session_assignment: "Assignment of {session}".to_owned(),
+            nowhere: "nowhere".to_owned(),
+            exam: "exam of {subject}".to_owned(),
+            specialist: "specialist {vigilant}".to_owned(),
+            moved: "{item} moved from {from} to {to}".to_owned(),
+        }
+    }
+}
+
+type Labels = HashMap<(EntityKind, EntityId), String>;
+
+/// Every difference between two versions of a project, reported with the given texts
+pub fn diff(before: &AppValues, after: &AppValues, texts: &ReportLabels) -> ProjectDiff {
+    let mut entities = Vec::new();
+    diff_entities(&before.examinees, &after.examinees, &mut entities);
+    diff_entities(
+        &before.academic_centres,
+        &after.academic_centres,
+        &mut entities,
+    );
+    diff_entities(&before.subjects, &after.subjects, &mut entities);
+    diff_entities(&before.vigilants, &after.vigilants, &mut entities);
+    diff_entities(&before.classrooms, &after.classrooms, &mut entities);
+    diff_entities(&before.exam_sessions, &after.exam_sessions, &mut entities);
+    diff_entities(&before.courts, &after.courts, &mut entities);
+    diff_entities(&before.buildings, &after.buildings, &mut entities);
+    // The assignments of the sessions are compared exam by exam below
+    for change in &mut entities {
+        if let EntityChange::Modified {
+            kind: EntityKind::ExamSession,
+            changes,
+            ..
+        } = change
+        {
+            changes.retain(|change| change.field != "assignation");
+        }
+    }
+    entities.retain(
+        |change| !matches!(change, EntityChange::Modified { changes, .. } if changes.is_empty()),
+    );
+
+    let assignation = diff_assignation(before.assignation.as_ref(), after.assignation.as_ref());
+    let sessions = diff_session_assignations(before, after);
+
+    let mut labels = Labels::new();
+    add_labels(before, &mut labels);
+    add_labels(after, &mut labels);
+    let report = write_report(&entities, &assignation, &sessions, &labels, texts);
+
+    ProjectDiff {
+        entities,
+        assignation,
+        sessions,
+        report,
+    }
+}
+
+fn diff_entities<T: Entity + Serialize>(
+    before: &[T],
+    after: &[T],
+    changes: &mut Vec<EntityChange>,
+) {
+    let before: HashMap<&EntityId, &T> =
+        before.iter().map(|entity| (entity.id(), entity)).collect();
+    let after: HashMap<&EntityId, &T> = after.iter().map(|entity| (entity.id(), entity)).collect();
+    let ids: BTreeSet<&EntityId> = before.keys().chain(after.keys()).copied().collect();
+
+    for id in ids {
+        match (before.get(id), after.get(id)) {
+            (Some(entity), None) => changes.push(EntityChange::Removed {
+                kind: T::KIND,
+                id: id.clone(),
+                label: entity.label(),
+            }),
+            (None, Some(entity)) => changes.push(EntityChange::Added {
+                kind: T::KIND,
+                id: id.clone(),
+                label: entity.label(),
+            }),
+            (Some(old), Some(new)) => {
+                let field_changes = diff_fields(old, new);
+                if !field_changes.is_empty() {
+                    changes.push(EntityChange::Modified {
+                        kind: T::KIND,
+                        id: id.clone(),
+                        label: new.label(),
+                        changes: field_changes,
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Fields that have a different value, comparing lists of ids or names regardless of their order
+pub(super) fn diff_fields(before: &impl Serialize, after: &impl Serialize) -> Vec<FieldChange> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            let new = after.get(field).cloned().unwrap_or(Value::Null);
+            (!same(&old, &new)).then(|| FieldChange {
+                field: field.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+fn exams_by_subject(
+    assignation: Option<&AllExamConfiguration>,
+) -> HashMap<&EntityId, &IndividualExam> {
+    assignation
+        .into_iter()
+        .flat_map(AllExamConfiguration::individual_exams)
+        .map(|exam| (&exam.subject, exam))
+        .collect()
+}
+
+/// Changes of the assignments of the sessions, a session that is only in one of the versions
+/// has all its exams added or removed
+fn diff_session_assignations(
+    before: &AppValues,
+    after: &AppValues,
+) -> Vec<SessionAssignationChanges> {
+    let assignations = |values: &AppValues| -> HashMap<EntityId, Option<AllExamConfiguration>> {
+        values
+            .exam_sessions
+            .iter()
+            .map(|session| (session.id.clone(), session.assignation.clone()))
+            .collect()
+    };
+    let before = assignations(before);
+    let after = assignations(after);
+    let sessions: BTreeSet<&EntityId> = before.keys().chain(after.keys()).collect();
+
+    sessions
+        .into_iter()
+        .filter_map(|session| {
+            let changes = diff_assignation(
+                before.get(session).and_then(Option::as_ref),
+                after.get(session).and_then(Option::as_ref),
+            );
+            (!changes.is_empty()).then(|| SessionAssignationChanges {
+                session: session.clone(),
+                changes,
+            })
+        })
+        .collect()
+}
+
+fn diff_assignation(
+    before: Option<&AllExamConfiguration>,
+    after: Option<&AllExamConfiguration>,
+) -> Vec<AssignationChange> {
+    let before = exams_by_subject(before);
+    let after = exams_by_subject(after);
+    let subjects: BTreeSet<&EntityId> = before.keys().chain(after.keys()).copied().collect();
+
+    let mut changes = Vec::new();
+    for subject in subjects {
+        let (old, new) = match (before.get(subject), after.get(subject)) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(_), None) => {
+                changes.push(AssignationChange::ExamRemoved {
+                    subject: subject.clone(),
+                });
+                continue;
+            }
+            (None, Some(_)) => {
+                changes.push(AssignationChange::ExamAdded {
+                    subject: subject.clone(),
+                });
+                continue;
+            }
+            (None, None) => continue,
+        };
+
+        for (kind, old_members, new_members) in [
+            (EntityKind::Examinee, &old.examinees, &new.examinees),
+            (EntityKind::Classroom, &old.classrooms, &new.classrooms),
+            (EntityKind::Vigilant, &old.vigilants, &new.vigilants),
+        ] {
+            let (added, removed) = diff_ids(old_members, new_members);
+            if !added.is_empty() || !removed.is_empty() {
+                changes.push(AssignationChange::MembersChanged {
+                    subject: subject.clone(),
+                    kind,
+                    added,
+                    removed,
+                });
+            }
+        }
+
+        let no_specialists = Vec::new();
+        let (added, removed) = diff_ids(
+            old.distribution
+                .as_ref()
+                .map_or(&no_specialists, |d| &d.specialists),
+            new.distribution
+                .as_ref()
+                .map_or(&no_specialists, |d| &d.specialists),
+        );
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(AssignationChange::SpecialistsChanged {
+                subject: subject.clone(),
+                added,
+                removed,
+            });
+        }
+
+        for kind in [EntityKind::Examinee, EntityKind::Vigilant] {
+            let old_classrooms = classrooms_of(old, kind);
+            let new_classrooms = classrooms_of(new, kind);
+            let ids: BTreeSet<&EntityId> = old_classrooms
+                .keys()
+                .chain(new_classrooms.keys())
+                .copied()
+                .collect();
+            for id in ids {
+                let from = old_classrooms.get(id).map(|&classroom| classroom.clone());
+                let to = new_classrooms.get(id).map(|&classroom| classroom.clone());
+                if from != to {
+                    changes.push(AssignationChange::Moved {
+                        subject: subject.clone(),
+                        kind,
+                        id: id.clone(),
+                        from,
+                        to,
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Classroom in which each examinee or vigilant is for the exam
+fn classrooms_of(exam: &IndividualExam, kind: EntityKind) -> HashMap<&EntityId, &EntityId> {
+    exam.distribution
+        .iter()
+        .flat_map(|distribution| &distribution.distribution)
+        .flat_map(|classroom| {
+            let members = match kind {
+                EntityKind::Examinee => &classroom.examinees,
+                _ => &classroom.vigilants,
+            };
+            members.iter().map(|member| (member, &classroom.classroom))
+        })
+        .collect()
+}
+
+fn diff_ids(before: &[EntityId], after: &[EntityId]) -> (Vec<EntityId>, Vec<EntityId>) {
+    let before: BTreeSet<&EntityId> = before.iter().collect();
+    let after: BTreeSet<&EntityId> = after.iter().collect();
+    (
+        after.difference(&before).map(|&id| id.clone()).collect(),
+        before.difference(&after).map(|&id| id.clone()).collect(),
+    )
+}
+
+fn add_labels(values: &AppValues, labels: &mut Labels) {
+    fn add<T: Entity>(entities: &[T], labels: &mut Labels) {
+        for entity in entities {
+            labels.insert((T::KIND, entity.id().clone()), entity.label());
+        }
+    }
+    add(&values.examinees, labels);
+    add(&values.academic_centres, labels);
+    add(&values.subjects, labels);
+    add(&values.vigilants, labels);
+    add(&values.classrooms, labels);
+    add(&values.exam_sessions, labels);
+    add(&values.courts, labels);
+    add(&values.buildings, labels);
+}
+
+fn write_report(
+    entities: &[EntityChange],
+    assignation: &[AssignationChange],
+    sessions: &[SessionAssignationChanges],
+    labels: &Labels,
+    texts: &ReportLabels,
+) -> String {
+    let label = |kind: EntityKind, id: &EntityId| {
+        labels
+            .get(&(kind, id.clone()))
+            .cloned()
+            .unwrap_or_else(|| format!("#{}", id.0))
+    };
+    let mut lines = Vec::new();
+    for kind in EntityKind::ALL {
+        let changes: Vec<&EntityChange> = entities
+            .iter()
+            .filter(|change| match change {
+                EntityChange::Added { kind: k, .. }
+                | EntityChange::Removed { kind: k, .. }
+                | EntityChange::Modified { kind: k, .. } => *k == kind,
+            })
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        lines.push(
+            texts
+                .kinds
+                .get(&kind)
+                .cloned()
+                .unwrap_or_else(|| kind_title(kind).to_owned()),
+        );
+        for change in changes {
+            match change {
+                EntityChange::Added { label, .. } => lines.push(format!("  + {label}")),
+                EntityChange::Removed { label, .. } => lines.push(format!("  - {label}")),
+                EntityChange::Modified { label, changes, .. } => {
+                    lines.push(format!("  ~ {label}"));
+                    for change in changes {
+                        lines.push(format!(
+                            "      {}: {} -> {}",
+                            change.field, change.before, change.after
+                        ));
+                    }
+                }
+            }
+        }
+        lines.push(String::new());
+    }
+
+    write_assignation(
+        &mut lines,
+        texts.assignment.clone(),
+        assignation,
+        &label,
+        texts,
+    );
+    for session in sessions {
+        let title = fill(
+            &texts.session_assignment,
+            &[("session", &label(EntityKind::ExamSession, &session.session))],
+        );
+        write_assignation(&mut lines, title, &session.changes, &label, texts);
+    }
+
+    lines.join("\n")
+}
+
+/// Writes the changes of an assignment under the title, nothing if there are none
+fn write_assignation(
+    lines: &mut Vec<String>,
+    title: String,
+    changes: &[AssignationChange],
+    label: &dyn Fn(EntityKind, &EntityId) -> String,
+    texts: &ReportLabels,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    if lines.last().is_some_and(|line| !line.is_empty()) {
+        lines.push(String::new());
+    }
+    lines.push(title);
+    let classroom = |id: &Option<EntityId>| {
+        id.as_ref()
+            .map_or(texts.nowhere.clone(), |id| label(EntityKind::Classroom, id))
+    };
+    for change in changes {
+        match change {
+            AssignationChange::ExamAdded { subject } => lines.push(format!(
+                "  + {}",
+                fill(
+                    &texts.exam,
+                    &[("subject", &label(EntityKind::Subject, subject))]
+                )
+            )),
+            AssignationChange::ExamRemoved { subject } => lines.push(format!(
+                "  - {}",
+                fill(
+                    &texts.exam,
+                    &[("subject", &label(EntityKind::Subject, subject))]
+                )
+            )),
+            AssignationChange::MembersChanged {
+                subject,
+                kind,
+                added,
+                removed,
+            } => {
+                let subject = label(EntityKind::Subject, subject);
+                for id in added {
+                    lines.push(format!("  {subject}: + {}", label(*kind, id)));
+                }
+                for id in removed {
+                    lines.push(format!("  {subject}: - {}", label(*kind, id)));
+                }
+            }
+            AssignationChange::SpecialistsChanged {
+                subject,
+                added,
+                removed,
+            } => {
+                let subject = label(EntityKind::Subject, subject);
+                let specialist = |id: &EntityId| {
+                    fill(
+                        &texts.specialist,
+                        &[("vigilant", &label(EntityKind::Vigilant, id))],
+                    )
+                };
+                for id in added {
+                    lines.push(format!("  {subject}: + {}", specialist(id)));
+                }
+                for id in removed {
+                    lines.push(format!("  {subject}: - {}", specialist(id)));
+                }
+            }
+            AssignationChange::Moved {
+                subject,
+                kind,
+                id,
+                from,
+                to,
+            } => lines.push(format!(
+                "  {}: {}",
+                label(EntityKind::Subject, subject),
+                fill(
+                    &texts.moved,
+                    &[
+                        ("item", &label(*kind, id)),
+                        ("from", &classroom(from)),
+                        ("to", &classroom(to)),
+                    ]
+                )
+            )),
+        }
+    }
+}
+
+/// Replaces the `{name}` placeholders of the template with their values
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+fn kind_title(kind: EntityKind) -> &'static str {
+    match kind {
+        EntityKind::Examinee => "Examinees",
+        EntityKind::AcademicCentre => "Academic centres",
+        EntityKind::Subject => "Subjects",
+        EntityKind::Vigilant => "Vigilants",
+        EntityKind::Classroom => "Classrooms",
+        EntityKind::ExamSession => "Exam sessions",
+        EntityKind::Court => "Courts",
+        EntityKind::Building => "Buildings",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::Ids;
+
+    fn examinee(id: i32, name: &str, subjects: &[i32]) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": name,
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "courtId": null,
+            "academicCentreId": null,
+            "subjectsIds": subjects,
+        })
+    }
+
+    /// An exam of the subject with the examinees and vigilants of each classroom
+    fn exam(subject: i32, classrooms: &[(i32, &[i32], &[i32])]) -> Value {
+        let examinees: Vec<i32> = classrooms.iter().flat_map(|c| c.1.to_vec()).collect();
+        let vigilants: Vec<i32> = classrooms.iter().flat_map(|c| c.2.to_vec()).collect();
+        json!({
+            "type": "individualExam",
+            "subject": subject,
+            "examinees": examinees,
+            "classrooms": classrooms.iter().map(|c| c.0).collect::<Vec<_>>(),
+            "vigilants": vigilants,
+            "distribution": {
+                "subject": subject,
+                "specialists": [],
+                "distribution": classrooms
+                    .iter()
+                    .map(|(classroom, examinees, vigilants)| json!({
+                        "classroom": classroom,
+                        "examinees": examinees,
+                        "vigilants": vigilants,
+                    }))
+                    .collect::<Vec<_>>(),
+            },
+        })
+    }
+
+    fn session(id: i32, assignation: Value) -> Value {
+        json!({
+            "id": id,
+            "name": format!("Session {id}"),
+            "schedules": [],
+            "classroomsIds": [],
+            "vigilantsIds": [],
+            "enrollments": [],
+            "assignation": assignation,
+        })
+    }
+
+    fn values(examinees: Vec<Value>, sessions: Vec<Value>, assignation: Value) -> AppValues {
+        let mut values: AppValues = serde_json::from_value(json!({
+            "examinees": examinees,
+            "academicCentres": [],
+            "subjects": [],
+            "vigilants": [],
+            "classrooms": [],
+            "examSessions": sessions,
+            "assignation": assignation,
+            "ids": Ids::default(),
+        }))
+        .unwrap();
+        values.ids = Ids::covering(&values);
+        values
+    }
+
+    fn compared(before: &AppValues, after: &AppValues) -> Value {
+        serde_json::to_value(diff(before, after, &ReportLabels::default())).unwrap()
+    }
+
+    #[test]
+    fn added_removed_and_modified_entities_are_listed() {
+        let before = values(
+            vec![examinee(0, "Kept", &[0, 1]), examinee(1, "Removed", &[])],
+            vec![],
+            Value::Null,
+        );
+        let after = values(
+            vec![examinee(0, "Renamed", &[1, 0]), examinee(2, "Added", &[])],
+            vec![],
+            Value::Null,
+        );
+
+        let diff = compared(&before, &after);
+
+        assert_eq!(
+            diff["entities"],
+            json!([
+                {
+                    "type": "modified",
+                    "kind": "examinee",
+                    "id": 0,
+                    "label": "Surenames, Renamed (00000000X)",
+                    "changes": [{ "field": "name", "before": "Kept", "after": "Renamed" }],
+                },
+                { "type": "removed", "kind": "examinee", "id": 1, "label": "Surenames, Removed (00000001X)" },
+                { "type": "added", "kind": "examinee", "id": 2, "label": "Surenames, Added (00000002X)" },
+            ])
+        );
+        assert_eq!(
+            diff["report"],
+            "Examinees\n  ~ Surenames, Renamed (00000000X)\n      name: \"Kept\" -> \"Renamed\"\n  \
+             - Surenames, Removed (00000001X)\n  + Surenames, Added (00000002X)\n"
+        );
+    }
+
+    #[test]
+    fn equal_values_have_no_changes() {
+        let values = values(
+            vec![examinee(0, "Examinee", &[0])],
+            vec![session(0, json!([exam(0, &[(0, &[0], &[0])])]))],
+            json!([exam(0, &[(0, &[0], &[0])])]),
+        );
+
+        let diff = compared(&values, &values);
+
+        assert_eq!(diff["entities"], json!([]));
+        assert_eq!(diff["assignation"], json!([]));
+        assert_eq!(diff["sessions"], json!([]));
+        assert_eq!(diff["report"], "");
+    }
+
+    #[test]
+    fn exams_members_and_moves_of_the_assignment_are_listed() {
+        let before = values(
+            vec![],
+            vec![],
+            json!([exam(0, &[(0, &[0, 1], &[0])]), exam(1, &[(0, &[0], &[])])]),
+        );
+        let after = values(
+            vec![],
+            vec![],
+            json!([
+                exam(0, &[(0, &[0], &[0]), (1, &[1, 2], &[])]),
+                exam(2, &[(0, &[0], &[])])
+            ]),
+        );
+
+        let diff = compared(&before, &after);
+
+        assert_eq!(
+            diff["assignation"],
+            json!([
+                { "type": "membersChanged", "subject": 0, "kind": "examinee", "added": [2], "removed": [] },
+                { "type": "membersChanged", "subject": 0, "kind": "classroom", "added": [1], "removed": [] },
+                { "type": "moved", "subject": 0, "kind": "examinee", "id": 1, "from": 0, "to": 1 },
+                { "type": "moved", "subject": 0, "kind": "examinee", "id": 2, "from": null, "to": 1 },
+                { "type": "examRemoved", "subject": 1 },
+                { "type": "examAdded", "subject": 2 },
+            ])
+        );
+        assert_eq!(diff["sessions"], json!([]));
+    }
+
+    #[test]
+    fn assignments_of_sessions_are_compared_exam_by_exam() {
+        let before = values(
+            vec![],
+            vec![
+                session(0, json!([exam(0, &[(0, &[0], &[0])])])),
+                session(1, json!([exam(0, &[(0, &[0], &[])])])),
+            ],
+            Value::Null,
+        );
+        let after = values(
+            vec![],
+            vec![
+                session(0, json!([exam(0, &[(0, &[0], &[1])])])),
+                session(1, json!([exam(0, &[(0, &[0], &[])])])),
+                session(2, json!([exam(3, &[(0, &[0], &[])])])),
+            ],
+            Value::Null,
+        );
+
+        let diff = compared(&before, &after);
+
+        assert_eq!(
+            diff["entities"],
+            json!([{ "type": "added", "kind": "examSession", "id": 2, "label": "Session 2" }])
+        );
+        assert_eq!(diff["assignation"], json!([]));
+        assert_eq!(
+            diff["sessions"],
+            json!([
+                {
+                    "session": 0,
+                    "changes": [
+                        { "type": "membersChanged", "subject": 0, "kind": "vigilant", "added": [1], "removed": [0] },
+                        { "type": "moved", "subject": 0, "kind": "vigilant", "id": 0, "from": 0, "to": null },
+                        { "type": "moved", "subject": 0, "kind": "vigilant", "id": 1, "from": null, "to": 0 },
+                    ],
+                },
+                { "session": 2, "changes": [{ "type": "examAdded", "subject": 3 }] },
+            ])
+        );
+        assert_eq!(
+            diff["report"],
+            "Exam sessions\n  + Session 2\n\nAssignment of Session 0\n  #0: + #1\n  #0: - #0\n  \
+             #0: #0 moved from #0 to nowhere\n  #0: #1 moved from nowhere to #0\n\n\
+             Assignment of Session 2\n  + exam of #3"
+        );
+    }
+}
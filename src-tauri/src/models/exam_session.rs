@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_with_macros::skip_serializing_none;
+use ts_rs::TS;
+
+use super::{
+    assignation::AllExamConfiguration,
+    subject::{exam_date, exam_duration},
+    Entity, EntityId, EntityKind,
+};
+
+/// When the exam of a subject is held in a session
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SessionSchedule {
+    pub subject: EntityId,
+    #[serde(default, with = "exam_date")]
+    #[ts(type = "string | null")]
+    pub exam_date: Option<DateTime<FixedOffset>>,
+    #[serde(default, with = "exam_duration")]
+    #[ts(type = "string | null")]
+    pub exam_duration: Option<Duration>,
+}
+
+/// The subjects an examinee takes in a session
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SessionEnrollment {
+    pub examinee: EntityId,
+    pub subjects_ids: HashSet<EntityId>,
+}
+
+/// A call of the exams, like the ordinary or the extraordinary one, with its own timetable,
+/// classrooms, staff and assignment
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ExamSession {
+    pub id: EntityId,
+    pub name: String,
+    pub schedules: Vec<SessionSchedule>,
+    /// Classrooms that can be used in this session
+    pub classrooms_ids: Vec<EntityId>,
+    /// Vigilants that take part in this session
+    pub vigilants_ids: Vec<EntityId>,
+    pub enrollments: Vec<SessionEnrollment>,
+    pub assignation: Option<AllExamConfiguration>,
+}
+
+impl Entity for ExamSession {
+    const KIND: EntityKind = EntityKind::ExamSession;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        for schedule in &mut self.schedules {
+            visit(EntityKind::Subject, &mut schedule.subject);
+        }
+        for classroom_id in &mut self.classrooms_ids {
+            visit(EntityKind::Classroom, classroom_id);
+        }
+        for vigilant_id in &mut self.vigilants_ids {
+            visit(EntityKind::Vigilant, vigilant_id);
+        }
+        for enrollment in &mut self.enrollments {
+            visit(EntityKind::Examinee, &mut enrollment.examinee);
+            enrollment.subjects_ids = std::mem::take(&mut enrollment.subjects_ids)
+                .into_iter()
+                .map(|mut subject_id| {
+                    visit(EntityKind::Subject, &mut subject_id);
+                    subject_id
+                })
+                .collect();
+        }
+        if let Some(assignation) = &mut self.assignation {
+            assignation.for_each_reference_mut(visit);
+        }
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.schedules
+            .retain(|schedule| keep(EntityKind::Subject, &schedule.subject));
+        self.classrooms_ids
+            .retain(|classroom_id| keep(EntityKind::Classroom, classroom_id));
+        self.vigilants_ids
+            .retain(|vigilant_id| keep(EntityKind::Vigilant, vigilant_id));
+        self.enrollments.retain_mut(|enrollment| {
+            if !keep(EntityKind::Examinee, &enrollment.examinee) {
+                return false;
+            }
+            enrollment
+                .subjects_ids
+                .retain(|subject_id| keep(EntityKind::Subject, subject_id));
+            true
+        });
+        if let Some(assignation) = &mut self.assignation {
+            assignation.retain_references(keep);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use super::EntityId;
+use super::{subject::Subject, Entity, EntityId, EntityKind};
+use chrono::{DateTime, Duration, FixedOffset};
 use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
@@ -15,9 +16,136 @@ pub struct Examinee {
     pub name: String,
     pub surenames: String,
     pub origin: String,
-    pub court: i16,
+    /// Court the examinee is examined by
+    pub court_id: Option<EntityId>,
     pub academic_centre_id: Option<EntityId>,
     pub subjects_ids: HashSet<EntityId>,
+    #[serde(default)]
+    pub accommodations: Accommodations,
+}
+
+/// Adaptations some examinees need to take the exams
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct Accommodations {
+    /// Extra time over the duration of every exam, as a percentage of it
+    #[serde(default)]
+    pub extra_time_percentage: u16,
+    /// Needs a classroom with wheelchair access
+    #[serde(default)]
+    pub accessible_room: bool,
+    /// Has to take the exams alone in a supervised classroom
+    #[serde(default)]
+    pub separate_room: bool,
+}
+
+impl Accommodations {
+    /// Reads the accommodations from a text like `25%, accessible, separate`. Returns `None` if
+    /// some part is not understood
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut accommodations = Accommodations::default();
+        for part in text.split([',', ';', '+']) {
+            let part = part.trim().to_lowercase();
+            if part.is_empty() {
+                continue;
+            }
+            if let Some(percentage) = part.strip_suffix('%') {
+                accommodations.extra_time_percentage = percentage.trim().parse().ok()?;
+            } else if ["accessible", "wheelchair", "accesible", "silla de ruedas"]
+                .contains(&part.as_str())
+            {
+                accommodations.accessible_room = true;
+            } else if ["separate", "alone", "separada", "aparte"].contains(&part.as_str()) {
+                accommodations.separate_room = true;
+            } else {
+                return None;
+            }
+        }
+        Some(accommodations)
+    }
+}
+
+impl Examinee {
+    /// How long the examinee has for the exam of the subject, extra time included
+    pub fn exam_duration(&self, subject: &Subject) -> Option<Duration> {
+        let duration = subject.exam_duration?;
+        let extra = Duration::try_milliseconds(
+            duration
+                .num_milliseconds()
+                .checked_mul(i64::from(self.accommodations.extra_time_percentage))?
+                / 100,
+        )?;
+        duration.checked_add(&extra)
+    }
+
+    /// When the examinee finishes the exam of the subject, extra time included
+    pub fn exam_end(&self, subject: &Subject) -> Option<DateTime<FixedOffset>> {
+        subject
+            .exam_date?
+            .checked_add_signed(self.exam_duration(subject)?)
+    }
+
+    /// If the examinee would have to be at both exams at the same time, extra time included. Exams
+    /// that start right when the other one finishes also overlap
+    pub fn exam_overlaps(&self, first: &Subject, second: &Subject) -> bool {
+        match (
+            first.exam_date,
+            self.exam_end(first),
+            second.exam_date,
+            self.exam_end(second),
+        ) {
+            (Some(start), Some(end), Some(other_start), Some(other_end)) => {
+                start <= other_end && other_start <= end
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Entity for Examinee {
+    const KIND: EntityKind = EntityKind::Examinee;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        format!("{}, {} ({})", self.surenames, self.name, self.nif)
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        if let Some(court_id) = &mut self.court_id {
+            visit(EntityKind::Court, court_id);
+        }
+        if let Some(academic_centre_id) = &mut self.academic_centre_id {
+            visit(EntityKind::AcademicCentre, academic_centre_id);
+        }
+        self.subjects_ids = std::mem::take(&mut self.subjects_ids)
+            .into_iter()
+            .map(|mut subject_id| {
+                visit(EntityKind::Subject, &mut subject_id);
+                subject_id
+            })
+            .collect();
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.court_id = self
+            .court_id
+            .take()
+            .filter(|court_id| keep(EntityKind::Court, court_id));
+        self.academic_centre_id = self
+            .academic_centre_id
+            .take()
+            .filter(|academic_centre_id| keep(EntityKind::AcademicCentre, academic_centre_id));
+        self.subjects_ids
+            .retain(|subject_id| keep(EntityKind::Subject, subject_id));
+    }
 }
 
 #[skip_serializing_none]
@@ -32,4 +160,5 @@ pub struct ImportedExaminee {
     pub court: i16,
     pub subjects: Vec<String>,
     pub academic_centre: Option<String>,
+    pub accommodations: Accommodations,
 }
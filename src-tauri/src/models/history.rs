@@ -0,0 +1,528 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use super::{
+    comparable,
+    court::{migrate_court_numbers, DEFAULT_COURT_NAME},
+    diff::diff_fields,
+    diff::FieldChange,
+    same, AppValues, Entity, EntityId, EntityKind, Ids,
+};
+
+/// History of the opened projects as they were last loaded or saved, by path
+pub type ProjectHistories = Arc<Mutex<HashMap<String, ProjectHistory>>>;
+
+pub struct ProjectHistory {
+    /// The values as they are in the file
+    pub persisted: AppValues,
+    pub entries: Vec<HistoryEntry>,
+    /// Operation that produced the values that will be saved next, if it is not a plain edition
+    pub pending: Option<HistoryOperation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum HistoryOperation {
+    /// The project was saved for the first time
+    Create,
+    Save,
+    /// The project was taken back to how it was after the given entry
+    Revert {
+        to: usize,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum HistoryChange {
+    Added {
+        kind: EntityKind,
+        id: EntityId,
+        #[ts(type = "unknown")]
+        after: Value,
+    },
+    Removed {
+        kind: EntityKind,
+        id: EntityId,
+        #[ts(type = "unknown")]
+        before: Value,
+    },
+    Modified {
+        kind: EntityKind,
+        id: EntityId,
+        changes: Vec<FieldChange>,
+    },
+    /// The whole assignment before and after, as older versions recorded it
+    Assignation {
+        #[ts(type = "unknown")]
+        before: Value,
+        #[ts(type = "unknown")]
+        after: Value,
+    },
+    /// Slots of the assignment of the project, or of one of its sessions, that changed
+    #[serde(rename_all = "camelCase")]
+    AssignationSlots {
+        session: Option<EntityId>,
+        /// Number of slots before and after, none when there was no assignment
+        before_length: Option<usize>,
+        after_length: Option<usize>,
+        slots: Vec<SlotChange>,
+    },
+    Ids {
+        before: Ids,
+        after: Ids,
+    },
+}
+
+/// A slot of an assignment as it was before and after, null where there was none
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SlotChange {
+    pub index: usize,
+    #[ts(type = "unknown")]
+    pub before: Value,
+    #[ts(type = "unknown")]
+    pub after: Value,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct HistoryEntry {
+    /// Seconds since the unix epoch
+    #[ts(type = "number")]
+    pub timestamp: u64,
+    pub operator: String,
+    pub operation: HistoryOperation,
+    pub changes: Vec<HistoryChange>,
+}
+
+impl HistoryEntry {
+    /// Entry with every change needed to go from `before` to `after`
+    pub fn record(
+        before: Option<&AppValues>,
+        after: &AppValues,
+        operator: String,
+        operation: HistoryOperation,
+    ) -> Self {
+        let changes = match before {
+            Some(before) => changes_between(before, after),
+            None => Vec::new(),
+        };
+        HistoryEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            operator,
+            operation,
+            changes,
+        }
+    }
+}
+
+/// The values as they were right after the entry at `index`, undoing every later entry. Court
+/// numbers of entries recorded before courts were entities are migrated
+pub fn values_at(
+    current: &AppValues,
+    entries: &[HistoryEntry],
+    index: usize,
+) -> Result<AppValues, serde_json::Error> {
+    let mut root = serde_json::to_value(current)?;
+    for entry in entries.iter().skip(index + 1).rev() {
+        for change in entry.changes.iter().rev() {
+            undo(&mut root, change);
+        }
+    }
+    if let Value::Object(object) = &mut root {
+        migrate_court_numbers(object, DEFAULT_COURT_NAME);
+    }
+    serde_json::from_value(root)
+}
+
+fn changes_between(before: &AppValues, after: &AppValues) -> Vec<HistoryChange> {
+    let mut changes = Vec::new();
+    entity_changes(&before.examinees, &after.examinees, &mut changes);
+    entity_changes(
+        &before.academic_centres,
+        &after.academic_centres,
+        &mut changes,
+    );
+    entity_changes(&before.subjects, &after.subjects, &mut changes);
+    entity_changes(&before.vigilants, &after.vigilants, &mut changes);
+    entity_changes(&before.classrooms, &after.classrooms, &mut changes);
+    entity_changes(&before.exam_sessions, &after.exam_sessions, &mut changes);
+    entity_changes(&before.courts, &after.courts, &mut changes);
+    entity_changes(&before.buildings, &after.buildings, &mut changes);
+
+    // Only the slots that changed are kept, the assignments are too big to store them whole
+    let mut session_slots = Vec::new();
+    for change in &mut changes {
+        if let HistoryChange::Modified {
+            kind: EntityKind::ExamSession,
+            id,
+            changes: field_changes,
+        } = change
+        {
+            if let Some(index) = field_changes
+                .iter()
+                .position(|change| change.field == "assignation")
+            {
+                let assignation = field_changes.remove(index);
+                session_slots.extend(slot_changes(
+                    Some(id.clone()),
+                    &assignation.before,
+                    &assignation.after,
+                ));
+            }
+        }
+    }
+    changes.retain(
+        |change| !matches!(change, HistoryChange::Modified { changes, .. } if changes.is_empty()),
+    );
+    changes.extend(session_slots);
+    if let Some(change) = slot_changes(
+        None,
+        &serde_json::to_value(&before.assignation).unwrap_or(Value::Null),
+        &serde_json::to_value(&after.assignation).unwrap_or(Value::Null),
+    ) {
+        changes.push(change);
+    }
+    if comparable(&before.ids) != comparable(&after.ids) {
+        changes.push(HistoryChange::Ids {
+            before: before.ids.clone(),
+            after: after.ids.clone(),
+        });
+    }
+    changes
+}
+
+/// The slots of an assignment that changed, or none if it did not change
+fn slot_changes(session: Option<EntityId>, before: &Value, after: &Value) -> Option<HistoryChange> {
+    if same(before, after) {
+        return None;
+    }
+    let no_slots = Vec::new();
+    let before_slots = before.as_array().unwrap_or(&no_slots);
+    let after_slots = after.as_array().unwrap_or(&no_slots);
+    let slots = (0..before_slots.len().max(after_slots.len()))
+        .filter_map(|index| {
+            let old = before_slots.get(index).cloned().unwrap_or(Value::Null);
+            let new = after_slots.get(index).cloned().unwrap_or(Value::Null);
+            (!same(&old, &new)).then_some(SlotChange {
+                index,
+                before: old,
+                after: new,
+            })
+        })
+        .collect();
+    Some(HistoryChange::AssignationSlots {
+        session,
+        before_length: before.as_array().map(Vec::len),
+        after_length: after.as_array().map(Vec::len),
+        slots,
+    })
+}
+
+fn entity_changes<T: Entity + Serialize>(
+    before: &[T],
+    after: &[T],
+    changes: &mut Vec<HistoryChange>,
+) {
+    let old: HashMap<&EntityId, &T> = before.iter().map(|entity| (entity.id(), entity)).collect();
+    let new: HashMap<&EntityId, &T> = after.iter().map(|entity| (entity.id(), entity)).collect();
+
+    for entity in before {
+        if !new.contains_key(entity.id()) {
+            changes.push(HistoryChange::Removed {
+                kind: T::KIND,
+                id: entity.id().clone(),
+                before: serde_json::to_value(entity).unwrap_or(Value::Null),
+            });
+        }
+    }
+    for entity in after {
+        match old.get(entity.id()) {
+            None => changes.push(HistoryChange::Added {
+                kind: T::KIND,
+                id: entity.id().clone(),
+                after: serde_json::to_value(entity).unwrap_or(Value::Null),
+            }),
+            Some(previous) => {
+                let field_changes = diff_fields(previous, entity);
+                if !field_changes.is_empty() {
+                    changes.push(HistoryChange::Modified {
+                        kind: T::KIND,
+                        id: entity.id().clone(),
+                        changes: field_changes,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn undo(root: &mut Value, change: &HistoryChange) {
+    match change {
+        HistoryChange::Added { kind, id, .. } => {
+            if let Some(entities) = entities_of(root, *kind) {
+                entities.retain(|entity| !has_id(entity, id));
+            }
+        }
+        HistoryChange::Removed { kind, before, .. } => {
+            if let Some(entities) = entities_of(root, *kind) {
+                entities.push(before.clone());
+            }
+        }
+        HistoryChange::Modified { kind, id, changes } => {
+            let entity = entities_of(root, *kind)
+                .and_then(|entities| entities.iter_mut().find(|entity| has_id(entity, id)))
+                .and_then(Value::as_object_mut);
+            if let Some(entity) = entity {
+                for change in changes {
+                    entity.insert(change.field.clone(), change.before.clone());
+                }
+            }
+        }
+        HistoryChange::Assignation { before, .. } => root["assignation"] = before.clone(),
+        HistoryChange::AssignationSlots {
+            session,
+            before_length,
+            slots,
+            ..
+        } => {
+            let assignation = match session {
+                None => Some(&mut root["assignation"]),
+                Some(session) => entities_of(root, EntityKind::ExamSession)
+                    .and_then(|sessions| sessions.iter_mut().find(|entity| has_id(entity, session)))
+                    .map(|session| &mut session["assignation"]),
+            };
+            if let Some(assignation) = assignation {
+                undo_slots(assignation, *before_length, slots);
+            }
+        }
+        HistoryChange::Ids { before, .. } => {
+            root["ids"] = serde_json::to_value(before).unwrap_or(Value::Null)
+        }
+    }
+}
+
+fn undo_slots(assignation: &mut Value, before_length: Option<usize>, slots: &[SlotChange]) {
+    let Some(length) = before_length else {
+        *assignation = Value::Null;
+        return;
+    };
+    let mut restored = match assignation.take() {
+        Value::Array(slots) => slots,
+        _ => Vec::new(),
+    };
+    restored.resize(restored.len().max(length), Value::Null);
+    for slot in slots {
+        if let Some(restored) = restored.get_mut(slot.index) {
+            *restored = slot.before.clone();
+        }
+    }
+    restored.truncate(length);
+    *assignation = Value::Array(restored);
+}
+
+fn entities_of(root: &mut Value, kind: EntityKind) -> Option<&mut Vec<Value>> {
+    let field = match kind {
+        EntityKind::Examinee => "examinees",
+        EntityKind::AcademicCentre => "academicCentres",
+        EntityKind::Subject => "subjects",
+        EntityKind::Vigilant => "vigilants",
+        EntityKind::Classroom => "classrooms",
+        EntityKind::ExamSession => "examSessions",
+        EntityKind::Court => "courts",
+        EntityKind::Building => "buildings",
+    };
+    root.get_mut(field).and_then(Value::as_array_mut)
+}
+
+fn has_id(entity: &Value, id: &EntityId) -> bool {
+    entity.get("id") == Some(&Value::from(id.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn examinee(id: i32, name: &str) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": name,
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "courtId": null,
+            "academicCentreId": null,
+            "subjectsIds": [],
+        })
+    }
+
+    fn exam(subject: i32, examinees: &[i32]) -> Value {
+        json!({
+            "type": "individualExam",
+            "subject": subject,
+            "examinees": examinees,
+            "classrooms": [],
+            "vigilants": [],
+        })
+    }
+
+    fn session(id: i32, assignation: Value) -> Value {
+        json!({
+            "id": id,
+            "name": format!("Session {id}"),
+            "schedules": [],
+            "classroomsIds": [],
+            "vigilantsIds": [],
+            "enrollments": [],
+            "assignation": assignation,
+        })
+    }
+
+    fn values(examinees: Vec<Value>, sessions: Vec<Value>, assignation: Value) -> AppValues {
+        let mut values: AppValues = serde_json::from_value(json!({
+            "examinees": examinees,
+            "academicCentres": [],
+            "subjects": [],
+            "vigilants": [],
+            "classrooms": [],
+            "examSessions": sessions,
+            "assignation": assignation,
+            "ids": Ids::default(),
+        }))
+        .unwrap();
+        values.ids = Ids::covering(&values);
+        values
+    }
+
+    /// Entries of a project saved once for each of the versions
+    fn history(versions: &[AppValues]) -> Vec<HistoryEntry> {
+        versions
+            .iter()
+            .enumerate()
+            .map(|(index, values)| {
+                HistoryEntry::record(
+                    index.checked_sub(1).map(|previous| &versions[previous]),
+                    values,
+                    "operator".to_owned(),
+                    HistoryOperation::Save,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn values_at_an_entry_undo_every_later_entry() {
+        let versions = [
+            values(vec![examinee(0, "First")], vec![], Value::Null),
+            values(
+                vec![examinee(0, "Renamed"), examinee(1, "Added")],
+                vec![session(0, Value::Null)],
+                json!([exam(0, &[0]), exam(1, &[1])]),
+            ),
+            values(
+                vec![examinee(1, "Added")],
+                vec![session(0, json!([exam(0, &[1])]))],
+                json!([exam(0, &[0])]),
+            ),
+            values(
+                vec![examinee(1, "Added"), examinee(2, "Last")],
+                vec![session(0, json!([exam(0, &[1, 2]), exam(1, &[2])]))],
+                Value::Null,
+            ),
+        ];
+        let entries = history(&versions);
+        let current = versions.last().unwrap();
+
+        for (index, version) in versions.iter().enumerate() {
+            let mut values = values_at(current, &entries, index).unwrap();
+            // Removed entities come back at the end
+            values.examinees.sort_by(|a, b| a.id.cmp(&b.id));
+            assert_eq!(comparable(&values), comparable(version), "entry {index}");
+        }
+    }
+
+    #[test]
+    fn only_the_slots_that_changed_are_recorded() {
+        let before = values(
+            vec![],
+            vec![session(0, json!([exam(0, &[0]), exam(1, &[1])]))],
+            json!([exam(0, &[0]), exam(1, &[1]), exam(2, &[2])]),
+        );
+        let after = values(
+            vec![],
+            vec![session(0, json!([exam(0, &[0]), exam(1, &[0, 1])]))],
+            json!([exam(0, &[0]), exam(1, &[1, 2])]),
+        );
+
+        let entry = HistoryEntry::record(
+            Some(&before),
+            &after,
+            "operator".to_owned(),
+            HistoryOperation::Save,
+        );
+
+        assert_eq!(
+            serde_json::to_value(entry.changes).unwrap(),
+            json!([
+                {
+                    "type": "assignationSlots",
+                    "session": 0,
+                    "beforeLength": 2,
+                    "afterLength": 2,
+                    "slots": [{ "index": 1, "before": exam(1, &[1]), "after": exam(1, &[0, 1]) }],
+                },
+                {
+                    "type": "assignationSlots",
+                    "session": null,
+                    "beforeLength": 3,
+                    "afterLength": 2,
+                    "slots": [
+                        { "index": 1, "before": exam(1, &[1]), "after": exam(1, &[1, 2]) },
+                        { "index": 2, "before": exam(2, &[2]), "after": null },
+                    ],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn whole_assignments_recorded_by_older_versions_are_undone() {
+        let before = values(vec![], vec![], json!([exam(0, &[0])]));
+        let after = values(vec![], vec![], json!([exam(0, &[1])]));
+        let entries: Vec<HistoryEntry> = serde_json::from_value(json!([
+            { "timestamp": 0, "operator": "operator", "operation": { "type": "create" }, "changes": [] },
+            {
+                "timestamp": 1,
+                "operator": "operator",
+                "operation": { "type": "save" },
+                "changes": [{
+                    "type": "assignation",
+                    "before": [exam(0, &[0])],
+                    "after": [exam(0, &[1])],
+                }],
+            },
+        ]))
+        .unwrap();
+
+        let values = values_at(&after, &entries, 0).unwrap();
+
+        assert_eq!(comparable(&values), comparable(&before));
+    }
+}
@@ -0,0 +1,602 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use super::{comparable, same, AppValues, Entity, EntityId, EntityKind, Ids};
+
+#[derive(Serialize, Clone, Copy, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum MergeSide {
+    Ours,
+    Theirs,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum MergeConflict {
+    /// Both sides changed the same field to different values, our value is kept
+    Field {
+        kind: EntityKind,
+        id: EntityId,
+        field: String,
+        #[ts(type = "unknown")]
+        base: Value,
+        #[ts(type = "unknown")]
+        ours: Value,
+        #[ts(type = "unknown")]
+        theirs: Value,
+    },
+    /// One side changed an entity the other side deleted, the changed entity is kept
+    #[serde(rename_all = "camelCase")]
+    ModifiedAndDeleted {
+        kind: EntityKind,
+        id: EntityId,
+        deleted_by: MergeSide,
+    },
+    /// Both sides changed the assignment in different ways, our assignment is kept
+    Assignation,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct RemappedId {
+    pub kind: EntityKind,
+    pub from: EntityId,
+    pub to: EntityId,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct MergeResult {
+    pub values: AppValues,
+    /// Changes that could not be merged automatically, in which our version was kept
+    pub conflicts: Vec<MergeConflict>,
+    /// Entities created by both sides with the same id, whose id was changed in their version
+    pub remapped: Vec<RemappedId>,
+}
+
+type IdRemap = HashMap<(EntityKind, EntityId), EntityId>;
+
+/// Merges the changes made in two projects that come from the same one, entity by entity and field
+/// by field
+pub fn merge(base: &AppValues, ours: &AppValues, theirs: &AppValues) -> MergeResult {
+    let mut ids = ours.ids.clone();
+    for kind in EntityKind::ALL {
+        let in_use = ours.ids_of(kind).into_iter().chain(theirs.ids_of(kind));
+        let first_free = EntityId::next_after(in_use)
+            .max(ours.ids.counter(kind).clone())
+            .max(theirs.ids.counter(kind).clone());
+        *ids.counter_mut(kind) = first_free;
+    }
+
+    let mut remap = IdRemap::new();
+    let mut remapped = Vec::new();
+    let mut collisions = |ids: &mut Ids, found: Vec<(EntityKind, EntityId)>| {
+        for (kind, from) in found {
+            let counter = ids.counter_mut(kind);
+            let to = counter.clone();
+            *counter = EntityId(to.0 + 1);
+            remap.insert((kind, from.clone()), to.clone());
+            remapped.push(RemappedId { kind, from, to });
+        }
+    };
+    collisions(
+        &mut ids,
+        find_collisions(&base.examinees, &ours.examinees, &theirs.examinees),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(
+            &base.academic_centres,
+            &ours.academic_centres,
+            &theirs.academic_centres,
+        ),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(&base.subjects, &ours.subjects, &theirs.subjects),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(&base.vigilants, &ours.vigilants, &theirs.vigilants),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(&base.classrooms, &ours.classrooms, &theirs.classrooms),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(
+            &base.exam_sessions,
+            &ours.exam_sessions,
+            &theirs.exam_sessions,
+        ),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(&base.courts, &ours.courts, &theirs.courts),
+    );
+    collisions(
+        &mut ids,
+        find_collisions(&base.buildings, &ours.buildings, &theirs.buildings),
+    );
+
+    let mut theirs = theirs.clone();
+    apply_remap(&mut theirs, &remap);
+
+    let mut conflicts = Vec::new();
+    let examinees = merge_entities(
+        &base.examinees,
+        &ours.examinees,
+        &theirs.examinees,
+        &mut conflicts,
+    );
+    let academic_centres = merge_entities(
+        &base.academic_centres,
+        &ours.academic_centres,
+        &theirs.academic_centres,
+        &mut conflicts,
+    );
+    let subjects = merge_entities(
+        &base.subjects,
+        &ours.subjects,
+        &theirs.subjects,
+        &mut conflicts,
+    );
+    let vigilants = merge_entities(
+        &base.vigilants,
+        &ours.vigilants,
+        &theirs.vigilants,
+        &mut conflicts,
+    );
+    let classrooms = merge_entities(
+        &base.classrooms,
+        &ours.classrooms,
+        &theirs.classrooms,
+        &mut conflicts,
+    );
+    let exam_sessions = merge_entities(
+        &base.exam_sessions,
+        &ours.exam_sessions,
+        &theirs.exam_sessions,
+        &mut conflicts,
+    );
+    let courts = merge_entities(&base.courts, &ours.courts, &theirs.courts, &mut conflicts);
+    let buildings = merge_entities(
+        &base.buildings,
+        &ours.buildings,
+        &theirs.buildings,
+        &mut conflicts,
+    );
+
+    let base_assignation = comparable(&base.assignation);
+    let assignation = if comparable(&ours.assignation) == base_assignation {
+        theirs.assignation
+    } else {
+        if comparable(&theirs.assignation) != base_assignation
+            && comparable(&theirs.assignation) != comparable(&ours.assignation)
+        {
+            conflicts.push(MergeConflict::Assignation);
+        }
+        ours.assignation.clone()
+    };
+
+    MergeResult {
+        values: AppValues {
+            examinees,
+            academic_centres,
+            subjects,
+            vigilants,
+            classrooms,
+            exam_sessions,
+            courts,
+            buildings,
+            assignation,
+            ids,
+        },
+        conflicts,
+        remapped,
+    }
+}
+
+/// Ids that both sides used for different new entities
+fn find_collisions<T: Entity + Serialize>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+) -> Vec<(EntityKind, EntityId)> {
+    let base_ids: HashSet<&EntityId> = base.iter().map(Entity::id).collect();
+    let ours: HashMap<&EntityId, &T> = ours.iter().map(|entity| (entity.id(), entity)).collect();
+    theirs
+        .iter()
+        .filter(|entity| !base_ids.contains(entity.id()))
+        .filter(|entity| {
+            ours.get(entity.id())
+                .is_some_and(|our_entity| comparable(our_entity) != comparable(entity))
+        })
+        .map(|entity| (T::KIND, entity.id().clone()))
+        .collect()
+}
+
+fn apply_remap(values: &mut AppValues, remap: &IdRemap) {
+    if remap.is_empty() {
+        return;
+    }
+    let mut visit = |kind: EntityKind, id: &mut EntityId| {
+        if let Some(to) = remap.get(&(kind, id.clone())) {
+            *id = to.clone();
+        }
+    };
+    remap_entities(&mut values.examinees, &mut visit);
+    remap_entities(&mut values.academic_centres, &mut visit);
+    remap_entities(&mut values.subjects, &mut visit);
+    remap_entities(&mut values.vigilants, &mut visit);
+    remap_entities(&mut values.classrooms, &mut visit);
+    remap_entities(&mut values.exam_sessions, &mut visit);
+    remap_entities(&mut values.courts, &mut visit);
+    remap_entities(&mut values.buildings, &mut visit);
+    if let Some(assignation) = &mut values.assignation {
+        assignation.for_each_reference_mut(&mut visit);
+    }
+}
+
+fn remap_entities<T: Entity>(entities: &mut [T], visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+    for entity in entities {
+        visit(T::KIND, entity.id_mut());
+        entity.for_each_reference_mut(visit);
+    }
+}
+
+fn merge_entities<T: Entity + Serialize + DeserializeOwned + Clone>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<T> {
+    let base: HashMap<&EntityId, &T> = base.iter().map(|entity| (entity.id(), entity)).collect();
+    let their_entities: HashMap<&EntityId, &T> =
+        theirs.iter().map(|entity| (entity.id(), entity)).collect();
+    let our_ids: HashSet<&EntityId> = ours.iter().map(Entity::id).collect();
+
+    let mut merged = Vec::new();
+    for entity in ours {
+        match (base.get(entity.id()), their_entities.get(entity.id())) {
+            (None, _) => merged.push(entity.clone()),
+            (Some(base), None) => {
+                if comparable(base) != comparable(entity) {
+                    conflicts.push(MergeConflict::ModifiedAndDeleted {
+                        kind: T::KIND,
+                        id: entity.id().clone(),
+                        deleted_by: MergeSide::Theirs,
+                    });
+                    merged.push(entity.clone());
+                }
+            }
+            (Some(base), Some(their_entity)) => {
+                merged.push(merge_entity(base, entity, their_entity, conflicts))
+            }
+        }
+    }
+    for entity in theirs {
+        if our_ids.contains(entity.id()) {
+            continue;
+        }
+        match base.get(entity.id()) {
+            None => merged.push(entity.clone()),
+            Some(base) => {
+                if comparable(base) != comparable(entity) {
+                    conflicts.push(MergeConflict::ModifiedAndDeleted {
+                        kind: T::KIND,
+                        id: entity.id().clone(),
+                        deleted_by: MergeSide::Ours,
+                    });
+                    merged.push(entity.clone());
+                }
+            }
+        }
+    }
+    merged
+}
+
+fn merge_entity<T: Entity + Serialize + DeserializeOwned + Clone>(
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    conflicts: &mut Vec<MergeConflict>,
+) -> T {
+    let (
+        Ok(Value::Object(base_fields)),
+        Ok(Value::Object(our_fields)),
+        Ok(Value::Object(their_fields)),
+    ) = (
+        serde_json::to_value(base),
+        serde_json::to_value(ours),
+        serde_json::to_value(theirs),
+    )
+    else {
+        return ours.clone();
+    };
+
+    let mut merged = Map::new();
+    let fields: HashSet<&String> = base_fields
+        .keys()
+        .chain(our_fields.keys())
+        .chain(their_fields.keys())
+        .collect();
+    for field in fields {
+        let base_value = base_fields.get(field).cloned().unwrap_or(Value::Null);
+        let our_value = our_fields.get(field).cloned().unwrap_or(Value::Null);
+        let their_value = their_fields.get(field).cloned().unwrap_or(Value::Null);
+
+        let value = if same(&our_value, &base_value) {
+            their_value
+        } else if same(&their_value, &base_value) || same(&their_value, &our_value) {
+            our_value
+        } else {
+            conflicts.push(MergeConflict::Field {
+                kind: T::KIND,
+                id: ours.id().clone(),
+                field: field.clone(),
+                base: base_value,
+                ours: our_value.clone(),
+                theirs: their_value,
+            });
+            our_value
+        };
+        merged.insert(field.clone(), value);
+    }
+
+    serde_json::from_value(Value::Object(merged)).unwrap_or_else(|_| ours.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::examinee::Examinee;
+
+    fn examinee(id: i32, name: &str, subjects: &[i32]) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": name,
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "courtId": null,
+            "academicCentreId": null,
+            "subjectsIds": subjects,
+        })
+    }
+
+    fn subject(id: i32, name: &str) -> Value {
+        json!({ "id": id, "name": name, "kind": "OBLIGATORY" })
+    }
+
+    fn exam(subject: i32, examinees: &[i32]) -> Value {
+        json!({
+            "type": "individualExam",
+            "subject": subject,
+            "examinees": examinees,
+            "classrooms": [],
+            "vigilants": [],
+        })
+    }
+
+    fn values(examinees: Vec<Value>, subjects: Vec<Value>, assignation: Value) -> AppValues {
+        let mut values: AppValues = serde_json::from_value(json!({
+            "examinees": examinees,
+            "academicCentres": [],
+            "subjects": subjects,
+            "vigilants": [],
+            "classrooms": [],
+            "assignation": assignation,
+            "ids": Ids::default(),
+        }))
+        .unwrap();
+        values.ids = Ids::covering(&values);
+        values
+    }
+
+    fn examinee_named<'a>(values: &'a AppValues, name: &str) -> &'a Examinee {
+        values
+            .examinees
+            .iter()
+            .find(|examinee| examinee.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn edits_of_different_fields_are_both_kept() {
+        let base = values(vec![examinee(0, "Base", &[])], vec![], Value::Null);
+        let ours = values(vec![examinee(0, "Ours", &[])], vec![], Value::Null);
+        let mut theirs = base.clone();
+        theirs.examinees[0].origin = "Theirs".to_owned();
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.values.examinees[0].name, "Ours");
+        assert_eq!(result.values.examinees[0].origin, "Theirs");
+    }
+
+    #[test]
+    fn edits_of_the_same_field_keep_ours_and_are_reported() {
+        let base = values(vec![examinee(0, "Base", &[])], vec![], Value::Null);
+        let ours = values(vec![examinee(0, "Ours", &[])], vec![], Value::Null);
+        let theirs = values(vec![examinee(0, "Theirs", &[])], vec![], Value::Null);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.values.examinees[0].name, "Ours");
+        assert!(matches!(
+            result.conflicts.as_slice(),
+            [MergeConflict::Field { kind: EntityKind::Examinee, id: EntityId(0), field, .. }]
+                if field == "name"
+        ));
+    }
+
+    #[test]
+    fn the_same_edit_on_both_sides_is_not_a_conflict() {
+        let base = values(vec![examinee(0, "Base", &[])], vec![], Value::Null);
+        let ours = values(vec![examinee(0, "Both", &[])], vec![], Value::Null);
+
+        let result = merge(&base, &ours, &ours);
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.values.examinees[0].name, "Both");
+    }
+
+    #[test]
+    fn entities_created_on_both_sides_with_the_same_id_are_remapped_in_theirs() {
+        let base = values(
+            vec![examinee(0, "Base", &[0])],
+            vec![subject(0, "Base")],
+            Value::Null,
+        );
+        let ours = values(
+            vec![examinee(0, "Base", &[0]), examinee(1, "Ours", &[1])],
+            vec![subject(0, "Base"), subject(1, "Ours")],
+            Value::Null,
+        );
+        let theirs = values(
+            vec![examinee(0, "Base", &[0]), examinee(1, "Theirs", &[1])],
+            vec![subject(0, "Base"), subject(1, "Theirs")],
+            json!([exam(1, &[1])]),
+        );
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.conflicts.is_empty());
+        let remapped: Vec<(EntityKind, &EntityId, &EntityId)> = result
+            .remapped
+            .iter()
+            .map(|remapped| (remapped.kind, &remapped.from, &remapped.to))
+            .collect();
+        assert_eq!(
+            remapped,
+            [
+                (EntityKind::Examinee, &EntityId(1), &EntityId(2)),
+                (EntityKind::Subject, &EntityId(1), &EntityId(2)),
+            ]
+        );
+        assert_eq!(examinee_named(&result.values, "Ours").id, EntityId(1));
+        let their_examinee = examinee_named(&result.values, "Theirs");
+        assert_eq!(their_examinee.id, EntityId(2));
+        assert_eq!(their_examinee.subjects_ids, [EntityId(2)].into());
+        let exams = result
+            .values
+            .assignation
+            .as_ref()
+            .unwrap()
+            .individual_exams();
+        assert_eq!(exams[0].subject, EntityId(2));
+        assert_eq!(exams[0].examinees, [EntityId(2)]);
+        assert_eq!(
+            *result.values.ids.counter(EntityKind::Examinee),
+            EntityId(3)
+        );
+        assert_eq!(*result.values.ids.counter(EntityKind::Subject), EntityId(3));
+    }
+
+    #[test]
+    fn entities_created_on_both_sides_alike_are_not_remapped() {
+        let base = values(vec![], vec![], Value::Null);
+        let ours = values(vec![examinee(0, "Same", &[])], vec![], Value::Null);
+
+        let result = merge(&base, &ours, &ours);
+
+        assert!(result.remapped.is_empty());
+        assert_eq!(result.values.examinees.len(), 1);
+    }
+
+    #[test]
+    fn entities_deleted_on_one_side_and_modified_on_the_other_are_kept() {
+        let base = values(
+            vec![examinee(0, "Base", &[]), examinee(1, "Base", &[])],
+            vec![],
+            Value::Null,
+        );
+        let ours = values(vec![examinee(1, "Ours", &[])], vec![], Value::Null);
+        let theirs = values(vec![examinee(0, "Theirs", &[])], vec![], Value::Null);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(examinee_named(&result.values, "Ours").id, EntityId(1));
+        assert_eq!(examinee_named(&result.values, "Theirs").id, EntityId(0));
+        assert!(matches!(
+            result.conflicts.as_slice(),
+            [
+                MergeConflict::ModifiedAndDeleted {
+                    id: EntityId(1),
+                    deleted_by: MergeSide::Theirs,
+                    ..
+                },
+                MergeConflict::ModifiedAndDeleted {
+                    id: EntityId(0),
+                    deleted_by: MergeSide::Ours,
+                    ..
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn entities_deleted_on_one_side_and_untouched_on_the_other_are_deleted() {
+        let base = values(
+            vec![examinee(0, "Base", &[]), examinee(1, "Base", &[])],
+            vec![],
+            Value::Null,
+        );
+        let ours = values(vec![examinee(1, "Base", &[])], vec![], Value::Null);
+        let theirs = values(vec![examinee(0, "Base", &[])], vec![], Value::Null);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.conflicts.is_empty());
+        assert!(result.values.examinees.is_empty());
+    }
+
+    #[test]
+    fn the_assignment_changed_only_by_them_is_taken() {
+        let base = values(
+            vec![examinee(0, "Base", &[0])],
+            vec![subject(0, "Base")],
+            Value::Null,
+        );
+        let mut theirs = base.clone();
+        theirs.assignation = values(vec![], vec![], json!([exam(0, &[0])])).assignation;
+
+        let result = merge(&base, &base, &theirs);
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.values.assignation, theirs.assignation);
+    }
+
+    #[test]
+    fn the_assignment_changed_by_both_sides_keeps_ours() {
+        let base = values(
+            vec![examinee(0, "Base", &[0])],
+            vec![subject(0, "Base")],
+            Value::Null,
+        );
+        let mut ours = base.clone();
+        ours.assignation = values(vec![], vec![], json!([exam(0, &[0])])).assignation;
+        let mut theirs = base.clone();
+        theirs.assignation = values(vec![], vec![], json!([exam(0, &[])])).assignation;
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.values.assignation, ours.assignation);
+        assert!(matches!(
+            result.conflicts.as_slice(),
+            [MergeConflict::Assignation]
+        ));
+    }
+}
@@ -1,25 +1,94 @@
 use academic_centre::AcademicCentre;
 use assignation::AllExamConfiguration;
+use building::Building;
 use classroom::Classroom;
+use court::Court;
+use exam_session::ExamSession;
 use examinee::Examinee;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use subject::Subject;
 use ts_rs::TS;
 use vigilant::Vigilant;
 
 pub mod academic_centre;
+pub mod accommodation;
 pub mod assignation;
+pub mod availability;
+pub mod building;
 pub mod classroom;
+pub mod court;
+pub mod diff;
+pub mod exam_session;
 pub mod examinee;
+pub mod history;
+pub mod merge;
 pub mod subject;
+pub mod validation;
 pub mod vigilant;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash, TS)]
+#[derive(
+    Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Default, TS,
+)]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 
 pub struct EntityId(i32);
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+impl EntityId {
+    /// Id that follows every one of the given ids, or the first id if there are none
+    fn next_after<'a>(ids: impl Iterator<Item = &'a EntityId>) -> EntityId {
+        ids.max()
+            .map(|EntityId(id)| EntityId(id + 1))
+            .unwrap_or(EntityId(0))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Hash, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum EntityKind {
+    Examinee,
+    AcademicCentre,
+    Subject,
+    Vigilant,
+    Classroom,
+    ExamSession,
+    Court,
+    Building,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 8] = [
+        EntityKind::Examinee,
+        EntityKind::AcademicCentre,
+        EntityKind::Subject,
+        EntityKind::Vigilant,
+        EntityKind::Classroom,
+        EntityKind::ExamSession,
+        EntityKind::Court,
+        EntityKind::Building,
+    ];
+}
+
+/// Common behaviour of the models that are stored by id
+pub trait Entity {
+    const KIND: EntityKind;
+
+    fn id(&self) -> &EntityId;
+
+    fn id_mut(&mut self) -> &mut EntityId;
+
+    /// Human readable name of the entity, for reports
+    fn label(&self) -> String;
+
+    /// Visits the ids of the other entities this one points to
+    fn for_each_reference_mut(&mut self, _visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {}
+
+    /// Drops the ids of the other entities this one points to for which `keep` returns false
+    fn retain_references(&mut self, _keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {}
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub struct AppValues {
@@ -28,11 +97,95 @@ pub struct AppValues {
     pub subjects: Vec<Subject>,
     pub vigilants: Vec<Vigilant>,
     pub classrooms: Vec<Classroom>,
+    /// Calls of the exams held in the project. Without sessions, the exam dates of the subjects
+    /// and the assignment apply to the only call there is
+    #[serde(default)]
+    pub exam_sessions: Vec<ExamSession>,
+    #[serde(default)]
+    pub courts: Vec<Court>,
+    #[serde(default)]
+    pub buildings: Vec<Building>,
     pub assignation: Option<AllExamConfiguration>,
     pub ids: Ids,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+impl AppValues {
+    /// Ids of all the entities of the given kind
+    pub fn ids_of(&self, kind: EntityKind) -> Vec<&EntityId> {
+        match kind {
+            EntityKind::Examinee => self.examinees.iter().map(Entity::id).collect(),
+            EntityKind::AcademicCentre => self.academic_centres.iter().map(Entity::id).collect(),
+            EntityKind::Subject => self.subjects.iter().map(Entity::id).collect(),
+            EntityKind::Vigilant => self.vigilants.iter().map(Entity::id).collect(),
+            EntityKind::Classroom => self.classrooms.iter().map(Entity::id).collect(),
+            EntityKind::ExamSession => self.exam_sessions.iter().map(Entity::id).collect(),
+            EntityKind::Court => self.courts.iter().map(Entity::id).collect(),
+            EntityKind::Building => self.buildings.iter().map(Entity::id).collect(),
+        }
+    }
+
+    /// The project as seen from one of its sessions: only the subjects scheduled in it, with the
+    /// dates of the session, the classrooms and vigilants that take part in it, the examinees
+    /// enrolled in it with the subjects they take and the assignment of the session
+    pub fn in_session(&self, session: &EntityId) -> Option<AppValues> {
+        let session = self
+            .exam_sessions
+            .iter()
+            .find(|exam_session| exam_session.id == *session)?;
+
+        let subjects = session
+            .schedules
+            .iter()
+            .filter_map(|schedule| {
+                let subject = self.subjects.iter().find(|s| s.id == schedule.subject)?;
+                Some(Subject {
+                    exam_date: schedule.exam_date,
+                    exam_duration: schedule.exam_duration,
+                    ..subject.clone()
+                })
+            })
+            .collect();
+        let examinees = session
+            .enrollments
+            .iter()
+            .filter_map(|enrollment| {
+                let examinee = self
+                    .examinees
+                    .iter()
+                    .find(|e| e.id == enrollment.examinee)?;
+                Some(Examinee {
+                    subjects_ids: enrollment.subjects_ids.clone(),
+                    ..examinee.clone()
+                })
+            })
+            .collect();
+
+        Some(AppValues {
+            examinees,
+            academic_centres: self.academic_centres.clone(),
+            subjects,
+            vigilants: self
+                .vigilants
+                .iter()
+                .filter(|vigilant| session.vigilants_ids.contains(&vigilant.id))
+                .cloned()
+                .collect(),
+            classrooms: self
+                .classrooms
+                .iter()
+                .filter(|classroom| session.classrooms_ids.contains(&classroom.id))
+                .cloned()
+                .collect(),
+            exam_sessions: Vec::new(),
+            courts: self.courts.clone(),
+            buildings: self.buildings.clone(),
+            assignation: session.assignation.clone(),
+            ids: self.ids.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub struct Ids {
@@ -41,4 +194,80 @@ pub struct Ids {
     subjects: EntityId,
     vigilants: EntityId,
     classrooms: EntityId,
+    #[serde(default)]
+    exam_sessions: EntityId,
+    #[serde(default)]
+    courts: EntityId,
+    #[serde(default)]
+    buildings: EntityId,
+}
+
+impl Ids {
+    /// Builds the id counters so no new entity can collide with the ones in the values. The
+    /// counters already in the values are ignored
+    pub fn covering(values: &AppValues) -> Self {
+        let mut ids = Ids::default();
+        for kind in EntityKind::ALL {
+            *ids.counter_mut(kind) = EntityId::next_after(values.ids_of(kind).into_iter());
+        }
+        ids
+    }
+
+    /// Counter of the next id of the given kind of entity
+    pub fn counter(&self, kind: EntityKind) -> &EntityId {
+        match kind {
+            EntityKind::Examinee => &self.examinees,
+            EntityKind::AcademicCentre => &self.academic_centres,
+            EntityKind::Subject => &self.subjects,
+            EntityKind::Vigilant => &self.vigilants,
+            EntityKind::Classroom => &self.classrooms,
+            EntityKind::ExamSession => &self.exam_sessions,
+            EntityKind::Court => &self.courts,
+            EntityKind::Building => &self.buildings,
+        }
+    }
+
+    pub fn counter_mut(&mut self, kind: EntityKind) -> &mut EntityId {
+        match kind {
+            EntityKind::Examinee => &mut self.examinees,
+            EntityKind::AcademicCentre => &mut self.academic_centres,
+            EntityKind::Subject => &mut self.subjects,
+            EntityKind::Vigilant => &mut self.vigilants,
+            EntityKind::Classroom => &mut self.classrooms,
+            EntityKind::ExamSession => &mut self.exam_sessions,
+            EntityKind::Court => &mut self.courts,
+            EntityKind::Building => &mut self.buildings,
+        }
+    }
+}
+
+/// Value of something in a form where the order of lists of ids or names does not matter
+fn comparable(value: &impl Serialize) -> Value {
+    normalize(serde_json::to_value(value).unwrap_or(Value::Null))
+}
+
+fn same(a: &Value, b: &Value) -> bool {
+    normalize(a.clone()) == normalize(b.clone())
+}
+
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Array(values) => {
+            let mut values: Vec<Value> = values.into_iter().map(normalize).collect();
+            if values
+                .iter()
+                .all(|value| value.is_number() || value.is_string())
+            {
+                values.sort_by_key(|value| value.to_string());
+            }
+            Value::Array(values)
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, normalize(value)))
+                .collect(),
+        ),
+        value => value,
+    }
 }
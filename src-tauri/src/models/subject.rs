@@ -1,8 +1,11 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, FixedOffset};
 use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
 
-use super::EntityId;
+use super::{classroom::ClassroomFeature, examinee::Examinee, Entity, EntityId, EntityKind};
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
@@ -32,8 +35,51 @@ pub struct Subject {
     pub id: EntityId,
     pub name: String,
     pub kind: SubjectKind,
-    pub exam_date: Option<String>,
-    pub exam_duration: Option<String>,
+    /// Start of the exam, with the offset of the timezone it was set in
+    #[serde(default, with = "exam_date")]
+    #[ts(type = "string | null")]
+    pub exam_date: Option<DateTime<FixedOffset>>,
+    #[serde(default, with = "exam_duration")]
+    #[ts(type = "string | null")]
+    pub exam_duration: Option<Duration>,
+    /// Features the classrooms of the exam must have
+    #[serde(default)]
+    pub required_features: HashSet<ClassroomFeature>,
+}
+
+impl Subject {
+    /// When the exam finishes, if both its start and its duration are known
+    pub fn exam_end(&self) -> Option<DateTime<FixedOffset>> {
+        self.exam_date?.checked_add_signed(self.exam_duration?)
+    }
+
+    /// When the last of the examinees finishes the exam, extra time included
+    pub fn exam_end_for<'e>(
+        &self,
+        examinees: impl IntoIterator<Item = &'e Examinee>,
+    ) -> Option<DateTime<FixedOffset>> {
+        examinees
+            .into_iter()
+            .filter_map(|examinee| examinee.exam_end(self))
+            .chain(self.exam_end())
+            .max()
+    }
+}
+
+impl Entity for Subject {
+    const KIND: EntityKind = EntityKind::Subject;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        self.name.clone()
+    }
 }
 
 #[skip_serializing_none]
@@ -44,3 +90,155 @@ pub struct ImportedSubject {
     pub name: String,
     pub kind: SubjectKind,
 }
+
+/// Exam dates are stored as RFC 3339 date-times with offset. Files written before had whatever
+/// the frontend produced, so dates without offset are read in the local timezone and unreadable
+/// ones are dropped and reported when the file is loaded
+pub(super) mod exam_date {
+    use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+    use log::warn;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => serializer.serialize_str(&date.to_rfc3339()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<FixedOffset>>, D::Error> {
+        let Some(date) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let parsed = parse(date.trim());
+        if parsed.is_none() {
+            warn!("Dropping unreadable exam date {date:?}");
+        }
+        Ok(parsed)
+    }
+
+    pub fn parse(date: &str) -> Option<DateTime<FixedOffset>> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+            return Some(date);
+        }
+        let naive = [
+            "%Y-%m-%dT%H:%M:%S%.f",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M:%S%.f",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|date| date.fixed_offset())
+    }
+}
+
+/// Exam durations are stored as ISO 8601 durations made of hours, minutes and seconds. Older files
+/// may have any ISO 8601 duration without years or months, or a number of minutes. Negative
+/// durations and durations longer than a week are unreadable
+pub(super) mod exam_duration {
+    use chrono::Duration;
+    use log::warn;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Longest duration an exam can have
+    const MAX_MILLISECONDS: f64 = 7.0 * 86_400_000.0;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_str(&format(duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        let parsed = match Option::<Value>::deserialize(deserializer)? {
+            None | Some(Value::Null) => return Ok(None),
+            Some(Value::String(duration)) => parse(duration.trim()),
+            Some(Value::Number(minutes)) => minutes
+                .as_f64()
+                .and_then(|minutes| from_milliseconds(minutes * 60_000.0)),
+            Some(_) => None,
+        };
+        if parsed.is_none() {
+            warn!("Dropping unreadable exam duration");
+        }
+        Ok(parsed)
+    }
+
+    fn format(duration: &Duration) -> String {
+        let milliseconds = duration.num_milliseconds();
+        if milliseconds == 0 {
+            return "PT0S".to_owned();
+        }
+        let sign = if milliseconds < 0 { "-" } else { "" };
+        let milliseconds = milliseconds.unsigned_abs();
+        let hours = milliseconds / 3_600_000;
+        let minutes = milliseconds / 60_000 % 60;
+        let seconds = milliseconds % 60_000;
+
+        let mut formatted = format!("{sign}PT");
+        if hours > 0 {
+            formatted += &format!("{hours}H");
+        }
+        if minutes > 0 {
+            formatted += &format!("{minutes}M");
+        }
+        match (seconds / 1000, seconds % 1000) {
+            (0, 0) => {}
+            (seconds, 0) => formatted += &format!("{seconds}S"),
+            (seconds, milliseconds) => formatted += &format!("{seconds}.{milliseconds:03}S"),
+        }
+        formatted
+    }
+
+    fn parse(duration: &str) -> Option<Duration> {
+        let duration = duration.strip_prefix('P')?;
+        let (date, time) = duration.split_once('T').unwrap_or((duration, ""));
+
+        let mut milliseconds = 0.0;
+        let date_units: &[(char, f64)] = &[('W', 604_800.0), ('D', 86_400.0)];
+        let time_units: &[(char, f64)] = &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)];
+        for (part, units) in [(date, date_units), (time, time_units)] {
+            let mut number = String::new();
+            for character in part.chars() {
+                if character.is_ascii_digit() || character == '.' || character == ',' {
+                    number.push(if character == ',' { '.' } else { character });
+                    continue;
+                }
+                let (_, seconds) = units.iter().find(|(unit, _)| *unit == character)?;
+                milliseconds += number.parse::<f64>().ok()? * seconds * 1000.0;
+                number.clear();
+            }
+            if !number.is_empty() {
+                return None;
+            }
+        }
+        from_milliseconds(milliseconds)
+    }
+
+    fn from_milliseconds(milliseconds: f64) -> Option<Duration> {
+        if !(0.0..=MAX_MILLISECONDS).contains(&milliseconds) {
+            return None;
+        }
+        Duration::try_milliseconds(milliseconds.round() as i64)
+    }
+}
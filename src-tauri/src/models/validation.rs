@@ -0,0 +1,502 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use super::{AppValues, Entity, EntityId, EntityKind};
+
+#[derive(Serialize, Debug, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum IntegrityIssue {
+    /// An entity, or the assignment when there is no owner, points to an entity that does not exist
+    #[serde(rename_all = "camelCase")]
+    DanglingReference {
+        owner_kind: Option<EntityKind>,
+        owner_id: Option<EntityId>,
+        kind: EntityKind,
+        id: EntityId,
+    },
+    /// More than one entity of the same kind has the id, only the first one is kept on repair
+    DuplicateId { kind: EntityKind, id: EntityId },
+    /// More than one court has the number, only the first one is kept on repair
+    DuplicateCourtNumber { number: i16 },
+    /// The counter would give a new entity an id that is already in use
+    CounterBehind {
+        kind: EntityKind,
+        counter: EntityId,
+        highest: EntityId,
+    },
+    /// A value of the entity could not be read when the file was loaded, it is dropped on repair
+    UnreadableValue {
+        kind: EntityKind,
+        id: EntityId,
+        field: String,
+        value: String,
+    },
+}
+
+/// Exam dates and durations that were in the stored values but could not be read, so they are
+/// missing from the values read out of them
+pub fn unreadable_values(stored: &Map<String, Value>, values: &AppValues) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    for (stored, subject) in stored_items(stored.get("subjects")).zip(&values.subjects) {
+        check_schedule(
+            stored,
+            "",
+            subject.exam_date.is_some(),
+            subject.exam_duration.is_some(),
+            (EntityKind::Subject, &subject.id),
+            &mut issues,
+        );
+    }
+    for (stored, session) in stored_items(stored.get("examSessions")).zip(&values.exam_sessions) {
+        for (index, (stored, schedule)) in stored_items(stored.get("schedules"))
+            .zip(&session.schedules)
+            .enumerate()
+        {
+            check_schedule(
+                stored,
+                &format!("schedules[{index}]."),
+                schedule.exam_date.is_some(),
+                schedule.exam_duration.is_some(),
+                (EntityKind::ExamSession, &session.id),
+                &mut issues,
+            );
+        }
+    }
+    issues
+}
+
+fn stored_items(stored: Option<&Value>) -> impl Iterator<Item = &Value> {
+    stored.and_then(Value::as_array).into_iter().flatten()
+}
+
+fn check_schedule(
+    stored: &Value,
+    prefix: &str,
+    date_read: bool,
+    duration_read: bool,
+    (kind, id): (EntityKind, &EntityId),
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    for (field, read) in [("examDate", date_read), ("examDuration", duration_read)] {
+        if read {
+            continue;
+        }
+        let value = match stored.get(field) {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        };
+        issues.push(IntegrityIssue::UnreadableValue {
+            kind,
+            id: id.clone(),
+            field: format!("{prefix}{field}"),
+            value,
+        });
+    }
+}
+
+/// Looks for references to entities that do not exist, repeated ids and id counters that are
+/// behind the ids in use
+pub fn validate(values: &AppValues) -> Vec<IntegrityIssue> {
+    check(&mut values.clone())
+}
+
+/// Same as [`validate`], but also fixes the issues found: duplicated entities and dangling
+/// references are dropped and counters are moved past the highest id
+pub fn repair(values: &mut AppValues) -> Vec<IntegrityIssue> {
+    check(values)
+}
+
+fn check(values: &mut AppValues) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    drop_duplicates(&mut values.examinees, &mut issues);
+    drop_duplicates(&mut values.academic_centres, &mut issues);
+    drop_duplicates(&mut values.subjects, &mut issues);
+    drop_duplicates(&mut values.vigilants, &mut issues);
+    drop_duplicates(&mut values.classrooms, &mut issues);
+    drop_duplicates(&mut values.exam_sessions, &mut issues);
+    drop_duplicates(&mut values.courts, &mut issues);
+    drop_duplicates(&mut values.buildings, &mut issues);
+    let mut court_numbers = HashSet::new();
+    values.courts.retain(|court| {
+        if court_numbers.insert(court.number) {
+            return true;
+        }
+        issues.push(IntegrityIssue::DuplicateCourtNumber {
+            number: court.number,
+        });
+        false
+    });
+
+    let existing: HashSet<(EntityKind, EntityId)> = EntityKind::ALL
+        .into_iter()
+        .flat_map(|kind| {
+            values
+                .ids_of(kind)
+                .into_iter()
+                .map(move |id| (kind, id.clone()))
+        })
+        .collect();
+
+    drop_dangling(&mut values.examinees, &existing, &mut issues);
+    drop_dangling(&mut values.academic_centres, &existing, &mut issues);
+    drop_dangling(&mut values.subjects, &existing, &mut issues);
+    drop_dangling(&mut values.vigilants, &existing, &mut issues);
+    drop_dangling(&mut values.classrooms, &existing, &mut issues);
+    drop_dangling(&mut values.exam_sessions, &existing, &mut issues);
+    drop_dangling(&mut values.courts, &existing, &mut issues);
+    drop_dangling(&mut values.buildings, &existing, &mut issues);
+    if let Some(assignation) = &mut values.assignation {
+        assignation.retain_references(&mut |kind, id| {
+            keep_existing(&existing, None, kind, id, &mut issues)
+        });
+    }
+
+    for kind in EntityKind::ALL {
+        let next = EntityId::next_after(values.ids_of(kind).into_iter());
+        let counter = values.ids.counter_mut(kind);
+        if *counter < next {
+            issues.push(IntegrityIssue::CounterBehind {
+                kind,
+                counter: counter.clone(),
+                highest: EntityId(next.0 - 1),
+            });
+            *counter = next;
+        }
+    }
+    issues
+}
+
+fn drop_duplicates<T: Entity>(entities: &mut Vec<T>, issues: &mut Vec<IntegrityIssue>) {
+    let mut seen = HashSet::new();
+    entities.retain(|entity| {
+        if seen.insert(entity.id().clone()) {
+            return true;
+        }
+        issues.push(IntegrityIssue::DuplicateId {
+            kind: T::KIND,
+            id: entity.id().clone(),
+        });
+        false
+    });
+}
+
+fn drop_dangling<T: Entity>(
+    entities: &mut [T],
+    existing: &HashSet<(EntityKind, EntityId)>,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    for entity in entities {
+        let owner = (T::KIND, entity.id().clone());
+        entity.retain_references(&mut |kind, id| {
+            keep_existing(existing, Some(&owner), kind, id, issues)
+        });
+    }
+}
+
+fn keep_existing(
+    existing: &HashSet<(EntityKind, EntityId)>,
+    owner: Option<&(EntityKind, EntityId)>,
+    kind: EntityKind,
+    id: &EntityId,
+    issues: &mut Vec<IntegrityIssue>,
+) -> bool {
+    if existing.contains(&(kind, id.clone())) {
+        return true;
+    }
+    issues.push(IntegrityIssue::DanglingReference {
+        owner_kind: owner.map(|(kind, _)| *kind),
+        owner_id: owner.map(|(_, id)| id.clone()),
+        kind,
+        id: id.clone(),
+    });
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::{comparable, Ids};
+
+    fn examinee(id: i32, name: &str, subjects: &[i32]) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": name,
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "courtId": null,
+            "academicCentreId": null,
+            "subjectsIds": subjects,
+        })
+    }
+
+    fn subject(id: i32) -> Value {
+        json!({ "id": id, "name": format!("Subject {id}"), "kind": "OBLIGATORY" })
+    }
+
+    fn court(id: i32, number: i16) -> Value {
+        json!({ "id": id, "number": number, "name": format!("Court {id}"), "academicCentresIds": [] })
+    }
+
+    fn exam(subject: i32, examinees: &[i32]) -> Value {
+        json!({
+            "type": "individualExam",
+            "subject": subject,
+            "examinees": examinees,
+            "classrooms": [],
+            "vigilants": [],
+        })
+    }
+
+    /// Values with the given entities and id counters that cover them
+    fn values(project: Value) -> AppValues {
+        let mut values = raw_values(project);
+        values.ids = Ids::covering(&values);
+        values
+    }
+
+    fn raw_values(project: Value) -> AppValues {
+        let mut full = json!({
+            "examinees": [],
+            "academicCentres": [],
+            "subjects": [],
+            "vigilants": [],
+            "classrooms": [],
+            "assignation": null,
+            "ids": Ids::default(),
+        });
+        for (key, value) in project.as_object().unwrap() {
+            full[key] = value.clone();
+        }
+        serde_json::from_value(full).unwrap()
+    }
+
+    /// Checks that validation finds the issues without touching the values, and that repairing
+    /// reports the same issues and leaves the expected values, which have no issues
+    fn assert_repairs(values: AppValues, issues: Value, repaired: AppValues) {
+        let before = comparable(&values);
+        assert_eq!(serde_json::to_value(validate(&values)).unwrap(), issues);
+        assert_eq!(comparable(&values), before);
+
+        let mut values = values;
+        assert_eq!(serde_json::to_value(repair(&mut values)).unwrap(), issues);
+        assert_eq!(comparable(&values), comparable(&repaired));
+        assert!(validate(&values).is_empty());
+    }
+
+    #[test]
+    fn sound_values_are_left_alone() {
+        let values = values(json!({
+            "examinees": [examinee(0, "Examinee", &[0])],
+            "subjects": [subject(0)],
+            "courts": [court(0, 1)],
+            "assignation": [exam(0, &[0])],
+        }));
+
+        assert_repairs(values.clone(), json!([]), values);
+    }
+
+    #[test]
+    fn repeated_ids_keep_the_first_entity() {
+        let values = values(json!({
+            "examinees": [
+                examinee(0, "First", &[]),
+                examinee(1, "Other", &[]),
+                examinee(0, "Second", &[]),
+            ],
+        }));
+        let repaired = self::values(json!({
+            "examinees": [examinee(0, "First", &[]), examinee(1, "Other", &[])],
+        }));
+
+        assert_repairs(
+            values,
+            json!([{ "type": "duplicateId", "kind": "examinee", "id": 0 }]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn repeated_court_numbers_keep_the_first_court() {
+        let values = values(json!({ "courts": [court(0, 1), court(1, 2), court(2, 1)] }));
+        let mut repaired = self::values(json!({ "courts": [court(0, 1), court(1, 2)] }));
+        repaired.ids = values.ids.clone();
+
+        assert_repairs(
+            values,
+            json!([{ "type": "duplicateCourtNumber", "number": 1 }]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn references_of_entities_to_missing_entities_are_dropped() {
+        let mut dangling = examinee(0, "Examinee", &[0, 5]);
+        dangling["academicCentreId"] = json!(7);
+        let values = values(json!({
+            "examinees": [dangling, examinee(1, "Other", &[0])],
+            "subjects": [subject(0)],
+        }));
+        let repaired = self::values(json!({
+            "examinees": [examinee(0, "Examinee", &[0]), examinee(1, "Other", &[0])],
+            "subjects": [subject(0)],
+        }));
+
+        assert_repairs(
+            values,
+            json!([
+                {
+                    "type": "danglingReference",
+                    "ownerKind": "examinee",
+                    "ownerId": 0,
+                    "kind": "academicCentre",
+                    "id": 7,
+                },
+                {
+                    "type": "danglingReference",
+                    "ownerKind": "examinee",
+                    "ownerId": 0,
+                    "kind": "subject",
+                    "id": 5,
+                },
+            ]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn references_to_missing_courts_are_dropped() {
+        let mut examinee = examinee(0, "Examinee", &[]);
+        examinee["courtId"] = json!(3);
+        let values = values(json!({
+            "examinees": [examinee],
+            "courts": [court(0, 1)],
+            "classrooms": [{
+                "id": 0,
+                "code": "C0",
+                "locationCode": "L",
+                "totalCapacity": 10,
+                "examCapacity": 10,
+                "priority": 0,
+                "courtLocationId": 0,
+                "kind": "",
+                "notes": [],
+            }],
+        }));
+        let mut repaired = values.clone();
+        repaired.examinees[0].court_id = None;
+
+        assert_repairs(
+            values,
+            json!([{
+                "type": "danglingReference",
+                "ownerKind": "examinee",
+                "ownerId": 0,
+                "kind": "court",
+                "id": 3,
+            }]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn references_of_the_assignment_to_missing_entities_are_dropped() {
+        let values = values(json!({
+            "examinees": [examinee(0, "Examinee", &[0])],
+            "subjects": [subject(0)],
+            "assignation": [exam(0, &[0, 9]), exam(5, &[0])],
+        }));
+        let repaired = self::values(json!({
+            "examinees": [examinee(0, "Examinee", &[0])],
+            "subjects": [subject(0)],
+            "assignation": [exam(0, &[0])],
+        }));
+
+        assert_repairs(
+            values,
+            json!([
+                {
+                    "type": "danglingReference",
+                    "ownerKind": null,
+                    "ownerId": null,
+                    "kind": "examinee",
+                    "id": 9,
+                },
+                {
+                    "type": "danglingReference",
+                    "ownerKind": null,
+                    "ownerId": null,
+                    "kind": "subject",
+                    "id": 5,
+                },
+            ]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn counters_behind_are_moved_past_the_highest_id() {
+        let values = raw_values(json!({
+            "examinees": [examinee(0, "First", &[]), examinee(3, "Last", &[])],
+        }));
+        let repaired = self::values(json!({
+            "examinees": [examinee(0, "First", &[]), examinee(3, "Last", &[])],
+        }));
+
+        assert_repairs(
+            values,
+            json!([{ "type": "counterBehind", "kind": "examinee", "counter": 0, "highest": 3 }]),
+            repaired,
+        );
+    }
+
+    #[test]
+    fn counters_ahead_are_left_alone() {
+        let mut values = values(json!({ "examinees": [examinee(0, "Examinee", &[])] }));
+        *values.ids.counter_mut(EntityKind::Examinee) = EntityId(10);
+
+        assert_repairs(values.clone(), json!([]), values);
+    }
+
+    #[test]
+    fn stored_exam_dates_that_could_not_be_read_are_reported() {
+        let stored = json!({
+            "subjects": [
+                { "id": 0, "examDate": "tomorrow", "examDuration": null },
+                { "id": 1, "examDate": "2024-06-04T09:00:00+02:00", "examDuration": 7 },
+            ],
+        });
+        let mut values = values(json!({ "subjects": [subject(0), subject(1)] }));
+        values.subjects[1].exam_date =
+            chrono::DateTime::parse_from_rfc3339("2024-06-04T09:00:00+02:00").ok();
+
+        let issues = unreadable_values(stored.as_object().unwrap(), &values);
+
+        assert_eq!(
+            serde_json::to_value(issues).unwrap(),
+            json!([
+                {
+                    "type": "unreadableValue",
+                    "kind": "subject",
+                    "id": 0,
+                    "field": "examDate",
+                    "value": "tomorrow",
+                },
+                {
+                    "type": "unreadableValue",
+                    "kind": "subject",
+                    "id": 1,
+                    "field": "examDuration",
+                    "value": "7",
+                },
+            ])
+        );
+    }
+}
@@ -1,8 +1,9 @@
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
 use ts_rs::TS;
 
-use super::EntityId;
+use super::{subject::exam_date, Entity, EntityId, EntityKind};
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
@@ -26,5 +27,107 @@ pub struct Vigilant {
     pub role: VigilantRole,
     pub specialties_ids: Vec<EntityId>,
     pub academic_centre_id: Option<EntityId>,
-    pub main_court: i16,
+    /// Court the vigilant belongs to
+    pub main_court_id: Option<EntityId>,
+    /// When the vigilant can attend exams. Without any window the vigilant is always available
+    #[serde(default)]
+    pub availability: Vec<AvailabilityWindow>,
+    /// Most exam slots the vigilant can be assigned to
+    #[serde(default)]
+    pub max_duties: Option<u16>,
+    /// NIFs of the examinees the vigilant declared as relatives
+    #[serde(default)]
+    pub relatives_nifs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, TS)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct AvailabilityWindow {
+    #[ts(type = "string")]
+    pub start: DateTime<FixedOffset>,
+    #[ts(type = "string")]
+    pub end: DateTime<FixedOffset>,
+}
+
+/// The part of an availability window that could not be read
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WindowPart {
+    Start,
+    /// The end is unreadable or is not after the start
+    End,
+}
+
+impl AvailabilityWindow {
+    /// Reads a window from the texts of its start and end, in the formats exam dates are read
+    pub fn parse(start: &str, end: &str) -> Result<Self, WindowPart> {
+        let start = exam_date::parse(start.trim()).ok_or(WindowPart::Start)?;
+        let end = exam_date::parse(end.trim())
+            .filter(|end| start < *end)
+            .ok_or(WindowPart::End)?;
+        Ok(AvailabilityWindow { start, end })
+    }
+}
+
+impl Vigilant {
+    /// If the vigilant can attend the whole time between the given instants
+    pub fn is_available(&self, start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> bool {
+        self.availability.is_empty()
+            || self
+                .availability
+                .iter()
+                .any(|window| window.start <= start && end <= window.end)
+    }
+}
+
+impl Entity for Vigilant {
+    const KIND: EntityKind = EntityKind::Vigilant;
+
+    fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut EntityId {
+        &mut self.id
+    }
+
+    fn label(&self) -> String {
+        format!("{}, {}", self.surenames, self.name)
+    }
+
+    fn for_each_reference_mut(&mut self, visit: &mut dyn FnMut(EntityKind, &mut EntityId)) {
+        if let Some(main_court_id) = &mut self.main_court_id {
+            visit(EntityKind::Court, main_court_id);
+        }
+        if let Some(academic_centre_id) = &mut self.academic_centre_id {
+            visit(EntityKind::AcademicCentre, academic_centre_id);
+        }
+        for specialty_id in &mut self.specialties_ids {
+            visit(EntityKind::Subject, specialty_id);
+        }
+    }
+
+    fn retain_references(&mut self, keep: &mut dyn FnMut(EntityKind, &EntityId) -> bool) {
+        self.main_court_id = self
+            .main_court_id
+            .take()
+            .filter(|main_court_id| keep(EntityKind::Court, main_court_id));
+        self.academic_centre_id = self
+            .academic_centre_id
+            .take()
+            .filter(|academic_centre_id| keep(EntityKind::AcademicCentre, academic_centre_id));
+        self.specialties_ids
+            .retain(|specialty_id| keep(EntityKind::Subject, specialty_id));
+    }
+}
+
+/// Availability of a vigilant read from a sheet, the vigilant is found by its name
+#[skip_serializing_none]
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ImportedAvailability {
+    pub name: String,
+    pub surenames: String,
+    pub availability: Vec<AvailabilityWindow>,
+    pub max_duties: Option<u16>,
 }
@@ -0,0 +1,89 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::Hasher,
+    io::ErrorKind,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+/// Fingerprints of the files as they were when last loaded or saved, by path
+pub type OpenedFiles = Arc<Mutex<HashMap<String, FileFingerprint>>>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FileFingerprint {
+    modified: Option<SystemTime>,
+    length: u64,
+    hash: u64,
+}
+
+impl FileFingerprint {
+    /// Fingerprint of the file on disk, or `None` if it does not exist
+    pub fn of(file: &str) -> std::io::Result<Option<Self>> {
+        let contents = match fs::read(file) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let modified = fs::metadata(file)?.modified().ok();
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&contents);
+        Ok(Some(FileFingerprint {
+            modified,
+            length: contents.len() as u64,
+            hash: hasher.finish(),
+        }))
+    }
+}
+
+/// Remembers how the file is right now, so later changes made by others can be noticed
+pub fn record_fingerprint(opened_files: &OpenedFiles, file: &str) {
+    let fingerprint = FileFingerprint::of(file).ok().flatten();
+    let mut opened_files = opened_files
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match fingerprint {
+        Some(fingerprint) => opened_files.insert(file.to_owned(), fingerprint),
+        None => opened_files.remove(file),
+    };
+}
+
+/// If the file was changed on disk since it was recorded. Files that were never recorded or that
+/// no longer exist are not considered modified
+pub fn was_modified(opened_files: &OpenedFiles, file: &str) -> bool {
+    let recorded = opened_files
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(file)
+        .cloned();
+    match (recorded, FileFingerprint::of(file)) {
+        (Some(recorded), Ok(Some(current))) => recorded != current,
+        (Some(_), Err(_)) => true,
+        _ => false,
+    }
+}
+
+/// First free path next to the file to save a copy of it
+pub fn copy_path(file: &str) -> String {
+    let path = Path::new(file);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|copy| {
+            let name = match copy {
+                1 => format!("{stem} (copy){extension}"),
+                _ => format!("{stem} (copy {copy}){extension}"),
+            };
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .map(|candidate| candidate.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,260 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::Path,
+    process::{self, Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::IoErrorDetails;
+
+/// A lock that has not been refreshed for this long is considered abandoned
+const STALE_LOCK_SECONDS: u64 = 12 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct FileLock {
+    pub owner: String,
+    pub host: String,
+    pub pid: u32,
+    /// Seconds since the unix epoch when the lock was taken or last refreshed
+    #[ts(type = "number")]
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum FileLockError {
+    /// Someone else is working on the file
+    Locked {
+        lock: FileLock,
+    },
+    Io {
+        error: IoErrorDetails,
+    },
+}
+
+impl FileLock {
+    fn current() -> Self {
+        FileLock {
+            owner: current_user(),
+            host: current_host(),
+            pid: process::id(),
+            timestamp: now(),
+        }
+    }
+
+    fn is_ours(&self) -> bool {
+        self.pid == process::id() && self.host == current_host() && self.owner == current_user()
+    }
+
+    /// Locks not refreshed for long, or left behind by a process of this machine that ended
+    fn is_stale(&self) -> bool {
+        now().saturating_sub(self.timestamp) > STALE_LOCK_SECONDS
+            || (self.host == current_host() && !is_running(self.pid))
+    }
+}
+
+/// Fails if the file is locked by someone else whose lock is still alive
+pub fn check_lock(file: &str) -> Result<(), FileLockError> {
+    match read_lock(file)? {
+        Some(lock) if !lock.is_ours() && !lock.is_stale() => Err(FileLockError::Locked { lock }),
+        _ => Ok(()),
+    }
+}
+
+/// Takes the lock of the file, or refreshes it if it is already ours. The lock file is only
+/// created if there is none, so two apps taking the lock at once can not both get it
+pub fn acquire_lock(file: &str) -> Result<(), FileLockError> {
+    // An abandoned lock is removed and the creation tried once more
+    for _ in 0..2 {
+        match create_lock(file) {
+            Ok(()) => return Ok(()),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(io_error(&err)),
+        }
+        match read_lock(file)? {
+            Some(lock) if lock.is_ours() => return write_lock(file),
+            Some(lock) if !lock.is_stale() => return Err(FileLockError::Locked { lock }),
+            _ => remove_lock(file)?,
+        }
+    }
+    Err(io_error(&io::Error::from(ErrorKind::AlreadyExists)))
+}
+
+/// Takes the lock of the file even if someone else is holding it
+pub fn take_over_lock(file: &str) -> Result<Option<FileLock>, FileLockError> {
+    let previous = read_lock(file)?;
+    write_lock(file)?;
+    Ok(previous)
+}
+
+/// Removes the lock of the file if it is ours
+pub fn release_lock(file: &str) -> Result<(), FileLockError> {
+    match read_lock(file)? {
+        Some(lock) if lock.is_ours() => remove_lock(file),
+        _ => Ok(()),
+    }
+}
+
+fn read_lock(file: &str) -> Result<Option<FileLock>, FileLockError> {
+    match fs::read(lock_path(file)) {
+        // an unreadable lock can not be attributed to anyone, so it does not block
+        Ok(contents) => Ok(serde_json::from_slice(&contents).ok()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(io_error(&err)),
+    }
+}
+
+fn write_lock(file: &str) -> Result<(), FileLockError> {
+    fs::write(lock_path(file), lock_contents()).map_err(|err| io_error(&err))
+}
+
+/// Writes our lock only if there is no lock file
+fn create_lock(file: &str) -> io::Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(lock_path(file))?
+        .write_all(&lock_contents())
+}
+
+fn remove_lock(file: &str) -> Result<(), FileLockError> {
+    match fs::remove_file(lock_path(file)) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(io_error(&err)),
+        _ => Ok(()),
+    }
+}
+
+fn lock_contents() -> Vec<u8> {
+    serde_json::to_vec(&FileLock::current()).unwrap_or_default()
+}
+
+fn io_error(err: &io::Error) -> FileLockError {
+    FileLockError::Io {
+        error: IoErrorDetails::from(err),
+    }
+}
+
+fn lock_path(file: &str) -> String {
+    format!("{file}.lock")
+}
+
+/// Name of the user of the operating system running the app
+pub fn current_user() -> String {
+    env::var("USERNAME")
+        .or_else(|_| env::var("USER"))
+        .unwrap_or_default()
+}
+
+fn current_host() -> String {
+    env::var("COMPUTERNAME")
+        .or_else(|_| env::var("HOSTNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname").map(|host| host.trim().to_owned()))
+        .unwrap_or_default()
+}
+
+/// If a process with the id runs on this machine, assumed when it can not be told
+fn is_running(pid: u32) -> bool {
+    // Where the processes are listed as directories there is no need to run anything
+    let processes = Path::new("/proc");
+    if processes.join("self").exists() {
+        return processes.join(pid.to_string()).exists();
+    }
+
+    let pid = pid.to_string();
+    let command = if cfg!(windows) {
+        let mut command = Command::new("tasklist");
+        command.args(["/FI", &format!("PID eq {pid}"), "/NH"]);
+        command
+    } else {
+        let mut command = Command::new("ps");
+        command.args(["-p", &pid]);
+        command
+    };
+    probe_running(command, &pid)
+}
+
+/// Runs the command that looks for the process. Only a command that ran fine and did not find the
+/// process tells it is not running, a command that is missing or fails tells nothing
+fn probe_running(mut command: Command, pid: &str) -> bool {
+    let Ok(output) = command.stdin(Stdio::null()).output() else {
+        return true;
+    };
+    if cfg!(windows) {
+        // tasklist succeeds and prints a notice when nothing matches
+        !output.status.success() || String::from_utf8_lossy(&output.stdout).contains(pid)
+    } else {
+        // ps exits with 1 when nothing matches, and also when it does not understand the arguments
+        output.status.success() || output.status.code() != Some(1) || !output.stderr.is_empty()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(host: String, pid: u32, age: u64) -> FileLock {
+        FileLock {
+            owner: current_user(),
+            host,
+            pid,
+            timestamp: now() - age,
+        }
+    }
+
+    /// Id of a process of this machine that already ended
+    fn ended_pid() -> u32 {
+        let mut child = Command::new(env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        child.wait().unwrap();
+        child.id()
+    }
+
+    #[test]
+    fn locks_not_refreshed_for_half_a_day_are_stale() {
+        let pid = process::id();
+
+        assert!(!lock(current_host(), pid, STALE_LOCK_SECONDS).is_stale());
+        assert!(lock(current_host(), pid, STALE_LOCK_SECONDS + 1).is_stale());
+        assert!(!lock("elsewhere".to_owned(), pid, STALE_LOCK_SECONDS).is_stale());
+        assert!(lock("elsewhere".to_owned(), pid, STALE_LOCK_SECONDS + 1).is_stale());
+    }
+
+    #[test]
+    fn locks_of_ended_processes_of_this_machine_are_stale() {
+        assert!(!lock(current_host(), process::id(), 0).is_stale());
+        assert!(lock(current_host(), ended_pid(), 0).is_stale());
+    }
+
+    #[test]
+    fn locks_of_other_machines_are_not_checked_for_their_process() {
+        assert!(!lock("elsewhere".to_owned(), ended_pid(), 0).is_stale());
+    }
+
+    #[test]
+    fn processes_are_assumed_running_when_the_probe_fails() {
+        let pid = ended_pid().to_string();
+
+        assert!(probe_running(Command::new("no-such-probe-command"), &pid));
+        let mut failing = Command::new(env::current_exe().unwrap());
+        failing.arg("--no-such-option");
+        assert!(probe_running(failing, &pid));
+    }
+}
@@ -1,12 +1,179 @@
+use std::io::{Read, Write};
+
 use aead::{consts::U32, generic_array::GenericArray};
 use argon2::{Config, Error};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{error::Category, Map, Value};
+use ts_rs::TS;
+
+use crate::models::{
+    court::migrate_court_numbers,
+    history::HistoryEntry,
+    validation::{unreadable_values, IntegrityIssue},
+    AppValues,
+};
 
+pub mod fingerprint;
+pub mod lock;
 pub mod read;
+pub mod verify;
 pub mod write;
 
 pub(self) const FILE_HEADER: &[u8; 4] = b"MEPv";
 pub(self) const PASSWORD_CHECK_LENGTH: u32 = 32;
+/// Size of every encrypted segment of the data stream, authentication tag included
+pub(self) const SEGMENT_SIZE: usize = 128;
+
+/// Contents of a project file: the values and the changes that led to them. Files saved before
+/// the history existed have none
+#[derive(Serialize)]
+pub struct StoredProject {
+    #[serde(flatten)]
+    pub values: AppValues,
+    pub history: Vec<HistoryEntry>,
+    /// Values that were in the file but could not be read, so they were dropped
+    #[serde(skip)]
+    pub unreadable: Vec<IntegrityIssue>,
+    /// Numbers of the courts created for the court numbers of files made before courts were
+    /// entities
+    #[serde(skip)]
+    pub created_courts: Vec<i16>,
+}
+
+#[derive(Deserialize)]
+struct StoredHistory {
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+}
+
+impl StoredProject {
+    /// Reads the values and the history out of the object stored in the file, each on its own so
+    /// errors keep the path of the field that failed. Court numbers of older files are migrated
+    /// first, naming the created courts after `court_name`. Fails with the path of the error
+    fn from_object(
+        mut object: Map<String, Value>,
+        court_name: &str,
+    ) -> Result<Self, (String, serde_json::Error)> {
+        let created_courts = migrate_court_numbers(&mut object, court_name);
+        let history = object
+            .remove("history")
+            .map(|history| Value::Object(Map::from_iter([("history".to_owned(), history)])))
+            .unwrap_or_else(|| Value::Object(Map::new()));
+        let schedules: Map<String, Value> = ["subjects", "examSessions"]
+            .into_iter()
+            .filter_map(|key| Some((key.to_owned(), object.get(key)?.clone())))
+            .collect();
+        let values: AppValues = serde_path_to_error::deserialize(Value::Object(object))
+            .map_err(|err| (err.path().to_string(), err.into_inner()))?;
+        let history: StoredHistory = serde_path_to_error::deserialize(history)
+            .map_err(|err| (err.path().to_string(), err.into_inner()))?;
+        Ok(StoredProject {
+            unreadable: unreadable_values(&schedules, &values),
+            values,
+            history: history.history,
+            created_courts,
+        })
+    }
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct IoErrorDetails {
+    /// Kind of the error as reported by the standard library
+    pub kind: String,
+    pub message: String,
+    /// Error code of the operating system, if it was the one that failed
+    pub os_code: Option<i32>,
+}
+
+impl From<&std::io::Error> for IoErrorDetails {
+    fn from(error: &std::io::Error) -> Self {
+        IoErrorDetails {
+            kind: format!("{:?}", error.kind()),
+            message: error.to_string(),
+            os_code: error.raw_os_error(),
+        }
+    }
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum JsonErrorCategory {
+    Io,
+    Syntax,
+    Data,
+    Eof,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct JsonErrorDetails {
+    pub category: JsonErrorCategory,
+    /// Path of the field that failed, like `examinees[3].subjectsIds`
+    pub path: String,
+    /// Line of the data where the error happened, if it is tied to a position of the data
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Position in the uncompressed data where the error happened, if it is tied to one
+    #[ts(type = "number | null")]
+    pub offset: Option<u64>,
+    pub message: String,
+}
+
+impl JsonErrorDetails {
+    pub(self) fn new(path: String, error: serde_json::Error, offset: Option<u64>) -> Self {
+        JsonErrorDetails {
+            category: match error.classify() {
+                Category::Io => JsonErrorCategory::Io,
+                Category::Syntax => JsonErrorCategory::Syntax,
+                Category::Data => JsonErrorCategory::Data,
+                Category::Eof => JsonErrorCategory::Eof,
+            },
+            path,
+            // serde_json reports line 0 for errors that are not tied to a position
+            line: (error.line() > 0).then(|| error.line()),
+            column: (error.line() > 0).then(|| error.column()),
+            offset,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Keeps track of how many bytes went through a reader or writer
+pub(self) struct ByteCounter<T> {
+    inner: T,
+    count: u64,
+}
+
+impl<T> ByteCounter<T> {
+    fn new(inner: T) -> Self {
+        ByteCounter { inner, count: 0 }
+    }
+}
+
+impl<R: Read> Read for ByteCounter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+impl<W: Write> Write for ByteCounter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
 
 #[derive(Default)]
 pub(self) struct SaltAndNonce {
@@ -1,14 +1,22 @@
-use std::{fs::OpenOptions, io::Read};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek},
+};
 
+use aead::{consts::U32, generic_array::GenericArray};
 use aead_io::{ArrayBuffer, DecryptBE32BufReader};
 use aes_gcm_siv::Aes256GcmSiv;
 use flate2::read::ZlibDecoder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::models::AppValues;
+use crate::models::{court::DEFAULT_COURT_NAME, validation::IntegrityIssue, AppValues};
 
-use super::{create_password_check, derive_key, SaltAndNonce, FILE_HEADER, PASSWORD_CHECK_LENGTH};
+use super::{
+    create_password_check, derive_key, lock::FileLockError, ByteCounter, IoErrorDetails,
+    JsonErrorDetails, SaltAndNonce, StoredProject, FILE_HEADER, PASSWORD_CHECK_LENGTH,
+    SEGMENT_SIZE,
+};
 
 #[derive(Serialize, TS)]
 #[serde(rename_all = "camelCase", tag = "type")]
@@ -42,16 +50,97 @@ pub enum ReadFromFileOpenFileError {
 #[serde(rename_all = "camelCase", tag = "type")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub enum ReadFromFileError {
-    Reading { part: ReadFromFileReadError },
-    Invalid { part: ReadFromFileInvalidError },
-    KeyDerivation,
+    Reading {
+        part: ReadFromFileReadError,
+        #[ts(type = "number")]
+        offset: u64,
+        error: IoErrorDetails,
+    },
+    Invalid {
+        part: ReadFromFileInvalidError,
+    },
+    KeyDerivation {
+        message: String,
+    },
     CreateCipher,
-    OpenFile { case: ReadFromFileOpenFileError },
-    Serialization,
-    PasswordCheck,
+    OpenFile {
+        case: ReadFromFileOpenFileError,
+        error: IoErrorDetails,
+    },
+    Serialization {
+        error: JsonErrorDetails,
+    },
+    PasswordCheck {
+        error: Option<IoErrorDetails>,
+    },
+    Lock {
+        case: FileLockError,
+    },
+    /// The values reference entities that do not exist or reuse ids, and were not repaired
+    Integrity {
+        issues: Vec<IntegrityIssue>,
+    },
+}
+
+/// A project file together with the password needed to open it
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct ProjectFile {
+    pub file: String,
+    pub password: String,
 }
 
 pub fn load_from_file(file: String, password: String) -> Result<AppValues, ReadFromFileError> {
+    load_project(file, password, DEFAULT_COURT_NAME).map(|project| project.values)
+}
+
+/// Loads the values of the file together with its history. Courts created for the court numbers
+/// of older files are named after `court_name`, where `{number}` is replaced with their number
+pub fn load_project(
+    file: String,
+    password: String,
+    court_name: &str,
+) -> Result<StoredProject, ReadFromFileError> {
+    let (file, data_key) = open_encrypted(file, &password)?;
+
+    let decriptor = DecryptBE32BufReader::<Aes256GcmSiv, _, _>::new(
+        &data_key,
+        ArrayBuffer::<SEGMENT_SIZE>::new(),
+        file,
+    )
+    .map_err(|_| ReadFromFileError::CreateCipher)?;
+
+    let mut decompressor = ByteCounter::new(ZlibDecoder::new(decriptor));
+
+    let mut deserializer = serde_json::Deserializer::from_reader(&mut decompressor);
+    let object = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|err| (err.path().to_string(), err.into_inner()))
+        .and_then(|object| {
+            deserializer
+                .end()
+                .map(|_| object)
+                .map_err(|err| (String::new(), err))
+        })
+        .map_err(|(path, err)| ReadFromFileError::Serialization {
+            error: JsonErrorDetails::new(path, err, Some(decompressor.count)),
+        })?;
+
+    // The values are read once the whole file has been parsed, so their errors only have a path
+    let project = StoredProject::from_object(object, court_name).map_err(|(path, err)| {
+        ReadFromFileError::Serialization {
+            error: JsonErrorDetails::new(path, err, None),
+        }
+    })?;
+
+    Ok(project)
+}
+
+/// Opens the file, checks its header and password and derives the key of the data stream.
+/// The returned file is positioned at the start of the encrypted data
+pub(super) fn open_encrypted(
+    file: String,
+    password: &String,
+) -> Result<(File, GenericArray<u8, U32>), ReadFromFileError> {
     let mut file =
         OpenOptions::new()
             .read(true)
@@ -62,26 +151,21 @@ pub fn load_from_file(file: String, password: String) -> Result<AppValues, ReadF
                     std::io::ErrorKind::PermissionDenied => ReadFromFileOpenFileError::Permissions,
                     _ => ReadFromFileOpenFileError::Other,
                 },
+                error: IoErrorDetails::from(&err),
             })?;
 
     verify_header_and_version(&mut file)?;
     let data_salt_and_nonce = extract_salt_and_nonce(&mut file)?;
 
-    verify_password(&mut file, &password)?;
-
-    let data_key = derive_key(&password, &data_salt_and_nonce.salt)
-        .map_err(|_| ReadFromFileError::KeyDerivation)?;
+    verify_password(&mut file, password)?;
 
-    let decriptor =
-        DecryptBE32BufReader::<Aes256GcmSiv, _, _>::new(&data_key, ArrayBuffer::<128>::new(), file)
-            .map_err(|_| ReadFromFileError::CreateCipher)?;
+    let data_key = derive_key(password, &data_salt_and_nonce.salt).map_err(|err| {
+        ReadFromFileError::KeyDerivation {
+            message: err.to_string(),
+        }
+    })?;
 
-    let decompressor = ZlibDecoder::new(decriptor);
-
-    let values =
-        serde_json::from_reader(decompressor).map_err(|_| ReadFromFileError::Serialization)?;
-
-    Ok(values)
+    Ok((file, data_key))
 }
 
 fn verify_password(reader: &mut impl Read, password: &String) -> Result<(), ReadFromFileError> {
@@ -90,13 +174,17 @@ fn verify_password(reader: &mut impl Read, password: &String) -> Result<(), Read
 
     reader
         .read_exact(&mut salt)
-        .map_err(|_| ReadFromFileError::PasswordCheck)?;
+        .map_err(|err| ReadFromFileError::PasswordCheck {
+            error: Some(IoErrorDetails::from(&err)),
+        })?;
     reader
         .read_exact(&mut readed)
-        .map_err(|_| ReadFromFileError::PasswordCheck)?;
+        .map_err(|err| ReadFromFileError::PasswordCheck {
+            error: Some(IoErrorDetails::from(&err)),
+        })?;
 
-    let expected =
-        create_password_check(password, &salt).map_err(|_| ReadFromFileError::PasswordCheck)?;
+    let expected = create_password_check(password, &salt)
+        .map_err(|_| ReadFromFileError::PasswordCheck { error: None })?;
 
     if expected != readed.to_vec() {
         return Err(ReadFromFileError::Invalid {
@@ -107,13 +195,9 @@ fn verify_password(reader: &mut impl Read, password: &String) -> Result<(), Read
     Ok(())
 }
 
-fn verify_header_and_version(reader: &mut impl Read) -> Result<(), ReadFromFileError> {
+fn verify_header_and_version(reader: &mut (impl Read + Seek)) -> Result<(), ReadFromFileError> {
     let mut header: [u8; 4] = [0; 4];
-    reader
-        .read_exact(&mut header)
-        .map_err(|_| ReadFromFileError::Reading {
-            part: ReadFromFileReadError::Header,
-        })?;
+    read_part(reader, &mut header, ReadFromFileReadError::Header)?;
     if &header != FILE_HEADER {
         return Err(ReadFromFileError::Invalid {
             part: ReadFromFileInvalidError::Header,
@@ -121,11 +205,7 @@ fn verify_header_and_version(reader: &mut impl Read) -> Result<(), ReadFromFileE
     }
 
     let mut version: [u8; 1] = [1; 1];
-    reader
-        .read_exact(&mut version)
-        .map_err(|_| ReadFromFileError::Reading {
-            part: ReadFromFileReadError::Version,
-        })?;
+    read_part(reader, &mut version, ReadFromFileReadError::Version)?;
     if version[0] != 1 {
         return Err(ReadFromFileError::Invalid {
             part: ReadFromFileInvalidError::Version,
@@ -135,20 +215,36 @@ fn verify_header_and_version(reader: &mut impl Read) -> Result<(), ReadFromFileE
     Ok(())
 }
 
-fn extract_salt_and_nonce(reader: &mut impl Read) -> Result<SaltAndNonce, ReadFromFileError> {
+fn extract_salt_and_nonce(
+    reader: &mut (impl Read + Seek),
+) -> Result<SaltAndNonce, ReadFromFileError> {
     let mut salt_and_nonce = SaltAndNonce::default();
 
-    reader
-        .read_exact(&mut salt_and_nonce.salt)
-        .map_err(|_| ReadFromFileError::Reading {
-            part: ReadFromFileReadError::Salt,
-        })?;
-
-    reader
-        .read_exact(&mut salt_and_nonce.nonce)
-        .map_err(|_| ReadFromFileError::Reading {
-            part: ReadFromFileReadError::Nonce,
-        })?;
+    read_part(
+        reader,
+        &mut salt_and_nonce.salt,
+        ReadFromFileReadError::Salt,
+    )?;
+    read_part(
+        reader,
+        &mut salt_and_nonce.nonce,
+        ReadFromFileReadError::Nonce,
+    )?;
 
     Ok(salt_and_nonce)
 }
+
+fn read_part(
+    reader: &mut (impl Read + Seek),
+    buffer: &mut [u8],
+    part: ReadFromFileReadError,
+) -> Result<(), ReadFromFileError> {
+    let offset = reader.stream_position().unwrap_or_default();
+    reader
+        .read_exact(buffer)
+        .map_err(|err| ReadFromFileError::Reading {
+            part,
+            offset,
+            error: IoErrorDetails::from(&err),
+        })
+}
@@ -0,0 +1,452 @@
+use std::io::{Cursor, Read, Seek};
+
+use aead::{
+    consts::{U32, U7},
+    generic_array::GenericArray,
+    stream::{NewStream, StreamBE32, StreamPrimitive},
+    KeyInit,
+};
+use aes_gcm_siv::Aes256GcmSiv;
+use flate2::read::ZlibDecoder;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use crate::models::{
+    court::{migrate_court_numbers, DEFAULT_COURT_NAME},
+    AppValues, Ids,
+};
+
+use super::{
+    read::{open_encrypted, ReadFromFileError},
+    SEGMENT_SIZE,
+};
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub enum SegmentFailure {
+    /// The nonce of the data stream could not be read
+    MissingNonce,
+    /// The segment could not be read from the file
+    Reading {
+        segment: u32,
+        #[ts(type = "number")]
+        offset: u64,
+    },
+    /// The segment was modified or is damaged
+    Authentication {
+        segment: u32,
+        #[ts(type = "number")]
+        offset: u64,
+    },
+    /// The segment is valid but the segments after it are missing
+    Truncated {
+        segment: u32,
+        #[ts(type = "number")]
+        offset: u64,
+    },
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct FileVerification {
+    /// Number of segments that passed the authentication
+    pub authenticated_segments: u32,
+    /// First problem found in the data stream, if any
+    pub failure: Option<SegmentFailure>,
+    /// If the authenticated data could be read as a project
+    pub readable: bool,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/lib/types/generated/")]
+pub struct SalvagedValues {
+    pub values: AppValues,
+    pub verification: FileVerification,
+    /// Entities found in the file that could not be recovered
+    pub discarded_entities: usize,
+}
+
+struct DecryptedSegments {
+    data: Vec<u8>,
+    authenticated: u32,
+    failure: Option<SegmentFailure>,
+}
+
+pub fn verify_file(file: String, password: String) -> Result<FileVerification, ReadFromFileError> {
+    let (mut file, data_key) = open_encrypted(file, &password)?;
+    let segments = decrypt_segments(&mut file, &data_key);
+
+    let readable = segments.failure.is_none()
+        && serde_json::from_reader::<_, AppValues>(ZlibDecoder::new(segments.data.as_slice()))
+            .is_ok();
+
+    Ok(FileVerification {
+        authenticated_segments: segments.authenticated,
+        failure: segments.failure,
+        readable,
+    })
+}
+
+/// Recovers everything that can be recovered from a damaged file: all the authenticated segments
+/// are decompressed as far as possible and every entity that can be read is kept
+pub fn salvage_from_file(
+    file: String,
+    password: String,
+) -> Result<SalvagedValues, ReadFromFileError> {
+    let (mut file, data_key) = open_encrypted(file, &password)?;
+    let segments = decrypt_segments(&mut file, &data_key);
+
+    let json = decompress_partially(&segments.data);
+    if segments.failure.is_none() {
+        if let Ok(values) = serde_json::from_slice::<AppValues>(&json) {
+            return Ok(SalvagedValues {
+                values,
+                verification: FileVerification {
+                    authenticated_segments: segments.authenticated,
+                    failure: None,
+                    readable: true,
+                },
+                discarded_entities: 0,
+            });
+        }
+    }
+
+    let json = String::from_utf8_lossy(&json);
+    let mut root = match serde_json::from_str::<Value>(&close_truncated_json(&json)) {
+        Ok(Value::Object(root)) => root,
+        _ => Map::new(),
+    };
+    migrate_court_numbers(&mut root, DEFAULT_COURT_NAME);
+
+    let mut discarded_entities = 0;
+    let examinees = salvage_entities(&mut root, "examinees", &mut discarded_entities);
+    let academic_centres = salvage_entities(&mut root, "academicCentres", &mut discarded_entities);
+    let subjects = salvage_entities(&mut root, "subjects", &mut discarded_entities);
+    let vigilants = salvage_entities(&mut root, "vigilants", &mut discarded_entities);
+    let classrooms = salvage_entities(&mut root, "classrooms", &mut discarded_entities);
+    let exam_sessions = salvage_entities(&mut root, "examSessions", &mut discarded_entities);
+    let courts = salvage_entities(&mut root, "courts", &mut discarded_entities);
+    let buildings = salvage_entities(&mut root, "buildings", &mut discarded_entities);
+    let assignation = root
+        .remove("assignation")
+        .and_then(|value| serde_json::from_value(value).ok());
+    let ids = root
+        .remove("ids")
+        .and_then(|value| serde_json::from_value(value).ok());
+
+    let mut values = AppValues {
+        examinees,
+        academic_centres,
+        subjects,
+        vigilants,
+        classrooms,
+        exam_sessions,
+        courts,
+        buildings,
+        assignation,
+        ids: Ids::default(),
+    };
+    values.ids = ids.unwrap_or_else(|| Ids::covering(&values));
+
+    Ok(SalvagedValues {
+        values,
+        verification: FileVerification {
+            authenticated_segments: segments.authenticated,
+            failure: segments.failure,
+            readable: false,
+        },
+        discarded_entities,
+    })
+}
+
+/// Decrypts the data stream segment by segment, stopping at the first one that fails
+fn decrypt_segments(
+    reader: &mut (impl Read + Seek),
+    key: &GenericArray<u8, U32>,
+) -> DecryptedSegments {
+    let mut result = DecryptedSegments {
+        data: Vec::new(),
+        authenticated: 0,
+        failure: None,
+    };
+
+    let mut nonce = GenericArray::<u8, U7>::default();
+    if reader.read_exact(&mut nonce).is_err() {
+        result.failure = Some(SegmentFailure::MissingNonce);
+        return result;
+    }
+    let stream = StreamBE32::from_aead(Aes256GcmSiv::new(key), &nonce);
+
+    let mut offset = reader.stream_position().unwrap_or_default();
+    let mut current = match read_segment(reader) {
+        Ok(segment) => segment,
+        Err(_) => {
+            result.failure = Some(SegmentFailure::Reading { segment: 0, offset });
+            return result;
+        }
+    };
+
+    loop {
+        let segment = result.authenticated;
+        // the last segment is only known once there is nothing after it
+        let next = match read_segment(reader) {
+            Ok(next) => next,
+            Err(_) => {
+                result.failure = Some(SegmentFailure::Reading {
+                    segment: segment + 1,
+                    offset: offset + current.len() as u64,
+                });
+                return result;
+            }
+        };
+        let last = next.is_empty();
+
+        let mut buffer = current.clone();
+        if stream
+            .decrypt_in_place(segment, last, b"", &mut buffer)
+            .is_err()
+        {
+            // a segment that is only valid as an intermediate one means the rest of the file is gone
+            let mut buffer = current.clone();
+            result.failure = Some(
+                if last
+                    && stream
+                        .decrypt_in_place(segment, false, b"", &mut buffer)
+                        .is_ok()
+                {
+                    result.data.extend_from_slice(&buffer);
+                    result.authenticated += 1;
+                    SegmentFailure::Truncated { segment, offset }
+                } else {
+                    SegmentFailure::Authentication { segment, offset }
+                },
+            );
+            return result;
+        }
+
+        result.data.extend_from_slice(&buffer);
+        result.authenticated += 1;
+        if last {
+            return result;
+        }
+        offset += current.len() as u64;
+        current = next;
+    }
+}
+
+/// Reads a whole segment, which is shorter only at the end of the file
+fn read_segment(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut segment = Vec::with_capacity(SEGMENT_SIZE);
+    reader.take(SEGMENT_SIZE as u64).read_to_end(&mut segment)?;
+    Ok(segment)
+}
+
+/// Decompresses until the end of the data or until it is not possible to continue
+fn decompress_partially(data: &[u8]) -> Vec<u8> {
+    let mut decompressor = ZlibDecoder::new(Cursor::new(data));
+    let mut decompressed = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        match decompressor.read(&mut chunk) {
+            Ok(0) | Err(_) => return decompressed,
+            Ok(read) => decompressed.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+/// Cuts a truncated json document at the last point where it was structurally complete and closes
+/// every object and array left open
+fn close_truncated_json(json: &str) -> String {
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut cut: Option<(usize, Vec<char>)> = None;
+
+    for (index, character) in json.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match character {
+            '"' => in_string = true,
+            '{' | '[' => {
+                open.push(character);
+                cut = Some((index + 1, open.clone()));
+            }
+            '}' | ']' => {
+                open.pop();
+                cut = Some((index + 1, open.clone()));
+            }
+            ',' => cut = Some((index, open.clone())),
+            _ => {}
+        }
+    }
+
+    let Some((position, open)) = cut else {
+        return String::new();
+    };
+    let mut closed = json[..position].to_owned();
+    closed.extend(open.iter().rev().map(|opened| match opened {
+        '{' => '}',
+        _ => ']',
+    }));
+    closed
+}
+
+/// Takes every entity of the list that can be read, counting the ones that can not
+fn salvage_entities<T: DeserializeOwned>(
+    root: &mut Map<String, Value>,
+    key: &str,
+    discarded: &mut usize,
+) -> Vec<T> {
+    let Some(Value::Array(values)) = root.remove(key) else {
+        return Vec::new();
+    };
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let entity = serde_json::from_value(value).ok();
+            if entity.is_none() {
+                *discarded += 1;
+            }
+            entity
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::examinee::Examinee;
+
+    fn examinee(id: i32) -> Value {
+        json!({
+            "id": id,
+            "nif": format!("{id:08}X"),
+            "name": format!("Examinee {id}"),
+            "surenames": "Surenames",
+            "origin": "Origin",
+            "courtId": null,
+            "academicCentreId": null,
+            "subjectsIds": [],
+        })
+    }
+
+    fn salvage_examinees(json: &str) -> (Vec<Examinee>, usize) {
+        let mut root = match serde_json::from_str(&close_truncated_json(json)).unwrap() {
+            Value::Object(root) => root,
+            _ => panic!("the closed document is not an object"),
+        };
+        let mut discarded = 0;
+        let examinees = salvage_entities(&mut root, "examinees", &mut discarded);
+        (examinees, discarded)
+    }
+
+    #[test]
+    fn complete_documents_are_kept() {
+        let json = r#"{"a":[1,{"b":"c"}],"d":{}}"#;
+
+        assert_eq!(close_truncated_json(json), json);
+    }
+
+    #[test]
+    fn documents_without_structure_are_dropped() {
+        assert_eq!(close_truncated_json(""), "");
+        assert_eq!(close_truncated_json(r#""abc"#), "");
+    }
+
+    #[test]
+    fn truncation_in_a_string_drops_the_unfinished_member() {
+        assert_eq!(
+            close_truncated_json(r#"{"a":"b","c":"unfini"#),
+            r#"{"a":"b"}"#
+        );
+        assert_eq!(close_truncated_json(r#"{"a":"b","unfini"#), r#"{"a":"b"}"#);
+    }
+
+    #[test]
+    fn truncation_in_an_object_closes_it() {
+        assert_eq!(close_truncated_json(r#"{"a":1,"b":2"#), r#"{"a":1}"#);
+        assert_eq!(
+            close_truncated_json(r#"{"a":{"b":1,"c":"#),
+            r#"{"a":{"b":1}}"#
+        );
+        assert_eq!(close_truncated_json(r#"{"a":{"#), r#"{"a":{}}"#);
+    }
+
+    #[test]
+    fn truncation_in_an_array_closes_it() {
+        assert_eq!(close_truncated_json("[1,2,3"), "[1,2]");
+        assert_eq!(close_truncated_json(r#"{"a":[[1],[2"#), r#"{"a":[[1],[]]}"#);
+        assert_eq!(close_truncated_json(r#"{"a":["#), r#"{"a":[]}"#);
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_strings() {
+        assert_eq!(
+            close_truncated_json(r#"{"a":"b","c":"\"},{\""#),
+            r#"{"a":"b"}"#
+        );
+        assert_eq!(close_truncated_json(r#"{"a":"\\","b":"#), r#"{"a":"\\"}"#);
+    }
+
+    #[test]
+    fn brackets_in_strings_are_not_structure() {
+        assert_eq!(close_truncated_json(r#"{"a":"[{","b"#), r#"{"a":"[{"}"#);
+    }
+
+    #[test]
+    fn entities_cut_after_the_last_complete_one_are_not_counted() {
+        let json = json!({ "examinees": [examinee(0), examinee(1)] }).to_string();
+        let between = json.find("},{").unwrap();
+
+        let (examinees, discarded) = salvage_examinees(&json[..between + 2]);
+
+        assert_eq!(examinees.len(), 1);
+        assert_eq!(examinees[0].id, serde_json::from_value(json!(0)).unwrap());
+        assert_eq!(discarded, 0);
+    }
+
+    #[test]
+    fn entities_cut_in_the_middle_are_discarded() {
+        let json = json!({ "examinees": [examinee(0), examinee(1)] }).to_string();
+        let name = json.rfind(r#""name""#).unwrap();
+
+        let (examinees, discarded) = salvage_examinees(&json[..name + 3]);
+
+        assert_eq!(examinees.len(), 1);
+        assert_eq!(discarded, 1);
+    }
+
+    #[test]
+    fn unreadable_entities_are_discarded_and_the_rest_kept() {
+        let mut broken = examinee(1);
+        broken["subjectsIds"] = json!("none");
+        let json = json!({ "examinees": [examinee(0), broken, examinee(2)] }).to_string();
+
+        let (examinees, discarded) = salvage_examinees(&json);
+
+        assert_eq!(examinees.len(), 2);
+        assert_eq!(discarded, 1);
+    }
+
+    #[test]
+    fn missing_lists_are_empty() {
+        let (examinees, discarded) = salvage_examinees(r#"{"subjects":[]}"#);
+
+        assert!(examinees.is_empty());
+        assert_eq!(discarded, 0);
+    }
+}
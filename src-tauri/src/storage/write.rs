@@ -7,9 +7,13 @@ use serde::Serialize;
 use std::{fs::OpenOptions, io::Write};
 use ts_rs::TS;
 
-use crate::models::AppValues;
+use crate::models::validation::IntegrityIssue;
 
-use super::{create_password_check, derive_key, SaltAndNonce, FILE_HEADER, PASSWORD_CHECK_LENGTH};
+use super::{
+    create_password_check, derive_key, lock::FileLockError, ByteCounter, IoErrorDetails,
+    JsonErrorDetails, SaltAndNonce, StoredProject, FILE_HEADER, PASSWORD_CHECK_LENGTH,
+    SEGMENT_SIZE,
+};
 
 #[derive(Serialize, TS)]
 #[serde(rename_all = "camelCase", tag = "type")]
@@ -33,22 +37,49 @@ pub enum SaveToFileOpenFileError {
 #[serde(rename_all = "camelCase", tag = "type")]
 #[ts(export, export_to = "../../src/lib/types/generated/")]
 pub enum SaveToFileError {
-    Writing { part: SaveToFileWriteError },
-    KeyDerivation,
+    Writing {
+        part: SaveToFileWriteError,
+        error: IoErrorDetails,
+    },
+    KeyDerivation {
+        message: String,
+    },
     CreateCipher,
-    OpenFile { case: SaveToFileOpenFileError },
-    Serialization,
-    PasswordCheck,
+    OpenFile {
+        case: SaveToFileOpenFileError,
+        error: IoErrorDetails,
+    },
+    Serialization {
+        error: JsonErrorDetails,
+    },
+    PasswordCheck {
+        error: Option<IoErrorDetails>,
+    },
+    Lock {
+        case: FileLockError,
+    },
+    /// The values reference entities that do not exist or reuse ids, and were not repaired
+    Integrity {
+        issues: Vec<IntegrityIssue>,
+    },
+    /// The file was changed on disk since it was opened
+    #[serde(rename_all = "camelCase")]
+    ModifiedExternally {
+        copy_path: String,
+    },
 }
 
 pub fn save_to_file(
-    values: AppValues,
+    project: &StoredProject,
     file: String,
     password: String,
 ) -> Result<(), SaveToFileError> {
     let data_salt_and_nonce = SaltAndNonce::random();
-    let key = derive_key(&password, &data_salt_and_nonce.salt)
-        .map_err(|_| SaveToFileError::KeyDerivation)?;
+    let key = derive_key(&password, &data_salt_and_nonce.salt).map_err(|err| {
+        SaveToFileError::KeyDerivation {
+            message: err.to_string(),
+        }
+    })?;
 
     let mut file = OpenOptions::new()
         .write(true)
@@ -60,6 +91,7 @@ pub fn save_to_file(
                 std::io::ErrorKind::PermissionDenied => SaveToFileOpenFileError::Permissions,
                 _ => SaveToFileOpenFileError::Other,
             },
+            error: IoErrorDetails::from(&err),
         })?;
 
     write_header_and_version(&mut file)?;
@@ -70,14 +102,21 @@ pub fn save_to_file(
     let encryptor = EncryptBE32BufWriter::<Aes256GcmSiv, _, _>::new(
         &key,
         &GenericArray::<u8, U7>::clone_from_slice(&data_salt_and_nonce.nonce[5..]),
-        ArrayBuffer::<128>::new(),
+        ArrayBuffer::<SEGMENT_SIZE>::new(),
         file,
     )
     .map_err(|_| SaveToFileError::CreateCipher)?;
 
-    let compressor = ZlibEncoder::new(encryptor, Compression::best());
+    let mut compressor = ByteCounter::new(ZlibEncoder::new(encryptor, Compression::best()));
 
-    serde_json::to_writer(compressor, &values).map_err(|_| SaveToFileError::Serialization)?;
+    serde_path_to_error::serialize(project, &mut serde_json::Serializer::new(&mut compressor))
+        .map_err(|err| SaveToFileError::Serialization {
+            error: JsonErrorDetails::new(
+                err.path().to_string(),
+                err.into_inner(),
+                Some(compressor.count),
+            ),
+        })?;
 
     Ok(())
 }
@@ -86,29 +125,25 @@ fn write_password_check(writer: &mut impl Write, password: &String) -> Result<()
     let mut salt = [0u8; PASSWORD_CHECK_LENGTH as usize];
     rand::thread_rng().fill(&mut salt);
 
-    let check =
-        create_password_check(password, &salt).map_err(|_| SaveToFileError::PasswordCheck)?;
+    let check = create_password_check(password, &salt)
+        .map_err(|_| SaveToFileError::PasswordCheck { error: None })?;
 
     writer
         .write_all(&salt)
-        .map_err(|_| SaveToFileError::PasswordCheck)?;
+        .map_err(|err| SaveToFileError::PasswordCheck {
+            error: Some(IoErrorDetails::from(&err)),
+        })?;
     writer
         .write_all(&check)
-        .map_err(|_| SaveToFileError::PasswordCheck)?;
+        .map_err(|err| SaveToFileError::PasswordCheck {
+            error: Some(IoErrorDetails::from(&err)),
+        })?;
     Ok(())
 }
 
 fn write_header_and_version(writer: &mut impl Write) -> Result<(), SaveToFileError> {
-    writer
-        .write_all(FILE_HEADER)
-        .map_err(|_| SaveToFileError::Writing {
-            part: SaveToFileWriteError::Header,
-        })?;
-    writer
-        .write_all(&[1][..])
-        .map_err(|_| SaveToFileError::Writing {
-            part: SaveToFileWriteError::Version,
-        })?;
+    write_part(writer, FILE_HEADER, SaveToFileWriteError::Header)?;
+    write_part(writer, &[1][..], SaveToFileWriteError::Version)?;
     Ok(())
 }
 
@@ -116,16 +151,20 @@ fn write_salt_and_nonce(
     writer: &mut impl Write,
     salt_and_nonce: &SaltAndNonce,
 ) -> Result<(), SaveToFileError> {
-    writer
-        .write_all(&salt_and_nonce.salt)
-        .map_err(|_| SaveToFileError::Writing {
-            part: SaveToFileWriteError::Salt,
-        })?;
+    write_part(writer, &salt_and_nonce.salt, SaveToFileWriteError::Salt)?;
+    write_part(writer, &salt_and_nonce.nonce, SaveToFileWriteError::Nonce)?;
+    Ok(())
+}
 
+fn write_part(
+    writer: &mut impl Write,
+    buffer: &[u8],
+    part: SaveToFileWriteError,
+) -> Result<(), SaveToFileError> {
     writer
-        .write_all(&salt_and_nonce.nonce)
-        .map_err(|_| SaveToFileError::Writing {
-            part: SaveToFileWriteError::Nonce,
-        })?;
-    Ok(())
+        .write_all(buffer)
+        .map_err(|err| SaveToFileError::Writing {
+            part,
+            error: IoErrorDetails::from(&err),
+        })
 }
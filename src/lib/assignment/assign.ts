@@ -4,42 +4,195 @@ import type { Examinee } from '$lib/models/examinees';
 import { Subject } from '$lib/models/subjects';
 import type { Vigilant } from '$lib/models/vigilant';
 import { derived, get, writable } from 'svelte/store';
-import { CollidingExamsConfiguration } from './collidingExamsConfiguration';
-import { ExamsConfiguration } from './examsConfiguration';
-import { IndividualExamConfiguration } from './individualExamConfiguration';
-import { getAllSubjects } from '$lib/services/subjects';
-import { getAllClassrooms } from '$lib/services/classroom';
-import { getAllVigilants } from '$lib/services/vigilant';
-import { getAllExaminees } from '$lib/services/examinees';
+import type { ExamsConfiguration } from './examsConfiguration';
+import { getSubject } from '$lib/services/subjects';
+import { makeSaveValuesObject, useAssignation } from '$lib/services/common';
 import { appState } from '$lib/models/appState';
+import { ipc_invoke, ipc_invoke_result } from '$lib/ipc';
+import type { AssignmentResult } from '$lib/types/generated/AssignmentResult';
+import type { AssignmentCost } from '$lib/types/generated/AssignmentCost';
+import type { OptimisationResult } from '$lib/types/generated/OptimisationResult';
+import type { OptimisationSettings } from '$lib/types/generated/OptimisationSettings';
+import type { Diagnosis } from '$lib/types/generated/Diagnosis';
+import type { AssignmentError as GeneratedAssignmentError } from '$lib/types/generated/AssignmentError';
+import type { EntityId } from '$lib/types/generated/EntityId';
+import type { SeatingResult } from '$lib/types/generated/SeatingResult';
+import type { SeatingError } from '$lib/types/generated/SeatingError';
+import type { SeatingStrategy } from '$lib/types/generated/SeatingStrategy';
+import type { ClassroomCentres } from '$lib/types/generated/ClassroomCentres';
+import type { ExamineeRoomChanges } from '$lib/types/generated/ExamineeRoomChanges';
+import type { ConflictResolution } from '$lib/types/generated/ConflictResolution';
+import type { ScheduleConflict } from '$lib/types/generated/ScheduleConflict';
+import type { SessionExam } from '$lib/types/generated/SessionExam';
+import type { BalanceResult } from '$lib/types/generated/BalanceResult';
+import type { WorkloadError } from '$lib/types/generated/WorkloadError';
+import type { WorkloadReport } from '$lib/types/generated/WorkloadReport';
+import type { InterestRules } from '$lib/types/generated/InterestRules';
+import type { InterestConflict } from '$lib/types/generated/InterestConflict';
+import type { AvailabilityIssue } from '$lib/types/generated/AvailabilityIssue';
+
+/** What was missing in the last assignment made, if some exams could not be assigned */
+export const assignmentDiagnosis = writable<Diagnosis | undefined>();
+
+/** Exams left for the incompatibility session in the last assignment made */
+export const incompatibilitySession = writable<SessionExam[]>([]);
 
 export const assignment = (function () {
 	const { subscribe, set, update } = writable<ExamsConfiguration | undefined>();
 
-	function createNew(): Promise<AssignmentError[]> {
-		return new Promise((resolve) => {
-			appState.lockNavigation(m.making_assignment());
-			const created = orderAndGroupSubjects(get(getAllSubjects()));
-			if (!created.ok) {
-				resolve([created.error]);
-				return;
-			}
-			const configuration: ExamsConfiguration =
-				created.configuration instanceof IndividualExamConfiguration ||
-				created.configuration instanceof CollidingExamsConfiguration
-					? new ExamsConfiguration([created.configuration])
-					: (created.configuration as ExamsConfiguration);
-
-			setTimeout(() => {
-				configuration.addClassrooms(get(getAllClassrooms()));
-				configuration.addVigilants(get(getAllVigilants()));
-				configuration.addExaminees(get(getAllExaminees()));
-				const result = configuration.doAssignment();
-				set(configuration);
-				appState.unlockNavigation();
-				resolve(result);
-			});
+	/**
+	 * The assignment is made by the backend, out of the values in the stores. The examinees
+	 * enrolled in overlapping exams are placed as `conflicts` says, and no vigilant watches an
+	 * examinee `interest` keeps it from watching. With a `session` only its exams are assigned
+	 */
+	function createNew(
+		conflicts: ConflictResolution = { type: 'sitBoth' },
+		interest: Partial<InterestRules> = {},
+		session: EntityId | null = null
+	): Promise<AssignmentError[]> {
+		return make('make_assignment', conflicts, interest, session);
+	}
+
+	/**
+	 * Like `createNew`, but what is locked in the current assignment is kept and only the rest is
+	 * distributed again
+	 */
+	function reassign(
+		conflicts: ConflictResolution = { type: 'sitBoth' },
+		interest: Partial<InterestRules> = {},
+		session: EntityId | null = null
+	): Promise<AssignmentError[]> {
+		return make('reassign_assignment', conflicts, interest, session);
+	}
+
+	async function make(
+		command: 'make_assignment' | 'reassign_assignment',
+		conflicts: ConflictResolution,
+		interest: Partial<InterestRules>,
+		session: EntityId | null
+	) {
+		appState.lockNavigation(m.making_assignment());
+		try {
+			const result = await ipc_invoke_result<AssignmentResult, GeneratedAssignmentError>(
+				command,
+				{ values: makeSaveValuesObject(), session, conflicts, interest }
+			);
+			if (!result.success) return [fromGeneratedError(result.error)];
+			useAssignation(result.value.assignation);
+			assignmentDiagnosis.set(result.value.diagnosis ?? undefined);
+			incompatibilitySession.set(result.value.incompatibilitySession);
+			return result.value.errors.map(fromGeneratedError);
+		} finally {
+			appState.unlockNavigation();
+		}
+	}
+
+	/**
+	 * Like `reassign`, but the backend keeps improving the assignment until the time budget of
+	 * the settings runs out
+	 */
+	async function optimise(
+		settings: Partial<OptimisationSettings> = {},
+		session: EntityId | null = null
+	): Promise<{ errors: AssignmentError[]; cost?: { before: AssignmentCost; after: AssignmentCost } }> {
+		appState.lockNavigation(m.optimising_assignment());
+		try {
+			const result = await ipc_invoke_result<OptimisationResult, GeneratedAssignmentError>(
+				'optimise_assignment',
+				{ values: makeSaveValuesObject(), session, settings }
+			);
+			if (!result.success) return { errors: [fromGeneratedError(result.error)] };
+			useAssignation(result.value.assignation);
+			assignmentDiagnosis.set(result.value.diagnosis ?? undefined);
+			incompatibilitySession.set(result.value.incompatibilitySession);
+			return {
+				errors: result.value.errors.map(fromGeneratedError),
+				cost: { before: result.value.initialCost, after: result.value.cost }
+			};
+		} finally {
+			appState.unlockNavigation();
+		}
+	}
+
+	/** Gives every examinee of the assignment a seat in its classroom */
+	async function assignSeats(
+		strategy: SeatingStrategy,
+		session: EntityId | null = null
+	): Promise<SeatingError[]> {
+		const result = await ipc_invoke_result<SeatingResult, SeatingError>('seat_examinees', {
+			values: makeSaveValuesObject(),
+			session,
+			strategy
 		});
+		if (!result.success) return [result.error];
+		useAssignation(result.value.assignation);
+		return result.value.errors;
+	}
+
+	/**
+	 * The academic centres of the examinees in every classroom, marking the classrooms where a
+	 * centre has more than `maxShare` percent of the examinees
+	 */
+	function centreSpread(
+		maxShare: number,
+		session: EntityId | null = null
+	): Promise<ClassroomCentres[]> {
+		return ipc_invoke<ClassroomCentres[]>('report_centre_spread', {
+			values: makeSaveValuesObject(),
+			session,
+			maxShare
+		});
+	}
+
+	/** Every pair of overlapping exams an examinee is enrolled in */
+	function scheduleConflicts(session: EntityId | null = null): Promise<ScheduleConflict[]> {
+		return ipc_invoke<ScheduleConflict[]>('check_schedule_conflicts', {
+			values: makeSaveValuesObject(),
+			session
+		});
+	}
+
+	/** Vigilants of the assignment watching examinees `interest` keeps them from watching */
+	function interestConflicts(
+		interest: Partial<InterestRules> = {},
+		session: EntityId | null = null
+	): Promise<InterestConflict[]> {
+		return ipc_invoke<InterestConflict[]>('check_interest_conflicts', {
+			values: makeSaveValuesObject(),
+			session,
+			interest
+		});
+	}
+
+	/** Vigilants assigned out of their availability or over their maximum of duties */
+	function availabilityIssues(): Promise<AvailabilityIssue[]> {
+		return ipc_invoke<AvailabilityIssue[]>('check_assignment_availability', {
+			values: makeSaveValuesObject()
+		});
+	}
+
+	/** How many times every examinee changes classroom between one exam and the next one */
+	function roomChanges(session: EntityId | null = null): Promise<ExamineeRoomChanges[]> {
+		return ipc_invoke<ExamineeRoomChanges[]>('report_room_changes', {
+			values: makeSaveValuesObject(),
+			session
+		});
+	}
+
+	/** Evens the duties of the vigilants across the whole assignment */
+	async function balanceWorkload(
+		interest: Partial<InterestRules> = {},
+		session: EntityId | null = null
+	): Promise<
+		{ error: WorkloadError } | { before: WorkloadReport; after: WorkloadReport }
+	> {
+		const result = await ipc_invoke_result<BalanceResult, WorkloadError>(
+			'balance_vigilant_workload',
+			{ values: makeSaveValuesObject(), session, interest }
+		);
+		if (!result.success) return { error: result.error };
+		useAssignation(result.value.assignation);
+		return { before: result.value.before, after: result.value.after };
 	}
 
 	function useEmptyAssignment() {
@@ -51,6 +204,8 @@ export const assignment = (function () {
 
 	function removeAssignation() {
 		set(undefined);
+		assignmentDiagnosis.set(undefined);
+		incompatibilitySession.set([]);
 	}
 
 	function parts() {
@@ -60,6 +215,15 @@ export const assignment = (function () {
 	return {
 		subscribe,
 		createNew,
+		reassign,
+		optimise,
+		assignSeats,
+		centreSpread,
+		scheduleConflicts,
+		interestConflicts,
+		availabilityIssues,
+		roomChanges,
+		balanceWorkload,
 		removeAssignation,
 		parts,
 		useEmptyAssignment,
@@ -69,84 +233,56 @@ export const assignment = (function () {
 
 export type ExamDistribution = {
 	subject: Subject;
-	distribution: { classroom: Classroom; examinees: Examinee[]; vigilants: Vigilant[] }[];
+	distribution: {
+		classroom: Classroom;
+		examinees: Examinee[];
+		vigilants: Vigilant[];
+		/** Kept for the subject when the assignment is done again */
+		locked?: boolean;
+		/** Stay in the classroom when the assignment is done again */
+		lockedExaminees?: Examinee[];
+		lockedVigilants?: Vigilant[];
+		/** Where the examinees sit, once the seats are assigned */
+		seats?: { examinee: Examinee; seat: number }[];
+	}[];
 	specialists: Vigilant[];
 };
 
 export type AssignmentError =
 	| { type: 'not-enough-seats'; subject: Subject }
+	| { type: 'not-enough-accessible-seats'; subject: Subject }
+	| { type: 'not-enough-separate-rooms'; subject: Subject }
 	| { type: 'not-enough-vigilants'; subject: Subject }
+	| { type: 'not-enough-available-vigilants'; subject: Subject }
 	| { type: 'no-classrooms' }
 	| { type: 'not-enough-classrooms'; subjects: Subject[] }
 	| { type: 'missing-exam-date'; subject: Subject }
-	| { type: 'missing-specialist'; subject: Subject };
+	| { type: 'missing-specialist'; subject: Subject }
+	| { type: 'conflict-of-interest'; subject: Subject }
+	| { type: 'not-enough-seats-in-shared-room'; subjects: Subject[] }
+	| { type: 'no-vigilant-for-shared-room'; subjects: Subject[] }
+	| { type: 'unknown-session' }
+	| { type: 'search-failed' };
 export type DistributionError = 'assignment-not-done';
 
-export interface ExamConfiguration {
-	addExaminees(examinees: readonly Examinee[]): void;
-	addClassrooms(classrooms: readonly Classroom[]): void;
-	addVigilants(vigilants: readonly Vigilant[]): void;
-	doAssignment(): AssignmentError[];
-	useEmptyAssignment(): void;
-	hasEnoughCapacity(): 'no-problem' | 'could-use-more' | 'not-enough';
-	getCapacities(): { totalCapacity: number; examCapacity: number };
-}
-
-export function orderAndGroupSubjects(
-	subjects: Subject[]
-): { ok: false; error: AssignmentError } | { ok: true; configuration: ExamConfiguration } {
-	function examDateCollides(firstSubject: Subject, seccondSubject: Subject) {
-		return (
-			firstSubject.examStartDate! <= seccondSubject.examStartDate! &&
-			seccondSubject.examStartDate! <= firstSubject.examFinishDate!
-		);
-	}
-
-	const totalSubjects = subjects.length;
-	if (totalSubjects === 0) return { ok: true, configuration: new ExamsConfiguration([]) };
-	for (let i = 0; i < totalSubjects; i++) {
-		const subject = subjects[i];
-		if (subject.examStartDate === undefined || subject.examDuration === undefined)
-			return { ok: false, error: { type: 'missing-exam-date', subject } };
-	}
-
-	const orderedSubjects = subjects.sort((a, b) =>
-		a.examStartDate!.diff(b.examStartDate!).toMillis()
-	);
-
-	let lastSubject = subjects[0];
-	let actualGroup = [lastSubject];
-	const grouped = [actualGroup];
-
-	for (let i = 1; i < totalSubjects; i++) {
-		const subject = orderedSubjects[i];
-		if (examDateCollides(lastSubject, subject)) {
-			actualGroup.push(subject);
-		} else {
-			actualGroup = [subject];
-			grouped.push(actualGroup);
-		}
-		lastSubject = subject;
+function fromGeneratedError(error: GeneratedAssignmentError): AssignmentError {
+	const subject = (id: EntityId) => get(getSubject(id))!;
+	switch (error.type) {
+		case 'no-classrooms':
+			return error;
+		case 'unknown-session':
+			return { type: error.type };
+		case 'search-failed':
+			return error;
+		case 'not-enough-classrooms':
+		case 'not-enough-seats-in-shared-room':
+		case 'no-vigilant-for-shared-room':
+			return { type: error.type, subjects: error.subjects.map(subject) };
+		default:
+			return { type: error.type, subject: subject(error.subject) };
 	}
-
-	return { ok: true, configuration: collidingExamnsToConfiguration(grouped) };
 }
 
-export function collidingExamnsToConfiguration(grouped: Subject[][]) {
-	if (grouped.length === 1) {
-		const group = grouped[0];
-		if (group.length === 1) return new IndividualExamConfiguration(group[0]);
-		return new CollidingExamsConfiguration(
-			group.map((subject) => new IndividualExamConfiguration(subject))
-		);
-	}
-	return new ExamsConfiguration(
-		grouped.map((collitions) =>
-			collitions.length === 1
-				? new IndividualExamConfiguration(collitions[0])
-				: new CollidingExamsConfiguration(
-						collitions.map((subject) => new IndividualExamConfiguration(subject))
-				  )
-		)
-	);
+export interface ExamConfiguration {
+	useEmptyAssignment(): void;
 }
@@ -1,3 +1,6 @@
+import * as m from '$paraglide/messages';
+import type { AvailabilityImportColumn } from './types/generated/AvailabilityImportColumn';
+import type { AvailabilityImportError } from './types/generated/AvailabilityImportError';
 import type { ExamineeImportColumn } from './types/generated/ExamineeImportColumn';
 import type { ExamineeImportError } from './types/generated/ExamineeImportError';
 import type { ExamineeImportInvalidValueError } from './types/generated/ExamineeImportInvalidValueError';
@@ -71,3 +74,58 @@ function getColumnName(missing: ExamineeImportColumn) {
 			return 'del centro académico del examinado';
 	}
 }
+
+export function getAvailabilityImportErrorMessage(error: AvailabilityImportError): {
+	title: string;
+	message: string;
+} {
+	const title = m.availability_import_error_title();
+	switch (error.type) {
+		case 'lock':
+			return { title, message: m.availability_import_error_lock() };
+		case 'noValuesLoaded':
+			return { title, message: m.availability_import_error_no_values_loaded() };
+		case 'noSheet':
+			return { title, message: m.availability_import_error_no_sheet() };
+		case 'missingValue':
+			return {
+				title,
+				message: m.availability_import_error_missing_value({
+					row: error.row,
+					column: getAvailabilityColumnName(error.missing)
+				})
+			};
+		case 'invalidValue':
+			return {
+				title,
+				message: m.availability_import_error_invalid_value({
+					row: error.row,
+					column: getAvailabilityColumnName(error.column),
+					value: error.invalidValue
+				})
+			};
+		case 'missmatchValue':
+			return {
+				title,
+				message: m.availability_import_error_missmatch_value({
+					row: error.row,
+					vigilant: error.vigilant,
+					established: error.establishedValue,
+					found: error.foundValue
+				})
+			};
+	}
+}
+
+function getAvailabilityColumnName(column: AvailabilityImportColumn) {
+	switch (column) {
+		case 'vigilantName':
+			return m.name();
+		case 'availabilityStart':
+			return m.availability_start();
+		case 'availabilityEnd':
+			return m.availability_end();
+		case 'maxDuties':
+			return m.max_duties();
+	}
+}
@@ -1,5 +1,24 @@
 import { z } from 'zod';
 import { ModelId, createStore, type Model } from './models';
+import type { ClassroomFeature } from '$lib/types/generated/ClassroomFeature';
+import type { SeatGrid } from '$lib/types/generated/SeatGrid';
+import type { SeatSpacing } from '$lib/types/generated/SeatSpacing';
+import type { Subject } from './subjects';
+
+export const CLASSROOM_FEATURE_VALUES = [
+	'wheelchairAccess',
+	'powerOutlets',
+	'drawingTables',
+	'audioEquipment',
+	'laboratory'
+] as const satisfies readonly ClassroomFeature[];
+
+export const SEAT_SPACING_VALUES = [
+	'none',
+	'alternateSeats',
+	'alternateRows',
+	'checkerboard'
+] as const satisfies readonly SeatSpacing[];
 
 export class Classroom implements Model {
 	static Id = ModelId;
@@ -16,6 +35,15 @@ export class Classroom implements Model {
 		.pipe(z.coerce.number().finite().gte(-32768).lte(32767).optional());
 	static Kind = z.string();
 	static Notes = z.string().array();
+	static Features = z.enum(CLASSROOM_FEATURE_VALUES).array().default([]);
+	static SeatGrid = z
+		.object({
+			rows: z.coerce.number().int().positive().lte(65535),
+			seatsPerRow: z.coerce.number().int().positive().lte(65535),
+			unusableSeats: z.coerce.number().int().positive().array().default([]),
+			spacing: z.enum(SEAT_SPACING_VALUES).default('none')
+		})
+		.optional();
 	static Type = z.object({
 		id: Classroom.Id,
 		code: Classroom.Code,
@@ -25,7 +53,9 @@ export class Classroom implements Model {
 		priority: Classroom.Priority,
 		courtLocation: Classroom.CourtLocation,
 		kind: Classroom.Kind,
-		notes: Classroom.Notes
+		notes: Classroom.Notes,
+		features: Classroom.Features,
+		seatGrid: Classroom.SeatGrid
 	});
 
 	readonly id: ModelId;
@@ -37,6 +67,11 @@ export class Classroom implements Model {
 	courtLocation: number | undefined;
 	kind: string;
 	notes: string[];
+	buildingId: ModelId | undefined;
+	floor: number | undefined;
+	features: Set<ClassroomFeature>;
+	/** How the seats are laid out, without it the seats are just numbered */
+	seatGrid: SeatGrid | undefined;
 
 	constructor(params: {
 		id: ModelId;
@@ -48,6 +83,10 @@ export class Classroom implements Model {
 		courtLocation?: number | null;
 		kind: string;
 		notes: string[];
+		buildingId?: ModelId | null;
+		floor?: number | null;
+		features?: ClassroomFeature[];
+		seatGrid?: SeatGrid | null;
 	}) {
 		this.id = params.id;
 		this.code = params.code;
@@ -58,6 +97,10 @@ export class Classroom implements Model {
 		this.courtLocation = params.courtLocation ?? undefined;
 		this.kind = params.kind;
 		this.notes = params.notes;
+		this.buildingId = params.buildingId ?? undefined;
+		this.floor = params.floor ?? undefined;
+		this.features = new Set(params.features ?? []);
+		this.seatGrid = params.seatGrid ?? undefined;
 	}
 
 	setCode(value: string): void {
@@ -99,6 +142,19 @@ export class Classroom implements Model {
 		this.notes = Classroom.Notes.parse(value);
 	}
 
+	setFeatures(value: ClassroomFeature[]): void {
+		this.features = new Set(Classroom.Features.parse(value));
+	}
+
+	setSeatGrid(value?: SeatGrid): void {
+		this.seatGrid = Classroom.SeatGrid.parse(value);
+	}
+
+	/** If the classroom has every feature the exam of the subject requires */
+	suits(subject: Subject): boolean {
+		return [...subject.requiredFeatures].every((feature) => this.features.has(feature));
+	}
+
 	toString(): string {
 		return `id: ${this.id}, code: ${this.code}, locationCode: ${this.locationCode}, totalCapacity: ${this.totalCapacity}, examCapacity: ${this.examCapacity}, priority: ${this.priority}, courtLocation: ${this.courtLocation}, kind: ${this.kind}, notes: ${this.notes}`;
 	}
@@ -112,7 +168,9 @@ export const ClassroomForCreate = z.object({
 	priority: Classroom.Priority,
 	courtLocation: Classroom.CourtLocation,
 	kind: Classroom.Kind,
-	notes: z.union([Classroom.Notes, z.string().transform((s) => s.split('\n'))])
+	notes: z.union([Classroom.Notes, z.string().transform((s) => s.split('\n'))]),
+	features: Classroom.Features,
+	seatGrid: Classroom.SeatGrid
 });
 export type ClassroomForCreate = z.infer<typeof ClassroomForCreate>;
 
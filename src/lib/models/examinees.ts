@@ -6,6 +6,7 @@ import { getAcademicCentre } from '$lib/services/academicCentres';
 import { Subject } from './subjects';
 import { getSubject } from '$lib/services/subjects';
 import { warn } from 'tauri-plugin-log-api';
+import type { Accommodations } from '$lib/types/generated/Accommodations';
 
 export class Examinee implements Model {
 	static Id = ModelId;
@@ -16,6 +17,13 @@ export class Examinee implements Model {
 	static Court = z.coerce.number().finite().gte(-32768).lte(32767);
 	static SubjectsIds = Subject.Id.array().default([]);
 	static AcademicCentreId = AcademicCentre.Id.optional();
+	static Accommodations = z
+		.object({
+			extraTimePercentage: z.coerce.number().int().gte(0).lte(65535).default(0),
+			accessibleRoom: z.boolean().default(false),
+			separateRoom: z.boolean().default(false)
+		})
+		.default({});
 	static Type = z.object({
 		id: Examinee.Id,
 		nif: Examinee.Nif,
@@ -24,7 +32,8 @@ export class Examinee implements Model {
 		origin: Examinee.Origin,
 		court: Examinee.Court,
 		subjectsIds: Examinee.SubjectsIds,
-		academicCentreId: Examinee.AcademicCentreId.optional()
+		academicCentreId: Examinee.AcademicCentreId.optional(),
+		accommodations: Examinee.Accommodations
 	});
 
 	readonly id: ModelId;
@@ -32,9 +41,10 @@ export class Examinee implements Model {
 	name: string;
 	surenames: string;
 	origin: string;
-	court: number;
+	court: number | undefined;
 	subjectsIds: Set<number>;
 	academicCentreId: number | undefined;
+	accommodations: Accommodations;
 
 	lazyAcademicCentreName: undefined | string = undefined;
 	lazySubjectsNames: string[] = [];
@@ -45,10 +55,11 @@ export class Examinee implements Model {
 		name: string;
 		surenames?: string;
 		origin: string;
-		court: number;
+		court?: number;
 		subjectsIds?: number[];
 		academicCentre?: ModelId;
 		academicCentreId?: ModelId;
+		accommodations?: Accommodations;
 	}) {
 		this.id = params.id;
 		this.nif = params.nif;
@@ -58,6 +69,11 @@ export class Examinee implements Model {
 		this.court = params.court;
 		this.subjectsIds = new Set(params.subjectsIds || []);
 		this.academicCentreId = params.academicCentre ?? params.academicCentreId;
+		this.accommodations = params.accommodations ?? {
+			extraTimePercentage: 0,
+			accessibleRoom: false,
+			separateRoom: false
+		};
 	}
 
 	setNif(value: string): void {
@@ -134,6 +150,14 @@ export class Examinee implements Model {
 		return ar;
 	}
 
+	/** When the examinee finishes the exam of the subject, extra time included */
+	examFinishDate(subject: Subject) {
+		const finish = subject.examFinishDate;
+		if (finish === undefined) return undefined;
+		const extra = (subject.examDuration!.toMillis() * this.accommodations.extraTimePercentage) / 100;
+		return finish.plus(extra);
+	}
+
 	toString(): string {
 		return `id: ${this.id}, nif: ${this.nif}, name: ${this.name}, surenames: ${
 			this.surenames
@@ -152,7 +176,8 @@ export const ExamineeForCreate = z.object({
 	origin: Examinee.Origin,
 	court: Examinee.Court,
 	subjectsIds: Examinee.SubjectsIds,
-	academicCentre: z.union([AcademicCentre.Id, AcademicCentreForCreate, z.string()]).optional()
+	academicCentre: z.union([AcademicCentre.Id, AcademicCentreForCreate, z.string()]).optional(),
+	accommodations: Examinee.Accommodations
 });
 export type ExamineeForCreate = z.infer<typeof ExamineeForCreate>;
 
@@ -1,6 +1,8 @@
 import { z } from 'zod';
 import { ModelId, createStore, type Model } from './models';
 import { DateTime, Duration } from 'luxon';
+import { Classroom } from './classroom';
+import type { ClassroomFeature } from '$lib/types/generated/ClassroomFeature';
 
 export const SUBJECT_KIND_VALUES = ['OBLIGATORY', 'VOLUNTARY', 'UNKNOWN'] as const;
 export type SubjectKind = (typeof SUBJECT_KIND_VALUES)[number];
@@ -21,12 +23,14 @@ export class Subject implements Model {
 				.positive()
 				.transform((minutes) => Duration.fromObject({ minutes }))
 		);
+	static RequiredFeatures = Classroom.Features;
 	static Type = z.object({
 		id: Subject.Id,
 		name: Subject.Name,
 		kind: Subject.Kind,
 		examDate: Subject.ExamStartDate.optional(),
-		examDuration: Subject.ExamDuration.optional()
+		examDuration: Subject.ExamDuration.optional(),
+		requiredFeatures: Subject.RequiredFeatures
 	});
 
 	readonly id: ModelId;
@@ -34,6 +38,7 @@ export class Subject implements Model {
 	kind: SubjectKind;
 	examStartDate: DateTime | undefined;
 	examDuration: Duration | undefined;
+	requiredFeatures: Set<ClassroomFeature>;
 
 	constructor(params: {
 		id: ModelId;
@@ -42,6 +47,7 @@ export class Subject implements Model {
 		examStartDate?: DateTime | string | null;
 		examDuration?: Duration | string | null;
 		examDate?: string | null;
+		requiredFeatures?: ClassroomFeature[];
 	}) {
 		if (params.examDate !== undefined) params.examStartDate = params.examDate;
 		this.id = params.id;
@@ -55,6 +61,7 @@ export class Subject implements Model {
 			(typeof params.examDuration === 'string'
 				? Duration.fromISO(params.examDuration)
 				: params.examDuration) ?? undefined;
+		this.requiredFeatures = new Set(params.requiredFeatures ?? []);
 	}
 
 	get hasExamDate() {
@@ -82,6 +89,10 @@ export class Subject implements Model {
 		this.examDuration = value;
 	}
 
+	setRequiredFeatures(value: ClassroomFeature[]): void {
+		this.requiredFeatures = new Set(Subject.RequiredFeatures.parse(value));
+	}
+
 	toString(): string {
 		return `id: ${this.id}, name: ${this.name}, kind: ${this.kind}, examStartDate: ${this.examStartDate}, examDuration: ${this.examDuration}`;
 	}
@@ -91,7 +102,8 @@ export const SubjectForCreate = z.object({
 	name: Subject.Name,
 	kind: Subject.Kind,
 	examStartDate: Subject.ExamStartDate.optional(),
-	examDuration: Subject.ExamDuration.optional()
+	examDuration: Subject.ExamDuration.optional(),
+	requiredFeatures: Subject.RequiredFeatures
 });
 export type SubjectForCreate = z.infer<typeof SubjectForCreate>;
 
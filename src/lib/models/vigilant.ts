@@ -6,6 +6,8 @@ import { getAcademicCentre } from '$lib/services/academicCentres';
 import { get } from 'svelte/store';
 import { getSubject } from '$lib/services/subjects';
 import { warn } from 'tauri-plugin-log-api';
+import { DateTime } from 'luxon';
+import type { AvailabilityWindow } from '$lib/types/generated/AvailabilityWindow';
 
 export const VIGILANT_ROLE_VALUES = ['PRESIDENT', 'SECRETARY', 'MEMBER'] as const;
 export type VigilantRole = (typeof VIGILANT_ROLE_VALUES)[number];
@@ -18,6 +20,22 @@ export class Vigilant implements Model {
 	static SpecialtiesIds = Subject.Id.array().default([]);
 	static AcademicCentreId = AcademicCentre.Id.optional();
 	static MainCourt = z.coerce.number().finite().gte(-32768).lte(32767);
+	/** A text is read as a list separated by commas */
+	static RelativesNifs = z
+		.preprocess(
+			(value) => (typeof value === 'string' ? value.split(',') : value),
+			z
+				.string()
+				.trim()
+				.array()
+				.transform((nifs) => nifs.filter((nif) => nif.length > 0))
+		)
+		.default([]);
+	/** An empty text means there is no maximum */
+	static MaxDuties = z.preprocess(
+		(value) => (value === '' || value === null ? undefined : value),
+		z.coerce.number().int().gte(0).lte(65535).optional()
+	);
 	static Type = z.object({
 		id: Vigilant.Id,
 		name: Vigilant.Name,
@@ -34,7 +52,12 @@ export class Vigilant implements Model {
 	role: VigilantRole;
 	specialtiesIds: Set<number>;
 	academicCentreId: number | undefined;
-	mainCourt: number;
+	mainCourt: number | undefined;
+	/** When the vigilant can attend exams, always if there are no windows */
+	availability: { start: DateTime; end: DateTime }[];
+	maxDuties: number | undefined;
+	/** NIFs of the examinees the vigilant declared as relatives */
+	relativesNifs: string[];
 
 	lazyAcademicCentreName: undefined | string = undefined;
 	lazySpecialtiesNames: string[] = [];
@@ -46,7 +69,10 @@ export class Vigilant implements Model {
 		role: VigilantRole;
 		specialtiesIds?: number[];
 		academicCentre?: number;
-		mainCourt: number;
+		mainCourt?: number;
+		availability?: AvailabilityWindow[];
+		maxDuties?: number | null;
+		relativesNifs?: string[];
 	}) {
 		this.id = params.id;
 		this.name = params.name;
@@ -55,6 +81,12 @@ export class Vigilant implements Model {
 		this.specialtiesIds = new Set(params.specialtiesIds || []);
 		this.academicCentreId = params.academicCentre;
 		this.mainCourt = params.mainCourt;
+		this.availability = (params.availability ?? []).map(({ start, end }) => ({
+			start: DateTime.fromISO(start),
+			end: DateTime.fromISO(end)
+		}));
+		this.maxDuties = params.maxDuties ?? undefined;
+		this.relativesNifs = params.relativesNifs ?? [];
 	}
 
 	setName(value: string): void {
@@ -105,6 +137,29 @@ export class Vigilant implements Model {
 		this.mainCourt = Vigilant.MainCourt.parse(value);
 	}
 
+	setRelativesNifs(value: string[]): void {
+		this.relativesNifs = Vigilant.RelativesNifs.parse(value);
+	}
+
+	setMaxDuties(value: number | undefined): void {
+		this.maxDuties = Vigilant.MaxDuties.parse(value);
+	}
+
+	setAvailability(windows: AvailabilityWindow[]): void {
+		this.availability = windows.map(({ start, end }) => ({
+			start: DateTime.fromISO(start),
+			end: DateTime.fromISO(end)
+		}));
+	}
+
+	/** If the vigilant can attend the whole time between the given instants */
+	isAvailable(start: DateTime, end: DateTime): boolean {
+		return (
+			this.availability.length === 0 ||
+			this.availability.some((window) => window.start <= start && end <= window.end)
+		);
+	}
+
 	public getSpecialties(): Subject[] {
 		const ar = [];
 		this.lazySpecialtiesNames.length = 0;
@@ -138,7 +193,9 @@ export const VigilantForCreate = z.object({
 	role: Vigilant.Role,
 	specialtiesIds: Vigilant.SpecialtiesIds,
 	academicCentre: z.union([AcademicCentre.Id, AcademicCentreForCreate, z.string()]).optional(),
-	mainCourt: Vigilant.MainCourt
+	mainCourt: Vigilant.MainCourt,
+	maxDuties: Vigilant.MaxDuties,
+	relativesNifs: Vigilant.RelativesNifs
 });
 export type VigilantForCreate = z.infer<typeof VigilantForCreate>;
 
@@ -1,6 +1,9 @@
 import { Classroom, ClassroomForCreate, classroomsStore } from '$lib/models/classroom';
 import type { ModelId } from '$lib/models/models';
 import { get } from 'svelte/store';
+import type { ClassroomFeature } from '$lib/types/generated/ClassroomFeature';
+import type { SeatSpacing } from '$lib/types/generated/SeatSpacing';
+import * as m from '$paraglide/messages';
 
 export let currentId = 0;
 
@@ -48,3 +51,43 @@ export function deleteClassroom(id: ModelId) {
 export function deleteClassrooms(ids: ModelId[]) {
 	return classroomsStore.deleteInstances(ids);
 }
+
+export function classroomFeatureTranslate(feature: ClassroomFeature) {
+	switch (feature) {
+		case 'wheelchairAccess':
+			return m.classroom_feature_wheelchair_access();
+		case 'powerOutlets':
+			return m.classroom_feature_power_outlets();
+		case 'drawingTables':
+			return m.classroom_feature_drawing_tables();
+		case 'audioEquipment':
+			return m.classroom_feature_audio_equipment();
+		case 'laboratory':
+			return m.classroom_feature_laboratory();
+	}
+}
+
+export function seatSpacingTranslate(spacing: SeatSpacing) {
+	switch (spacing) {
+		case 'none':
+			return m.seat_spacing_none();
+		case 'alternateSeats':
+			return m.seat_spacing_alternate_seats();
+		case 'alternateRows':
+			return m.seat_spacing_alternate_rows();
+		case 'checkerboard':
+			return m.seat_spacing_checkerboard();
+	}
+}
+
+/** The seat grid of a classroom form, only if its rows and seats per row are filled */
+export function seatGridFromForm(form: FormData) {
+	const rows = form.get('seatRows');
+	const seatsPerRow = form.get('seatsPerRow');
+	if (!rows || !seatsPerRow) return undefined;
+	const unusableSeats = String(form.get('unusableSeats') ?? '')
+		.split(',')
+		.map((seat) => seat.trim())
+		.filter((seat) => seat !== '');
+	return { rows, seatsPerRow, unusableSeats, spacing: form.get('seatSpacing') ?? 'none' };
+}
@@ -1,5 +1,6 @@
 import type { ExamineeImportValues } from '$lib/types/generated/ExamineeImportValues';
 import { get } from 'svelte/store';
+import * as m from '$paraglide/messages';
 import {
 	createAcademicCentre,
 	findAcademicCentreByName,
@@ -55,6 +56,38 @@ import { IndividualExamConfiguration } from '$lib/assignment/individualExamConfi
 import { ExamsConfiguration } from '$lib/assignment/examsConfiguration';
 import { CollidingExamsConfiguration } from '$lib/assignment/collidingExamsConfiguration';
 
+// Exam sessions, courts and buildings can not be edited yet, they are kept as loaded so they are
+// saved back
+let examSessions: AppValues['examSessions'] = [];
+let examSessionsId: AppValues['ids']['examSessions'] = 0;
+let courts: AppValues['courts'] = [];
+let courtsId: AppValues['ids']['courts'] = 0;
+let buildings: AppValues['buildings'] = [];
+let buildingsId: AppValues['ids']['buildings'] = 0;
+
+/** Number of the court with the id, the models keep the number that the user sees and types */
+function courtNumber(id: number | null): number | undefined {
+	if (id === null) return undefined;
+	return courts.find((court) => court.id === id)?.number;
+}
+
+/** Id of the court with the number, creating the court when there is none with it yet */
+function courtId(number: number | undefined): number | null {
+	if (number === undefined) return null;
+	const existing = courts.find((court) => court.number === number);
+	if (existing !== undefined) return existing.id;
+	const court = {
+		id: courtsId++,
+		number,
+		name: m.generated_court_name({ number }),
+		venue: null,
+		presidentId: null,
+		academicCentresIds: []
+	} satisfies AppValues['courts'][number];
+	courts = [...courts, court];
+	return court.id;
+}
+
 function useAssignationValuesObject(
 	values: GeneratedAllExamConfiguration,
 	subjects: Subject[],
@@ -79,7 +112,11 @@ function useAssignationValuesObject(
 					distribution: configuration.distribution.distribution.map((d) => ({
 						classroom: classrooms[d.classroom]!,
 						examinees: d.examinees.map((pos) => examinees[pos]!),
-						vigilants: d.vigilants.map((pos) => vigilants[pos]!)
+						vigilants: d.vigilants.map((pos) => vigilants[pos]!),
+						locked: d.locked,
+						lockedExaminees: d.lockedExaminees.map((pos) => examinees[pos]!),
+						lockedVigilants: d.lockedVigilants.map((pos) => vigilants[pos]!),
+						seats: d.seats.map(({ examinee, seat }) => ({ examinee: examinees[examinee]!, seat }))
 					}))
 				} satisfies ExamDistribution;
 			}
@@ -102,12 +139,40 @@ function useAssignationValuesObject(
 	assignment.set(new ExamsConfiguration(exams));
 }
 
+/** Uses an assignation made out of the values in the stores, like the one made by the backend */
+export function useAssignation(values: GeneratedAllExamConfiguration) {
+	function indexById<T extends { id: number }>(instances: T[]) {
+		return instances.reduce((accumulator, v) => {
+			accumulator[v.id] = v;
+			return accumulator;
+		}, [] as T[]);
+	}
+
+	useAssignationValuesObject(
+		values,
+		indexById(get(getAllSubjects())),
+		indexById(get(getAllVigilants())),
+		indexById(get(getAllExaminees())),
+		indexById(get(getAllClassrooms()))
+	);
+}
+
 export function useSavedValuesObject(values: AppValues) {
+	courts = values.courts ?? [];
+	courtsId = values.ids.courts ?? 0;
+
 	const academicCentres = values.academicCentres.map((instance) => new AcademicCentre(instance));
-	const classrooms = values.classrooms.map((instance) => new Classroom(instance));
+	const classrooms = values.classrooms.map(
+		(instance) =>
+			new Classroom({ ...instance, courtLocation: courtNumber(instance.courtLocationId) })
+	);
 	const subjects = values.subjects.map((instance) => new Subject(instance));
-	const vigilants = values.vigilants.map((instance) => new Vigilant(instance));
-	const examinees = values.examinees.map((instance) => new Examinee(instance));
+	const vigilants = values.vigilants.map(
+		(instance) => new Vigilant({ ...instance, mainCourt: courtNumber(instance.mainCourtId) })
+	);
+	const examinees = values.examinees.map(
+		(instance) => new Examinee({ ...instance, court: courtNumber(instance.courtId) })
+	);
 
 	examineesStore.clear();
 	vigilantsStore.clear();
@@ -159,6 +224,10 @@ export function useSavedValuesObject(values: AppValues) {
 	setVigilantsId(values.ids.vigilants);
 	setClassroomsId(values.ids.classrooms);
 	setSubjectsId(values.ids.subjects);
+	examSessions = values.examSessions ?? [];
+	examSessionsId = values.ids.examSessions ?? 0;
+	buildings = values.buildings ?? [];
+	buildingsId = values.ids.buildings ?? 0;
 
 	runSubjectsWithoutWarningCheck();
 	runExamineeAndVigilantHaveSameAcademicCentreCheck();
@@ -176,11 +245,23 @@ function makeAssignationValuesObject(): GeneratedAllExamConfiguration | null {
 						subject: exam.distribution.subject.id,
 						specialists: [...exam.distribution.specialists].map((specialist) => specialist.id),
 						distribution: exam.distribution.distribution.map(
-							({ classroom, examinees, vigilants }) =>
+							({
+								classroom,
+								examinees,
+								vigilants,
+								locked,
+								lockedExaminees,
+								lockedVigilants,
+								seats
+							}) =>
 								({
 									classroom: classroom.id,
 									examinees: examinees.map((examinee) => examinee.id),
-									vigilants: vigilants.map((vigilant) => vigilant.id)
+									vigilants: vigilants.map((vigilant) => vigilant.id),
+									locked: locked ?? false,
+									lockedExaminees: (lockedExaminees ?? []).map((examinee) => examinee.id),
+									lockedVigilants: (lockedVigilants ?? []).map((vigilant) => vigilant.id),
+									seats: (seats ?? []).map(({ examinee, seat }) => ({ examinee: examinee.id, seat }))
 								} satisfies GeneratedExamClassroomDistribution)
 						)
 				  } satisfies GeneratedExamDistribution);
@@ -215,10 +296,11 @@ export function makeSaveValuesObject(): AppValues {
 		nif: examinee.nif,
 		name: examinee.name,
 		surenames: examinee.surenames,
-		court: examinee.court,
+		courtId: courtId(examinee.court),
 		origin: examinee.origin,
 		academicCentreId: examinee.academicCentreId ?? null,
-		subjectsIds: [...examinee.subjectsIds]
+		subjectsIds: [...examinee.subjectsIds],
+		accommodations: examinee.accommodations
 	}));
 	const academicCentres: GeneratedAcademicCentre[] = get(getAllAcademicCentres()).map(
 		(academicCentre) => ({
@@ -231,27 +313,38 @@ export function makeSaveValuesObject(): AppValues {
 		name: subject.name,
 		kind: subject.kind,
 		examDate: subject.examStartDate?.toISO() ?? null,
-		examDuration: subject.examDuration?.toISO() ?? null
+		examDuration: subject.examDuration?.toISO() ?? null,
+		requiredFeatures: [...subject.requiredFeatures]
 	}));
 	const vigilants: GeneratedVigilant[] = get(getAllVigilants()).map((vigilant) => ({
 		id: vigilant.id,
 		name: vigilant.name,
 		surenames: vigilant.surenames,
 		role: vigilant.role,
-		mainCourt: vigilant.mainCourt,
+		mainCourtId: courtId(vigilant.mainCourt),
 		academicCentreId: vigilant.academicCentreId ?? null,
-		specialtiesIds: [...vigilant.specialtiesIds]
+		specialtiesIds: [...vigilant.specialtiesIds],
+		availability: vigilant.availability.map(({ start, end }) => ({
+			start: start.toISO()!,
+			end: end.toISO()!
+		})),
+		maxDuties: vigilant.maxDuties ?? null,
+		relativesNifs: vigilant.relativesNifs
 	}));
 	const classrooms: GeneratedClassroom[] = get(getAllClassrooms()).map((classroom) => ({
 		id: classroom.id,
 		code: classroom.code,
 		locationCode: classroom.locationCode,
-		courtLocation: classroom.courtLocation ?? null,
+		courtLocationId: courtId(classroom.courtLocation),
+		buildingId: classroom.buildingId ?? null,
+		floor: classroom.floor ?? null,
 		kind: classroom.kind,
 		totalCapacity: classroom.totalCapacity,
 		examCapacity: classroom.examCapacity,
 		priority: classroom.priority,
-		notes: classroom.notes
+		notes: classroom.notes,
+		features: [...classroom.features],
+		seatGrid: classroom.seatGrid ?? null
 	}));
 	const assignation = makeAssignationValuesObject();
 
@@ -261,13 +354,19 @@ export function makeSaveValuesObject(): AppValues {
 		subjects,
 		vigilants,
 		classrooms,
+		examSessions,
+		courts,
+		buildings,
 		assignation,
 		ids: {
 			examinees: examineesId,
 			academicCentres: academicCentresId,
 			classrooms: classroomsId,
 			subjects: subjectsId,
-			vigilants: vigilantsId
+			vigilants: vigilantsId,
+			examSessions: examSessionsId,
+			courts: courtsId,
+			buildings: buildingsId
 		}
 	};
 }
@@ -0,0 +1,36 @@
+import * as m from '$paraglide/messages';
+import { ipc_invoke_result } from '$lib/ipc';
+import type { DiffProjectsError } from '$lib/types/generated/DiffProjectsError';
+import type { ProjectDiff } from '$lib/types/generated/ProjectDiff';
+import type { ProjectSource } from '$lib/types/generated/ProjectSource';
+import type { ReportLabels } from '$lib/types/generated/ReportLabels';
+
+/** Texts of the report of the changes, the placeholders are filled in by the backend */
+function reportLabels(): ReportLabels {
+	return {
+		kinds: {
+			examinee: m.diff_report_examinees(),
+			academicCentre: m.diff_report_academic_centres(),
+			subject: m.diff_report_subjects(),
+			vigilant: m.diff_report_vigilants(),
+			classroom: m.diff_report_classrooms(),
+			examSession: m.diff_report_exam_sessions(),
+			court: m.diff_report_courts(),
+			building: m.diff_report_buildings()
+		},
+		assignment: m.diff_report_assignment(),
+		sessionAssignment: m.diff_report_session_assignment({ session: '{session}' }),
+		nowhere: m.diff_report_nowhere(),
+		exam: m.diff_report_exam({ subject: '{subject}' }),
+		specialist: m.diff_report_specialist({ vigilant: '{vigilant}' }),
+		moved: m.diff_report_moved({ item: '{item}', from: '{from}', to: '{to}' })
+	};
+}
+
+export function diffProjects(before: ProjectSource, after: ProjectSource) {
+	return ipc_invoke_result<ProjectDiff, DiffProjectsError>('diff_projects', {
+		before,
+		after,
+		labels: reportLabels()
+	});
+}
@@ -0,0 +1,37 @@
+import * as m from '$paraglide/messages';
+import { ipc_invoke_result } from '$lib/ipc';
+import type { FileLock } from '$lib/types/generated/FileLock';
+import type { FileLockError } from '$lib/types/generated/FileLockError';
+import { confirm } from '@tauri-apps/api/dialog';
+import { DateTime } from 'luxon';
+import { warn } from 'tauri-plugin-log-api';
+
+/**
+ * Asks the user whether to take the file from whoever holds its lock and takes it if so.
+ * Returns if the lock is now ours
+ */
+export async function promptTakeOverLock(
+	file: string,
+	error: Extract<FileLockError, { type: 'locked' }>
+): Promise<boolean> {
+	const { owner, host, timestamp } = error.lock;
+	const confirmed = await confirm(
+		m.file_locked_message({
+			owner,
+			host,
+			since: DateTime.fromSeconds(timestamp).toLocaleString(DateTime.DATETIME_SHORT)
+		}),
+		{ title: m.file_locked_title(), type: 'warning' }
+	);
+	if (!confirmed) return false;
+	const result = await ipc_invoke_result<FileLock | null, FileLockError>('take_over_file_lock', {
+		file
+	});
+	return result.success;
+}
+
+/** Lets others work on the file once we are done with it */
+export async function releaseLock(file: string) {
+	const result = await ipc_invoke_result<void, FileLockError>('release_file_lock', { file });
+	if (!result.success) warn(`Could not release the lock of ${file}: ${JSON.stringify(result.error)}`);
+}
@@ -0,0 +1,77 @@
+import * as m from '$paraglide/messages';
+import type { EntityKind } from '$lib/types/generated/EntityKind';
+import type { IntegrityIssue } from '$lib/types/generated/IntegrityIssue';
+import { confirm } from '@tauri-apps/api/dialog';
+
+/** Most issues listed to the user, the rest are only counted */
+const LISTED_ISSUES = 10;
+
+export function entityKindName(kind: EntityKind) {
+	switch (kind) {
+		case 'examinee':
+			return m.entity_kind_examinee();
+		case 'academicCentre':
+			return m.entity_kind_academic_centre();
+		case 'subject':
+			return m.entity_kind_subject();
+		case 'vigilant':
+			return m.entity_kind_vigilant();
+		case 'classroom':
+			return m.entity_kind_classroom();
+		case 'examSession':
+			return m.entity_kind_exam_session();
+		case 'court':
+			return m.entity_kind_court();
+		case 'building':
+			return m.entity_kind_building();
+	}
+}
+
+export function describeIntegrityIssue(issue: IntegrityIssue) {
+	switch (issue.type) {
+		case 'danglingReference': {
+			const kind = entityKindName(issue.kind);
+			if (issue.ownerKind === null || issue.ownerId === null)
+				return m.integrity_issue_dangling_reference_assignment({ kind, id: issue.id });
+			const owner = `${entityKindName(issue.ownerKind)} #${issue.ownerId}`;
+			return m.integrity_issue_dangling_reference({
+				owner: owner.charAt(0).toUpperCase() + owner.slice(1),
+				kind,
+				id: issue.id
+			});
+		}
+		case 'duplicateId':
+			return m.integrity_issue_duplicate_id({ kind: entityKindName(issue.kind), id: issue.id });
+		case 'duplicateCourtNumber':
+			return m.integrity_issue_duplicate_court_number({ number: issue.number });
+		case 'counterBehind':
+			return m.integrity_issue_counter_behind({
+				kind: entityKindName(issue.kind),
+				counter: issue.counter,
+				highest: issue.highest
+			});
+		case 'unreadableValue':
+			return m.integrity_issue_unreadable_value({
+				field: fieldName(issue.field),
+				owner: `${entityKindName(issue.kind)} #${issue.id}`,
+				value: issue.value
+			});
+	}
+}
+
+function fieldName(field: string) {
+	if (field.endsWith('examDate')) return m.exam_date();
+	if (field.endsWith('examDuration')) return m.exam_duration();
+	return field;
+}
+
+/** Lists the issues to the user and asks whether to repair them. Returns if the user agreed */
+export async function promptRepairIssues(issues: IntegrityIssue[]) {
+	const lines = issues.slice(0, LISTED_ISSUES).map((issue) => `• ${describeIntegrityIssue(issue)}`);
+	if (issues.length > LISTED_ISSUES)
+		lines.push(m.integrity_issues_more({ count: issues.length - LISTED_ISSUES }));
+	return confirm(m.integrity_issues_message({ issues: lines.join('\n') }), {
+		title: m.integrity_issues_title(),
+		type: 'warning'
+	});
+}
@@ -9,6 +9,8 @@ import * as m from '$paraglide/messages';
 import { get } from 'svelte/store';
 import { getOrCreateAcademicCentre } from './academicCentres';
 import { runExamineeAndVigilantHaveSameAcademicCentreCheck } from './warnings';
+import type { AvailabilityImportValues } from '$lib/types/generated/AvailabilityImportValues';
+import type { ImportedAvailability } from '$lib/types/generated/ImportedAvailability';
 
 export let currentId = 0;
 
@@ -80,6 +82,25 @@ export function updatedVigilant(id: ModelId) {
 	return result;
 }
 
+/**
+ * Gives the imported availability and maximum duties to the vigilants with the same name.
+ * Returns the imported vigilants that were not found
+ */
+export function useImportedAvailability(values: AvailabilityImportValues) {
+	const notFound: ImportedAvailability[] = [];
+	for (const imported of values.vigilants) {
+		const vigilant = findVigilantByName(imported.name, imported.surenames);
+		if (vigilant === undefined) {
+			notFound.push(imported);
+			continue;
+		}
+		vigilant.setAvailability(imported.availability);
+		if (imported.maxDuties !== null) vigilant.setMaxDuties(imported.maxDuties);
+		updatedVigilant(vigilant.id);
+	}
+	return notFound;
+}
+
 export function deleteVigilant(id: ModelId) {
 	if (vigilantsStore.deleteInstance(id)) {
 		runExamineeAndVigilantHaveSameAcademicCentreCheck();
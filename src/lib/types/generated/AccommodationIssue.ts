@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type AccommodationIssue = { "type": "inaccessibleClassroom", examinee: EntityId, subject: EntityId, classroom: EntityId, } | { "type": "sharedRoom", examinee: EntityId, subject: EntityId, classroom: EntityId, };
\ No newline at end of file
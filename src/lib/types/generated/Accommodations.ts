@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Adaptations some examinees need to take the exams
+ */
+export type Accommodations = { 
+/**
+ * Extra time over the duration of every exam, as a percentage of it
+ */
+extraTimePercentage: number, 
+/**
+ * Needs a classroom with wheelchair access
+ */
+accessibleRoom: boolean, 
+/**
+ * Has to take the exams alone in a supervised classroom
+ */
+separateRoom: boolean, };
\ No newline at end of file
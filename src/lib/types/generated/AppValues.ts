@@ -1,10 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AcademicCentre } from "./AcademicCentre";
 import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { Building } from "./Building";
 import type { Classroom } from "./Classroom";
+import type { Court } from "./Court";
+import type { ExamSession } from "./ExamSession";
 import type { Examinee } from "./Examinee";
 import type { Ids } from "./Ids";
 import type { Subject } from "./Subject";
 import type { Vigilant } from "./Vigilant";
 
-export type AppValues = { examinees: Array<Examinee>, academicCentres: Array<AcademicCentre>, subjects: Array<Subject>, vigilants: Array<Vigilant>, classrooms: Array<Classroom>, assignation: AllExamConfiguration | null, ids: Ids, };
\ No newline at end of file
+export type AppValues = { examinees: Array<Examinee>, academicCentres: Array<AcademicCentre>, subjects: Array<Subject>, vigilants: Array<Vigilant>, classrooms: Array<Classroom>, 
+/**
+ * Calls of the exams held in the project. Without sessions, the exam dates of the subjects
+ * and the assignment apply to the only call there is
+ */
+examSessions: Array<ExamSession>, courts: Array<Court>, buildings: Array<Building>, assignation: AllExamConfiguration | null, ids: Ids, };
\ No newline at end of file
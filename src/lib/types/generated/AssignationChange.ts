@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { EntityKind } from "./EntityKind";
+
+export type AssignationChange = { "type": "examAdded", subject: EntityId, } | { "type": "examRemoved", subject: EntityId, } | { "type": "membersChanged", subject: EntityId, kind: EntityKind, added: Array<EntityId>, removed: Array<EntityId>, } | { "type": "specialistsChanged", subject: EntityId, added: Array<EntityId>, removed: Array<EntityId>, } | { "type": "moved", subject: EntityId, kind: EntityKind, id: EntityId, from: EntityId | null, to: EntityId | null, };
\ No newline at end of file
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the optimiser minimises, each part is multiplied by its weight in the settings
+ */
+export type AssignmentCost = { 
+/**
+ * Classrooms with examinees in every exam
+ */
+roomsUsed: number, 
+/**
+ * Sum over the classrooms of the examinees squared divided by the vigilants, it grows
+ * quickly when few vigilants watch many examinees
+ */
+overload: number, 
+/**
+ * Times an examinee has to change classroom between one exam and the next one
+ */
+roomChanges: number, 
+/**
+ * Examinees above the maximum share of their academic centre in their classrooms
+ */
+centreExcess: number, };
\ No newline at end of file
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * Why the exams of some subjects could not be assigned. The kinds are the ones the frontend
+ * already shows
+ */
+export type AssignmentError = { "type": "not-enough-seats", subject: EntityId, } | { "type": "not-enough-accessible-seats", subject: EntityId, } | { "type": "not-enough-separate-rooms", subject: EntityId, } | { "type": "not-enough-vigilants", subject: EntityId, } | { "type": "not-enough-available-vigilants", subject: EntityId, } | { "type": "no-classrooms" } | { "type": "not-enough-classrooms", subjects: Array<EntityId>, } | { "type": "missing-exam-date", subject: EntityId, } | { "type": "missing-specialist", subject: EntityId, } | { "type": "conflict-of-interest", subject: EntityId, } | { "type": "not-enough-seats-in-shared-room", subjects: Array<EntityId>, } | { "type": "no-vigilant-for-shared-room", subjects: Array<EntityId>, } | { "type": "unknown-session", session: EntityId, } | { "type": "search-failed" };
\ No newline at end of file
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { AssignmentError } from "./AssignmentError";
+import type { Diagnosis } from "./Diagnosis";
+import type { SessionExam } from "./SessionExam";
+
+export type AssignmentResult = { assignation: AllExamConfiguration, 
+/**
+ * Problems of the exams that could not be assigned, they are left empty in the assignation
+ */
+errors: Array<AssignmentError>, 
+/**
+ * What is missing for the exams that could not be assigned, only if there are any
+ */
+diagnosis: Diagnosis | null, 
+/**
+ * Exams left for the incompatibility session by the examinees with overlapping exams
+ */
+incompatibility_session: Array<SessionExam>, };
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvailabilityImportColumn } from "./AvailabilityImportColumn";
+
+export type AvailabilityImportError = { "type": "lock" } | { "type": "noValuesLoaded" } | { "type": "noSheet" } | { "type": "missingValue", row: number, missing: AvailabilityImportColumn, } | { "type": "invalidValue", row: number, column: AvailabilityImportColumn, invalidValue: string, } | { "type": "missmatchValue", row: number, vigilant: string, establishedValue: string, foundValue: string, };
\ No newline at end of file
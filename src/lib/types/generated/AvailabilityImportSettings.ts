@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AvailabilityImportSettings = { selectedSheet: string, firstRowIsHeader: boolean, nameColumn: number, surenamesColumn: number, 
+/**
+ * A vigilant can have many rows, one for each window
+ */
+startColumn: number, endColumn: number, maxDutiesColumn: number | null, };
\ No newline at end of file
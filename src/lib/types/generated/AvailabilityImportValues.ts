@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportedAvailability } from "./ImportedAvailability";
+
+export type AvailabilityImportValues = { vigilants: Array<ImportedAvailability>, };
\ No newline at end of file
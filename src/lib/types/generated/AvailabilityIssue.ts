@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type AvailabilityIssue = { "type": "unavailable", vigilant: EntityId, subject: EntityId, } | { "type": "tooManyDuties", vigilant: EntityId, duties: number, maxDuties: number, } | { "type": "unsatisfiableSlot", subjects: Array<EntityId>, needed: number, available: number, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { WorkloadReport } from "./WorkloadReport";
+
+export type BalanceResult = { assignation: AllExamConfiguration, before: WorkloadReport, after: WorkloadReport, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * A building where there are classrooms used for the exams
+ */
+export type Building = { id: EntityId, name: string, campus: string | null, address: string | null, 
+/**
+ * Vigilant in charge of the building
+ */
+presidentId: EntityId | null, };
\ No newline at end of file
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type CentreShare = { 
+/**
+ * None for the examinees without an academic centre
+ */
+academicCentre: EntityId | null, examinees: number, 
+/**
+ * Percentage of the examinees of the classroom
+ */
+share: number, };
\ No newline at end of file
@@ -1,4 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassroomFeature } from "./ClassroomFeature";
 import type { EntityId } from "./EntityId";
+import type { SeatGrid } from "./SeatGrid";
 
-export type Classroom = { id: EntityId, code: string, locationCode: string, totalCapacity: number, examCapacity: number, priority: number, courtLocation: number | null, kind: string, notes: Array<string>, };
\ No newline at end of file
+export type Classroom = { id: EntityId, code: string, locationCode: string, totalCapacity: number, examCapacity: number, priority: number, 
+/**
+ * Court the classroom is reserved for, which keeps it out of the exams
+ */
+courtLocationId: EntityId | null, 
+/**
+ * Building the classroom is in and its floor there
+ */
+buildingId: EntityId | null, floor: number | null, kind: string, notes: Array<string>, features: Array<ClassroomFeature>, 
+/**
+ * How the seats are laid out, without it the seats are just numbered
+ */
+seatGrid: SeatGrid | null, };
\ No newline at end of file
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CentreShare } from "./CentreShare";
+import type { EntityId } from "./EntityId";
+
+/**
+ * How the examinees of a classroom are spread among their academic centres
+ */
+export type ClassroomCentres = { subject: EntityId, classroom: EntityId, examinees: number, 
+/**
+ * From the academic centre with the most examinees in the classroom to the one with the least
+ */
+centres: Array<CentreShare>, 
+/**
+ * Some academic centre has more examinees in the classroom than the maximum share
+ */
+exceeds: boolean, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Equipment of a classroom that some exams or examinees need
+ */
+export type ClassroomFeature = "wheelchairAccess" | "powerOutlets" | "drawingTables" | "audioEquipment" | "laboratory";
\ No newline at end of file
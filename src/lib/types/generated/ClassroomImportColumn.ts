@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClassroomImportColumn = "classroomCode" | "classroomLocationCode" | "classroomTotalCapacity" | "classroomExamCapacity" | "classroomFloor" | "buildingCampus" | "buildingAddress";
\ No newline at end of file
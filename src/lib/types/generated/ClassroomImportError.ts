@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassroomImportColumn } from "./ClassroomImportColumn";
+
+export type ClassroomImportError = { "type": "lock" } | { "type": "noValuesLoaded" } | { "type": "noSheet" } | { "type": "missingValue", row: number, missing: ClassroomImportColumn, } | { "type": "invalidValue", row: number, column: ClassroomImportColumn, invalidValue: string, } | { "type": "missmatchValue", row: number, building: string, missmatch: ClassroomImportColumn, establishedValue: string, foundValue: string, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClassroomImportSettings = { selectedSheet: string, firstRowIsHeader: boolean, codeColumn: number, locationCodeColumn: number, totalCapacityColumn: number, 
+/**
+ * Without it, the exam capacity is a third of the total one
+ */
+examCapacityColumn: number | null, buildingColumn: number | null, floorColumn: number | null, campusColumn: number | null, addressColumn: number | null, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportedBuilding } from "./ImportedBuilding";
+import type { ImportedClassroom } from "./ImportedClassroom";
+
+export type ClassroomImportValues = { classrooms: Array<ImportedClassroom>, buildings: Array<ImportedBuilding>, };
\ No newline at end of file
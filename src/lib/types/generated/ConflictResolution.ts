@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * What the assignment does with the examinees enrolled in exams held at the same time
+ */
+export type ConflictResolution = { "type": "sitBoth" } | { "type": "incompatibilitySession" } | { "type": "sharedRoom", classroom: EntityId, };
\ No newline at end of file
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * A tribunal in charge of the exams of some academic centres. Examinees, vigilants and
+ * classrooms refer to it by its id, files made before courts were entities by its number
+ */
+export type Court = { id: EntityId, number: number, name: string, 
+/**
+ * Where the court has its headquarters
+ */
+venue: string | null, 
+/**
+ * Vigilant that presides the court
+ */
+presidentId: EntityId | null, academicCentresIds: Array<EntityId>, };
\ No newline at end of file
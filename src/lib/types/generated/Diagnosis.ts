@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { ExcludedClassroom } from "./ExcludedClassroom";
+import type { Relaxation } from "./Relaxation";
+import type { SlotDiagnosis } from "./SlotDiagnosis";
+
+/**
+ * Why the exams can not be assigned and what could be changed so they can
+ */
+export type Diagnosis = { 
+/**
+ * Subjects without exam date or duration, they are not in any slot
+ */
+undatedSubjects: Array<EntityId>, slots: Array<SlotDiagnosis>, excludedClassrooms: Array<ExcludedClassroom>, 
+/**
+ * Smallest set of changes found that would let every exam be assigned, empty if nothing
+ * is missing
+ */
+relaxations: Array<Relaxation>, };
\ No newline at end of file
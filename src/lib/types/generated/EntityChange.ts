@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { EntityKind } from "./EntityKind";
+import type { FieldChange } from "./FieldChange";
+
+export type EntityChange = { "type": "added", kind: EntityKind, id: EntityId, label: string, } | { "type": "removed", kind: EntityKind, id: EntityId, label: string, } | { "type": "modified", kind: EntityKind, id: EntityId, label: string, changes: Array<FieldChange>, };
\ No newline at end of file
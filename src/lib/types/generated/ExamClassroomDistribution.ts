@@ -1,4 +1,21 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EntityId } from "./EntityId";
+import type { ExamineeSeat } from "./ExamineeSeat";
 
-export type ExamClassroomDistribution = { classroom: EntityId, examinees: Array<EntityId>, vigilants: Array<EntityId>, };
\ No newline at end of file
+export type ExamClassroomDistribution = { classroom: EntityId, examinees: Array<EntityId>, vigilants: Array<EntityId>, 
+/**
+ * The classroom is kept for the subject when the assignment is done again
+ */
+locked: boolean, 
+/**
+ * Examinees that stay in the classroom when the assignment is done again
+ */
+lockedExaminees: Array<EntityId>, 
+/**
+ * Vigilants that stay in the classroom when the assignment is done again
+ */
+lockedVigilants: Array<EntityId>, 
+/**
+ * Where the examinees sit, empty until the seats are assigned
+ */
+seats: Array<ExamineeSeat>, };
\ No newline at end of file
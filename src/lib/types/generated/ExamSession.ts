@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { EntityId } from "./EntityId";
+import type { SessionEnrollment } from "./SessionEnrollment";
+import type { SessionSchedule } from "./SessionSchedule";
+
+/**
+ * A call of the exams, like the ordinary or the extraordinary one, with its own timetable,
+ * classrooms, staff and assignment
+ */
+export type ExamSession = { id: EntityId, name: string, schedules: Array<SessionSchedule>, 
+/**
+ * Classrooms that can be used in this session
+ */
+classroomsIds: Array<EntityId>, 
+/**
+ * Vigilants that take part in this session
+ */
+vigilantsIds: Array<EntityId>, enrollments: Array<SessionEnrollment>, assignation: AllExamConfiguration | null, };
\ No newline at end of file
@@ -1,4 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Accommodations } from "./Accommodations";
 import type { EntityId } from "./EntityId";
 
-export type Examinee = { id: EntityId, nif: string, name: string, surenames: string, origin: string, court: number, academicCentreId: EntityId | null, subjectsIds: Array<EntityId>, };
\ No newline at end of file
+export type Examinee = { id: EntityId, nif: string, name: string, surenames: string, origin: string, 
+/**
+ * Court the examinee is examined by
+ */
+courtId: EntityId | null, academicCentreId: EntityId | null, subjectsIds: Array<EntityId>, accommodations: Accommodations, };
\ No newline at end of file
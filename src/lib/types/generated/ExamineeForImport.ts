@@ -1,12 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Accommodations } from "./Accommodations";
 
-export type ExamineeForImport = {
-	identifier: string;
-	nif: string | null;
-	name: string | null;
-	surenames: string | null;
-	origin: string | null;
-	court: number | null;
-	academicCentre: string | null;
-	subjects: Array<string>;
-};
+export type ExamineeForImport = { identifier: string, nif: string | null, name: string | null, surenames: string | null, origin: string | null, court: number | null, academicCentre: string | null, accommodations: Accommodations | null, subjects: Array<string>, };
\ No newline at end of file
@@ -1,11 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ExamineeImportColumn =
-	| 'subjectName'
-	| 'rowIdentifier'
-	| 'examineeNif'
-	| 'examineeName'
-	| 'examineeSurenames'
-	| 'examineeOrigin'
-	| 'examineeCourt'
-	| 'examineeAcademicCentre';
+export type ExamineeImportColumn = "subjectName" | "rowIdentifier" | "examineeNif" | "examineeName" | "examineeSurenames" | "examineeOrigin" | "examineeCourt" | "examineeAcademicCentre" | "examineeAccommodations";
\ No newline at end of file
@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ExamineeImportInvalidValueError = 'courtIsNotNumber';
+export type ExamineeImportInvalidValueError = "courtIsNotNumber" | "accommodationsNotUnderstood";
\ No newline at end of file
@@ -1,15 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ExamineeImportSettings = {
-	selectedSheet: string;
-	firstRowIsHeader: boolean;
-	groupRowsByColumn: number;
-	courtColumn: number;
-	subjectNameColumn: number;
-	surenamesColumn: number;
-	nameColumn: number;
-	nifColumn: number;
-	subjectKindColumn: number;
-	originColumn: number;
-	academicCentreColumn: number;
-};
+export type ExamineeImportSettings = { selectedSheet: string, firstRowIsHeader: boolean, groupRowsByColumn: number, courtColumn: number, subjectNameColumn: number, surenamesColumn: number, nameColumn: number, nifColumn: number, subjectKindColumn: number, originColumn: number, academicCentreColumn: number, accommodationsColumn?: number, };
\ No newline at end of file
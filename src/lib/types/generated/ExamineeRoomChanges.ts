@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * The classrooms an examinee goes through, in the order of the exams
+ */
+export type ExamineeRoomChanges = { examinee: EntityId, classrooms: Array<EntityId>, 
+/**
+ * Times the examinee has to change classroom between one exam and the next one
+ */
+changes: number, };
\ No newline at end of file
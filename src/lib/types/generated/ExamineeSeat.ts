@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type ExamineeSeat = { examinee: EntityId, 
+/**
+ * Number of the seat in the grid of the classroom
+ */
+seat: number, };
\ No newline at end of file
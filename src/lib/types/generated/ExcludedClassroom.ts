@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { ExclusionReason } from "./ExclusionReason";
+
+export type ExcludedClassroom = { classroom: EntityId, reason: ExclusionReason, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassroomFeature } from "./ClassroomFeature";
+import type { EntityId } from "./EntityId";
+
+export type ExclusionReason = { "type": "courtLocation", court: EntityId, } | { "type": "noCapacity" } | { "type": "missingFeatures", subject: EntityId, features: Array<ClassroomFeature>, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FileLock = { owner: string, host: string, pid: number, 
+/**
+ * Seconds since the unix epoch when the lock was taken or last refreshed
+ */
+timestamp: number, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileLock } from "./FileLock";
+import type { IoErrorDetails } from "./IoErrorDetails";
+
+export type FileLockError = { "type": "locked", lock: FileLock, } | { "type": "io", error: IoErrorDetails, };
\ No newline at end of file
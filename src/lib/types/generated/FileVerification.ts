@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SegmentFailure } from "./SegmentFailure";
+
+export type FileVerification = { 
+/**
+ * Number of segments that passed the authentication
+ */
+authenticatedSegments: number, 
+/**
+ * First problem found in the data stream, if any
+ */
+failure: SegmentFailure | null, 
+/**
+ * If the authenticated data could be read as a project
+ */
+readable: boolean, };
\ No newline at end of file
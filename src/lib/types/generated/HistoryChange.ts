@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { EntityKind } from "./EntityKind";
+import type { FieldChange } from "./FieldChange";
+import type { Ids } from "./Ids";
+import type { SlotChange } from "./SlotChange";
+
+export type HistoryChange = { "type": "added", kind: EntityKind, id: EntityId, after: unknown, } | { "type": "removed", kind: EntityKind, id: EntityId, before: unknown, } | { "type": "modified", kind: EntityKind, id: EntityId, changes: Array<FieldChange>, } | { "type": "assignation", before: unknown, after: unknown, } | { "type": "assignationSlots", session: EntityId | null, 
+/**
+ * Number of slots before and after, none when there was no assignment
+ */
+beforeLength: number | null, afterLength: number | null, slots: Array<SlotChange>, } | { "type": "ids", before: Ids, after: Ids, };
\ No newline at end of file
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HistoryChange } from "./HistoryChange";
+import type { HistoryOperation } from "./HistoryOperation";
+
+export type HistoryEntry = { 
+/**
+ * Seconds since the unix epoch
+ */
+timestamp: number, operator: string, operation: HistoryOperation, changes: Array<HistoryChange>, };
\ No newline at end of file
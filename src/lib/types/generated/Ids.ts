@@ -1,4 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EntityId } from "./EntityId";
 
-export type Ids = { examinees: EntityId, academicCentres: EntityId, subjects: EntityId, vigilants: EntityId, classrooms: EntityId, };
\ No newline at end of file
+export type Ids = { examinees: EntityId, academicCentres: EntityId, subjects: EntityId, vigilants: EntityId, classrooms: EntityId, examSessions: EntityId, courts: EntityId, buildings: EntityId, };
\ No newline at end of file
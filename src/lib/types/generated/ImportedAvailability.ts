@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvailabilityWindow } from "./AvailabilityWindow";
+
+/**
+ * Availability of a vigilant read from a sheet, the vigilant is found by its name
+ */
+export type ImportedAvailability = { name: string, surenames: string, availability: Array<AvailabilityWindow>, maxDuties: number | null, };
\ No newline at end of file
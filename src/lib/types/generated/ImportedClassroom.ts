@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportedClassroom = { code: string, locationCode: string, totalCapacity: number, examCapacity: number, 
+/**
+ * Name of the building
+ */
+building: string | null, floor: number | null, };
\ No newline at end of file
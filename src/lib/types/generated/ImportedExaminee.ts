@@ -1,12 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Accommodations } from "./Accommodations";
 
-export type ImportedExaminee = {
-	nif: string;
-	name: string;
-	surenames: string;
-	origin: string;
-	court: number;
-	subjects: Array<string>;
-	subjectsIds: Array<number>;
-	academicCentre: string | number | undefined;
-};
+export type ImportedExaminee = { nif: string, name: string, surenames: string, origin: string, court: number, subjects: Array<string>, academicCentre: string | null, accommodations: Accommodations, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { EntityKind } from "./EntityKind";
+
+export type IntegrityIssue = { "type": "danglingReference", ownerKind: EntityKind | null, ownerId: EntityId | null, kind: EntityKind, id: EntityId, } | { "type": "duplicateId", kind: EntityKind, id: EntityId, } | { "type": "duplicateCourtNumber", number: number, } | { "type": "counterBehind", kind: EntityKind, counter: EntityId, highest: EntityId, } | { "type": "unreadableValue", kind: EntityKind, id: EntityId, field: string, value: string, };
\ No newline at end of file
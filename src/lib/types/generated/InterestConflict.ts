@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { InterestKind } from "./InterestKind";
+
+/**
+ * A vigilant watching the classroom of an examinee it should not watch
+ */
+export type InterestConflict = { subject: EntityId, classroom: EntityId, vigilant: EntityId, examinee: EntityId, kind: InterestKind, };
\ No newline at end of file
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Relationships between a vigilant and an examinee that keep the vigilant from watching the
+ * classroom of the examinee
+ */
+export type InterestRules = { 
+/**
+ * The vigilant is from the academic centre of the examinee
+ */
+sameCentre: boolean, 
+/**
+ * The vigilant declared the examinee as a relative
+ */
+relatives: boolean, 
+/**
+ * The vigilant teaches, at the academic centre of the examinee, a subject the examinee takes
+ */
+subjectTeacher: boolean, };
\ No newline at end of file
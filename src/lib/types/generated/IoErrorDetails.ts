@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type IoErrorDetails = { 
+/**
+ * Kind of the error as reported by the standard library
+ */
+kind: string, message: string, 
+/**
+ * Error code of the operating system, if it was the one that failed
+ */
+osCode: number | null, };
\ No newline at end of file
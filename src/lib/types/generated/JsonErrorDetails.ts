@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonErrorCategory } from "./JsonErrorCategory";
+
+export type JsonErrorDetails = { category: JsonErrorCategory, 
+/**
+ * Path of the field that failed, like `examinees[3].subjectsIds`
+ */
+path: string, 
+/**
+ * Line of the data where the error happened, if it is tied to a position of the data
+ */
+line: number | null, column: number | null, 
+/**
+ * Position in the uncompressed data where the error happened, if it is tied to one
+ */
+offset: number | null, message: string, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { EntityKind } from "./EntityKind";
+import type { MergeSide } from "./MergeSide";
+
+export type MergeConflict = { "type": "field", kind: EntityKind, id: EntityId, field: string, base: unknown, ours: unknown, theirs: unknown, } | { "type": "modifiedAndDeleted", kind: EntityKind, id: EntityId, deletedBy: MergeSide, } | { "type": "assignation" };
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReadFromFileError } from "./ReadFromFileError";
+
+export type MergeFilesError = { "type": "ancestor", error: ReadFromFileError, } | { "type": "theirs", error: ReadFromFileError, };
\ No newline at end of file
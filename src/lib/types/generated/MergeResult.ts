@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppValues } from "./AppValues";
+import type { MergeConflict } from "./MergeConflict";
+import type { RemappedId } from "./RemappedId";
+
+export type MergeResult = { values: AppValues, 
+/**
+ * Changes that could not be merged automatically, in which our version was kept
+ */
+conflicts: Array<MergeConflict>, 
+/**
+ * Entities created by both sides with the same id, whose id was changed in their version
+ */
+remapped: Array<RemappedId>, };
\ No newline at end of file
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { AssignmentCost } from "./AssignmentCost";
+import type { AssignmentError } from "./AssignmentError";
+import type { Diagnosis } from "./Diagnosis";
+import type { SessionExam } from "./SessionExam";
+
+export type OptimisationResult = { assignation: AllExamConfiguration, 
+/**
+ * Problems of the exams that could not be assigned, these are not optimised
+ */
+errors: Array<AssignmentError>, diagnosis: Diagnosis | null, incompatibilitySession: Array<SessionExam>, 
+/**
+ * Cost of the assignment the search started from
+ */
+initialCost: AssignmentCost, cost: AssignmentCost, 
+/**
+ * The time budget ran out before the search settled, a better assignment could exist
+ */
+timedOut: boolean, };
\ No newline at end of file
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConflictResolution } from "./ConflictResolution";
+import type { InterestRules } from "./InterestRules";
+
+export type OptimisationSettings = { 
+/**
+ * Milliseconds the search can run for, at most a minute
+ */
+timeBudget: number, roomsWeight: number, overloadWeight: number, roomChangesWeight: number, 
+/**
+ * Highest percentage of the examinees of a classroom that should come from the same
+ * academic centre, 100 means there is no limit
+ */
+maxCentreShare: number, centreWeight: number, 
+/**
+ * How the assignment the search starts from places the examinees with overlapping exams
+ */
+conflicts: ConflictResolution, 
+/**
+ * Which vigilants can not watch which examinees, no move breaks them
+ */
+interest: InterestRules, 
+/**
+ * Makes the search repeatable, a random one is used if there is none
+ */
+seed?: number, };
\ No newline at end of file
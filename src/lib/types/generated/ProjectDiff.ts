@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AssignationChange } from "./AssignationChange";
+import type { EntityChange } from "./EntityChange";
+import type { SessionAssignationChanges } from "./SessionAssignationChanges";
+
+export type ProjectDiff = { 
+/**
+ * Changes of the entities, the assignments of the sessions are in `sessions`
+ */
+entities: Array<EntityChange>, assignation: Array<AssignationChange>, 
+/**
+ * Sessions whose assignment changed
+ */
+sessions: Array<SessionAssignationChanges>, 
+/**
+ * The changes written for people
+ */
+report: string, };
\ No newline at end of file
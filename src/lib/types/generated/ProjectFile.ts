@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A project file together with the password needed to open it
+ */
+export type ProjectFile = { file: string, password: string, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppValues } from "./AppValues";
+import type { ProjectFile } from "./ProjectFile";
+
+export type ProjectSource = { "type": "file" } & ProjectFile | { "type": "values", values: AppValues, };
\ No newline at end of file
@@ -1,6 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileLockError } from "./FileLockError";
+import type { IntegrityIssue } from "./IntegrityIssue";
+import type { IoErrorDetails } from "./IoErrorDetails";
+import type { JsonErrorDetails } from "./JsonErrorDetails";
 import type { ReadFromFileInvalidError } from "./ReadFromFileInvalidError";
 import type { ReadFromFileOpenFileError } from "./ReadFromFileOpenFileError";
 import type { ReadFromFileReadError } from "./ReadFromFileReadError";
 
-export type ReadFromFileError = { "type": "reading", part: ReadFromFileReadError, } | { "type": "invalid", part: ReadFromFileInvalidError, } | { "type": "keyDerivation" } | { "type": "createCipher" } | { "type": "openFile", case: ReadFromFileOpenFileError, } | { "type": "serialization" } | { "type": "passwordCheck" };
\ No newline at end of file
+export type ReadFromFileError = { "type": "reading", part: ReadFromFileReadError, offset: number, error: IoErrorDetails, } | { "type": "invalid", part: ReadFromFileInvalidError, } | { "type": "keyDerivation", message: string, } | { "type": "createCipher" } | { "type": "openFile", case: ReadFromFileOpenFileError, error: IoErrorDetails, } | { "type": "serialization", error: JsonErrorDetails, } | { "type": "passwordCheck", error: IoErrorDetails | null, } | { "type": "lock", case: FileLockError, } | { "type": "integrity", issues: Array<IntegrityIssue>, };
\ No newline at end of file
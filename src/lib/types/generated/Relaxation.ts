@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassroomFeature } from "./ClassroomFeature";
+import type { EntityId } from "./EntityId";
+
+export type Relaxation = { "type": "setExamDate", subject: EntityId, } | { "type": "dropRequiredFeatures", subject: EntityId, features: Array<ClassroomFeature>, } | { "type": "useCourtClassrooms", subjects: Array<EntityId>, classrooms: Array<EntityId>, } | { "type": "addSeats", subjects: Array<EntityId>, seats: number, } | { "type": "addAccessibleSeats", subject: EntityId, seats: number, } | { "type": "addSeparateRooms", subject: EntityId, rooms: number, } | { "type": "extendAvailability", subjects: Array<EntityId>, vigilants: Array<EntityId>, } | { "type": "addVigilants", subjects: Array<EntityId>, vigilants: number, } | { "type": "addSpecialist", subject: EntityId, } | { "type": "raiseMaxDuties", subjects: Array<EntityId>, vigilants: Array<EntityId>, } | { "type": "addVigilantWithoutConflicts", subject: EntityId, examinees: Array<EntityId>, } | { "type": "useBiggerSharedRoom", subjects: Array<EntityId>, seats: number, };
\ No newline at end of file
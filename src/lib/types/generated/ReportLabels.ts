@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityKind } from "./EntityKind";
+
+/**
+ * Texts the report is written with, in the language of the user. In the templates `{subject}`,
+ * `{vigilant}`, `{item}`, `{from}` and `{to}` are replaced with the labels of the entities
+ */
+export type ReportLabels = { 
+/**
+ * Title of the changes of each kind of entity
+ */
+kinds: { [key in EntityKind]?: string }, 
+/**
+ * Title of the changes of the assignment
+ */
+assignment: string, 
+/**
+ * Title of the changes of the assignment of a session, with `{session}`
+ */
+sessionAssignment: string, 
+/**
+ * Stands for a missing classroom
+ */
+nowhere: string, 
+/**
+ * An exam, with `{subject}`
+ */
+exam: string, 
+/**
+ * A specialist of an exam, with `{vigilant}`
+ */
+specialist: string, 
+/**
+ * Someone moved between classrooms, with `{item}`, `{from}` and `{to}`
+ */
+moved: string, };
\ No newline at end of file
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppValues } from "./AppValues";
+import type { FileVerification } from "./FileVerification";
+
+export type SalvagedValues = { values: AppValues, verification: FileVerification, 
+/**
+ * Entities found in the file that could not be recovered
+ */
+discardedEntities: number, };
\ No newline at end of file
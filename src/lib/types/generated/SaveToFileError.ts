@@ -1,5 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileLockError } from "./FileLockError";
+import type { IntegrityIssue } from "./IntegrityIssue";
+import type { IoErrorDetails } from "./IoErrorDetails";
+import type { JsonErrorDetails } from "./JsonErrorDetails";
 import type { SaveToFileOpenFileError } from "./SaveToFileOpenFileError";
 import type { SaveToFileWriteError } from "./SaveToFileWriteError";
 
-export type SaveToFileError = { "type": "writing", part: SaveToFileWriteError, } | { "type": "keyDerivation" } | { "type": "createCipher" } | { "type": "openFile", case: SaveToFileOpenFileError, } | { "type": "serialization" } | { "type": "passwordCheck" };
\ No newline at end of file
+export type SaveToFileError = { "type": "writing", part: SaveToFileWriteError, error: IoErrorDetails, } | { "type": "keyDerivation", message: string, } | { "type": "createCipher" } | { "type": "openFile", case: SaveToFileOpenFileError, error: IoErrorDetails, } | { "type": "serialization", error: JsonErrorDetails, } | { "type": "passwordCheck", error: IoErrorDetails | null, } | { "type": "lock", case: FileLockError, } | { "type": "integrity", issues: Array<IntegrityIssue>, } | { "type": "modifiedExternally", copyPath: string, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * Two exams held at the same time that an examinee is enrolled in
+ */
+export type ScheduleConflict = { examinee: EntityId, 
+/**
+ * The exam that starts first
+ */
+first: EntityId, second: EntityId, };
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SeatSpacing } from "./SeatSpacing";
+
+/**
+ * Seats of a classroom in rows, numbered from 1 row by row starting at the front
+ */
+export type SeatGrid = { rows: number, seatsPerRow: number, 
+/**
+ * Seats that can not be used, like broken ones or the ones behind a column
+ */
+unusableSeats: Array<number>, 
+/**
+ * Seats left free between examinees as long as there is room for it
+ */
+spacing: SeatSpacing, };
\ No newline at end of file
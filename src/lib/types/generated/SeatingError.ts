@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type SeatingError = { "type": "assignment-not-done" } | { "type": "unknown-session", session: EntityId, } | { "type": "not-enough-usable-seats", subject: EntityId, classroom: EntityId, seats: number, examinees: number, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AllExamConfiguration } from "./AllExamConfiguration";
+import type { SeatingError } from "./SeatingError";
+
+export type SeatingResult = { assignation: AllExamConfiguration, errors: Array<SeatingError>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * In which order the examinees of a classroom take its seats
+ */
+export type SeatingStrategy = { "type": "alphabetical" } | { "type": "byCentreInterleaved" } | { "type": "random", 
+/**
+ * Makes the order repeatable, a random one is used if there is none
+ */
+seed?: number, };
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SegmentFailure = { "type": "missingNonce" } | { "type": "reading", segment: number, offset: number, } | { "type": "authentication", segment: number, offset: number, } | { "type": "truncated", segment: number, offset: number, };
\ No newline at end of file
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AssignationChange } from "./AssignationChange";
+import type { EntityId } from "./EntityId";
+
+/**
+ * Changes of the assignment of one of the sessions of the project
+ */
+export type SessionAssignationChanges = { session: EntityId, changes: Array<AssignationChange>, };
\ No newline at end of file
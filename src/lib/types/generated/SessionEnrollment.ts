@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * The subjects an examinee takes in a session
+ */
+export type SessionEnrollment = { examinee: EntityId, subjectsIds: Array<EntityId>, };
\ No newline at end of file
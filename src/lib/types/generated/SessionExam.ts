@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * An exam an examinee takes in the incompatibility session instead of with the rest
+ */
+export type SessionExam = { examinee: EntityId, subject: EntityId, };
\ No newline at end of file
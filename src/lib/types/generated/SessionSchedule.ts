@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+/**
+ * When the exam of a subject is held in a session
+ */
+export type SessionSchedule = { subject: EntityId, examDate: string | null, examDuration: string | null, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A slot of an assignment as it was before and after, null where there was none
+ */
+export type SlotChange = { index: number, before: unknown, after: unknown, };
\ No newline at end of file
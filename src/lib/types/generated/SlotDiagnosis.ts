@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+import type { SubjectDiagnosis } from "./SubjectDiagnosis";
+
+/**
+ * Exams held at the same time, they compete for the classrooms and vigilants
+ */
+export type SlotDiagnosis = { start: string, end: string, subjects: Array<SubjectDiagnosis>, 
+/**
+ * Seats the examinees need, one for every exam they take in the slot
+ */
+seatsNeeded: number, 
+/**
+ * Total capacity of the classrooms some exam of the slot can use
+ */
+seats: number, seatShortfall: number, vigilantsNeeded: number, vigilants: number, vigilantShortfall: number, 
+/**
+ * Vigilants left out because they are not available for the whole slot
+ */
+unavailableVigilants: Array<EntityId>, 
+/**
+ * Vigilants left out because they did their maximum of duties in the slots before
+ */
+exhaustedVigilants: Array<EntityId>, 
+/**
+ * Examinees enrolled in overlapping exams of the slot, they take them in the shared room
+ * when there is one
+ */
+sharingExaminees: number, 
+/**
+ * Seats the shared room is missing for them, all of them if it can not be used
+ */
+sharedRoomShortfall: number, };
\ No newline at end of file
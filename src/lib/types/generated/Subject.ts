@@ -1,5 +1,14 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassroomFeature } from "./ClassroomFeature";
 import type { EntityId } from "./EntityId";
 import type { SubjectKind } from "./SubjectKind";
 
-export type Subject = { id: EntityId, name: string, kind: SubjectKind, examDate: string | null, examDuration: string | null, };
\ No newline at end of file
+export type Subject = { id: EntityId, name: string, kind: SubjectKind, 
+/**
+ * Start of the exam, with the offset of the timezone it was set in
+ */
+examDate: string | null, examDuration: string | null, 
+/**
+ * Features the classrooms of the exam must have
+ */
+requiredFeatures: Array<ClassroomFeature>, };
\ No newline at end of file
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type SubjectDiagnosis = { subject: EntityId, examinees: number, 
+/**
+ * Total capacity of the classrooms the exam can use
+ */
+seats: number, seatShortfall: number, accessibleSeatShortfall: number, separateRoomShortfall: number, 
+/**
+ * Other subjects of the slot that can use some of the same classrooms
+ */
+competingSubjects: Array<EntityId>, 
+/**
+ * The exam collides with others and no vigilant can be its specialist
+ */
+missingSpecialist: boolean, 
+/**
+ * Examinees every vigilant of the slot has a conflict of interest with
+ */
+conflictedExaminees: Array<EntityId>, };
\ No newline at end of file
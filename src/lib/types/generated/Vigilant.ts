@@ -1,5 +1,22 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvailabilityWindow } from "./AvailabilityWindow";
 import type { EntityId } from "./EntityId";
 import type { VigilantRole } from "./VigilantRole";
 
-export type Vigilant = { id: EntityId, name: string, surenames: string, role: VigilantRole, specialtiesIds: Array<EntityId>, academicCentreId: EntityId | null, mainCourt: number, };
\ No newline at end of file
+export type Vigilant = { id: EntityId, name: string, surenames: string, role: VigilantRole, specialtiesIds: Array<EntityId>, academicCentreId: EntityId | null, 
+/**
+ * Court the vigilant belongs to
+ */
+mainCourtId: EntityId | null, 
+/**
+ * When the vigilant can attend exams. Without any window the vigilant is always available
+ */
+availability: Array<AvailabilityWindow>, 
+/**
+ * Most exam slots the vigilant can be assigned to
+ */
+maxDuties: number | null, 
+/**
+ * NIFs of the examinees the vigilant declared as relatives
+ */
+relativesNifs: Array<string>, };
\ No newline at end of file
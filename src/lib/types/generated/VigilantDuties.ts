@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityId } from "./EntityId";
+
+export type VigilantDuties = { vigilant: EntityId, 
+/**
+ * Exam slots in which the vigilant watches a classroom or is a specialist
+ */
+duties: number, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VigilantDuties } from "./VigilantDuties";
+
+/**
+ * Duties of the vigilants that watch classrooms, presidents and secretaries do not
+ */
+export type WorkloadReport = { min: number, max: number, mean: number, 
+/**
+ * From the vigilant with the most duties to the one with the least
+ */
+duties: Array<VigilantDuties>, };
\ No newline at end of file